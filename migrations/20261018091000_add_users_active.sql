@@ -0,0 +1,3 @@
+-- Permite deshabilitar usuarios sin borrarlos
+ALTER TABLE users
+    ADD COLUMN active BOOLEAN NOT NULL DEFAULT TRUE AFTER role;
@@ -0,0 +1,11 @@
+-- Registro de intentos de login para throttling, bloqueo de cuentas y auditoría
+CREATE TABLE IF NOT EXISTS login_attempts (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    username VARCHAR(255) NOT NULL,
+    ip_address VARCHAR(45) NOT NULL,
+    result ENUM('Success', 'Failure', 'Locked') NOT NULL,
+    cleared BOOLEAN NOT NULL DEFAULT FALSE,
+    attempted_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_login_attempts_username (username, attempted_at),
+    INDEX idx_login_attempts_ip (ip_address, attempted_at)
+) ENGINE=InnoDB;
@@ -0,0 +1,16 @@
+-- Segundo factor TOTP (RFC 6238) y códigos de recuperación
+ALTER TABLE users
+    ADD COLUMN mfa_secret VARCHAR(64) DEFAULT NULL AFTER active,
+    ADD COLUMN mfa_enabled BOOLEAN NOT NULL DEFAULT FALSE AFTER mfa_secret,
+    ADD COLUMN mfa_last_step BIGINT DEFAULT NULL AFTER mfa_enabled;
+
+CREATE TABLE IF NOT EXISTS mfa_recovery_codes (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    user_id INT NOT NULL,
+    code_hash CHAR(64) NOT NULL,
+    used_at DATETIME DEFAULT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_mfa_recovery_codes_user (user_id),
+    CONSTRAINT fk_mfa_recovery_codes_user FOREIGN KEY (user_id) REFERENCES users(id)
+        ON DELETE CASCADE
+) ENGINE=InnoDB;
@@ -0,0 +1,19 @@
+-- API keys para dispositivos e integraciones (kiosco de check-in, scripts de sincronización).
+-- Solo se guarda el hash SHA-256 de la key; el prefijo permite buscarla sin recorrer la tabla
+CREATE TABLE IF NOT EXISTS api_keys (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    prefix VARCHAR(16) NOT NULL UNIQUE,
+    key_hash CHAR(64) NOT NULL,
+    -- Scopes separados por coma, ej. "attendance:write,clients:read"
+    scopes VARCHAR(1024) NOT NULL,
+    -- IPs o rangos CIDR separados por coma; NULL = cualquier IP
+    ip_allowlist VARCHAR(1024) NULL,
+    expires_at DATETIME NULL,
+    last_used_at DATETIME NULL,
+    last_used_ip VARCHAR(45) NULL,
+    created_by INT NULL,
+    revoked_at DATETIME NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+) ENGINE=InnoDB;
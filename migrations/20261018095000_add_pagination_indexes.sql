@@ -0,0 +1,6 @@
+-- Índices para ordenar y paginar por cursor (columna de orden + id como desempate)
+CREATE INDEX idx_clients_name_id ON clients (name, id);
+CREATE INDEX idx_clients_last_name_id ON clients (last_name, id);
+CREATE INDEX idx_clients_created_at_id ON clients (created_at, id);
+CREATE INDEX idx_subscriptions_expires_at_id ON subscriptions (expires_at, id);
+CREATE INDEX idx_subscriptions_created_at_id ON subscriptions (created_at, id);
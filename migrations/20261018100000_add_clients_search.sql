@@ -0,0 +1,7 @@
+-- Documento del cliente y columna normalizada para /clients/search (minúsculas, sin acentos,
+-- con teléfono y documento). La mantiene la aplicación en cada alta/edición; para los clientes
+-- existentes queda vacía y la completa `backfill_search_text` al iniciar
+ALTER TABLE clients
+    ADD COLUMN document_number VARCHAR(20) DEFAULT NULL AFTER phone,
+    ADD COLUMN search_text VARCHAR(1024) NOT NULL DEFAULT '' AFTER document_number,
+    ADD UNIQUE INDEX idx_clients_document_number (document_number);
@@ -0,0 +1,4 @@
+-- Precios exactos: FLOAT -> DECIMAL(12,2) (los valores existentes se redondean a centavos) y moneda ISO 4217
+ALTER TABLE memberships
+    MODIFY price DECIMAL(12,2) NOT NULL,
+    ADD COLUMN currency CHAR(3) NOT NULL DEFAULT 'ARS' AFTER price;
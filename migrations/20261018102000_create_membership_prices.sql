@@ -0,0 +1,24 @@
+-- Historial de precios de cada plan: el precio vigente en un momento es el último con effective_from <= ese momento.
+-- Las filas con effective_from futuro son aumentos programados. Las suscripciones guardan el precio cobrado
+CREATE TABLE IF NOT EXISTS membership_prices (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    membership_id INT NOT NULL,
+    price DECIMAL(12,2) NOT NULL,
+    currency CHAR(3) NOT NULL,
+    effective_from DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_membership_prices_effective (membership_id, effective_from),
+    CONSTRAINT fk_membership_prices_membership FOREIGN KEY (membership_id) REFERENCES memberships(id)
+        ON DELETE CASCADE
+) ENGINE=InnoDB;
+
+-- El precio actual de cada plan pasa a ser la primera entrada de su historial
+INSERT INTO membership_prices (membership_id, price, currency, effective_from)
+SELECT id, price, currency, created_at FROM memberships;
+
+ALTER TABLE subscriptions
+    ADD COLUMN membership_id INT DEFAULT NULL AFTER discipline_id,
+    ADD COLUMN price DECIMAL(12,2) DEFAULT NULL AFTER membership_id,
+    ADD COLUMN currency CHAR(3) DEFAULT NULL AFTER price,
+    ADD CONSTRAINT fk_subscriptions_membership FOREIGN KEY (membership_id) REFERENCES memberships(id)
+        ON DELETE SET NULL;
@@ -0,0 +1,32 @@
+-- Una fila por compra: plan, precio cobrado, clases otorgadas y vigencia propia.
+-- El saldo de la suscripción (remaining_classes / expires_at) se recalcula a partir de sus períodos vigentes
+CREATE TABLE IF NOT EXISTS subscription_periods (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    subscription_id INT NOT NULL,
+    membership_id INT DEFAULT NULL,
+    price DECIMAL(12,2) DEFAULT NULL,
+    currency CHAR(3) DEFAULT NULL,
+    classes_granted INT NOT NULL,
+    classes_used INT NOT NULL DEFAULT 0,
+    starts_at DATETIME NOT NULL,
+    expires_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_subscription_periods_validity (subscription_id, expires_at),
+    CONSTRAINT fk_subscription_periods_subscription FOREIGN KEY (subscription_id) REFERENCES subscriptions(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_subscription_periods_membership FOREIGN KEY (membership_id) REFERENCES memberships(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
+
+-- Las suscripciones existentes pasan a tener un único período con su saldo actual
+-- (el plan y el precio solo se conocen si se compraron después de 20261018102000)
+INSERT INTO subscription_periods
+    (subscription_id, membership_id, price, currency, classes_granted, starts_at, expires_at)
+SELECT id, membership_id, price, currency, COALESCE(remaining_classes, 0), created_at, COALESCE(expires_at, created_at)
+FROM subscriptions;
+
+-- Cada asistencia descuenta de un período concreto
+ALTER TABLE class_attendance
+    ADD COLUMN period_id INT DEFAULT NULL AFTER subscription_id,
+    ADD CONSTRAINT fk_class_attendance_period FOREIGN KEY (period_id) REFERENCES subscription_periods(id)
+        ON DELETE SET NULL;
@@ -0,0 +1,45 @@
+-- Cargos: lo que debe cada cliente. Cada compra de un plan genera un cargo por el precio cobrado (period_id);
+-- también se pueden cargar conceptos sueltos (matrícula, clase suelta, etc.)
+CREATE TABLE IF NOT EXISTS charges (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    client_id INT NOT NULL,
+    period_id INT DEFAULT NULL UNIQUE,
+    description VARCHAR(255) NOT NULL,
+    amount DECIMAL(12,2) NOT NULL,
+    currency CHAR(3) NOT NULL,
+    created_by INT DEFAULT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_charges_client (client_id, created_at),
+    CONSTRAINT fk_charges_client FOREIGN KEY (client_id) REFERENCES clients(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_charges_period FOREIGN KEY (period_id) REFERENCES subscription_periods(id)
+        ON DELETE SET NULL,
+    CONSTRAINT fk_charges_user FOREIGN KEY (created_by) REFERENCES users(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
+
+-- Pagos recibidos contra un cargo (puede haber varios parciales). Las devoluciones son filas con
+-- importe negativo que apuntan al pago original (refund_of)
+CREATE TABLE IF NOT EXISTS payments (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    charge_id INT NOT NULL,
+    client_id INT NOT NULL,
+    amount DECIMAL(12,2) NOT NULL,
+    currency CHAR(3) NOT NULL,
+    method ENUM('cash', 'debit', 'credit', 'transfer', 'mercado_pago') NOT NULL,
+    reference VARCHAR(100) DEFAULT NULL,
+    notes VARCHAR(255) DEFAULT NULL,
+    refund_of INT DEFAULT NULL,
+    paid_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    recorded_by INT DEFAULT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_payments_paid_at (paid_at),
+    INDEX idx_payments_client (client_id, paid_at),
+    CONSTRAINT fk_payments_charge FOREIGN KEY (charge_id) REFERENCES charges(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_payments_client FOREIGN KEY (client_id) REFERENCES clients(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_payments_refund FOREIGN KEY (refund_of) REFERENCES payments(id),
+    CONSTRAINT fk_payments_user FOREIGN KEY (recorded_by) REFERENCES users(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
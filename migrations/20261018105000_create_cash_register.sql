@@ -0,0 +1,43 @@
+-- Caja de recepción: una sesión por turno, abierta con un fondo inicial y cerrada con el efectivo contado.
+-- Solo puede haber una sesión abierta (open_marker es 1 mientras está abierta y NULL al cerrarla)
+CREATE TABLE IF NOT EXISTS cash_register_sessions (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    currency CHAR(3) NOT NULL,
+    opening_float DECIMAL(12,2) NOT NULL,
+    opened_by INT DEFAULT NULL,
+    opened_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    closed_by INT DEFAULT NULL,
+    closed_at DATETIME DEFAULT NULL,
+    counted_cash DECIMAL(12,2) DEFAULT NULL,
+    expected_cash DECIMAL(12,2) DEFAULT NULL,
+    discrepancy DECIMAL(12,2) DEFAULT NULL,
+    notes VARCHAR(255) DEFAULT NULL,
+    open_marker TINYINT AS (IF(closed_at IS NULL, 1, NULL)) STORED UNIQUE,
+    INDEX idx_cash_register_sessions_opened_at (opened_at),
+    CONSTRAINT fk_cash_sessions_opened_by FOREIGN KEY (opened_by) REFERENCES users(id)
+        ON DELETE SET NULL,
+    CONSTRAINT fk_cash_sessions_closed_by FOREIGN KEY (closed_by) REFERENCES users(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
+
+-- Movimientos de la sesión por medio de pago: cobros (pagos registrados con la caja abierta), devoluciones,
+-- otros ingresos y retiros de caja chica. Egresos con importe negativo
+CREATE TABLE IF NOT EXISTS cash_movements (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    session_id INT NOT NULL,
+    kind ENUM('sale', 'refund', 'income', 'withdrawal') NOT NULL,
+    method ENUM('cash', 'debit', 'credit', 'transfer', 'mercado_pago') NOT NULL,
+    amount DECIMAL(12,2) NOT NULL,
+    currency CHAR(3) NOT NULL,
+    payment_id INT DEFAULT NULL UNIQUE,
+    description VARCHAR(255) DEFAULT NULL,
+    recorded_by INT DEFAULT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_cash_movements_session (session_id, method),
+    CONSTRAINT fk_cash_movements_session FOREIGN KEY (session_id) REFERENCES cash_register_sessions(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_cash_movements_payment FOREIGN KEY (payment_id) REFERENCES payments(id)
+        ON DELETE SET NULL,
+    CONSTRAINT fk_cash_movements_user FOREIGN KEY (recorded_by) REFERENCES users(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
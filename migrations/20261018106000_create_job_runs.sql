@@ -0,0 +1,15 @@
+-- Historial de ejecuciones de las tareas programadas, automáticas (schedule) o lanzadas a mano por un Admin (manual)
+CREATE TABLE IF NOT EXISTS job_runs (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    job VARCHAR(64) NOT NULL,
+    source ENUM('schedule', 'manual') NOT NULL,
+    status ENUM('running', 'succeeded', 'failed') NOT NULL DEFAULT 'running',
+    affected_rows INT DEFAULT NULL,
+    error VARCHAR(1000) DEFAULT NULL,
+    triggered_by INT DEFAULT NULL,
+    started_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at DATETIME DEFAULT NULL,
+    INDEX idx_job_runs_job_started (job, started_at),
+    CONSTRAINT fk_job_runs_user FOREIGN KEY (triggered_by) REFERENCES users(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
@@ -0,0 +1,31 @@
+-- Datos de contacto e idioma para los avisos a clientes
+ALTER TABLE clients
+    ADD COLUMN email VARCHAR(255) DEFAULT NULL AFTER document_number,
+    ADD COLUMN language CHAR(2) NOT NULL DEFAULT 'es' AFTER email;
+
+-- Outbox de avisos: cada mensaje se guarda ya renderizado y se envía (y reintenta) en segundo plano.
+-- dedupe_key evita repetir el aviso de la misma suscripción en el mismo ciclo de vencimiento
+CREATE TABLE IF NOT EXISTS notification_outbox (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    client_id INT NOT NULL,
+    subscription_id INT DEFAULT NULL,
+    kind ENUM('expiring', 'low_classes') NOT NULL,
+    channel ENUM('email', 'webhook', 'log') NOT NULL,
+    recipient VARCHAR(255) NOT NULL,
+    language CHAR(2) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    status ENUM('pending', 'sent', 'failed') NOT NULL DEFAULT 'pending',
+    attempts INT NOT NULL DEFAULT 0,
+    last_error VARCHAR(1000) DEFAULT NULL,
+    next_attempt_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sent_at DATETIME DEFAULT NULL,
+    dedupe_key VARCHAR(191) NOT NULL UNIQUE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_notification_outbox_pending (status, next_attempt_at),
+    INDEX idx_notification_outbox_client (client_id, created_at),
+    CONSTRAINT fk_notification_outbox_client FOREIGN KEY (client_id) REFERENCES clients(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_notification_outbox_subscription FOREIGN KEY (subscription_id) REFERENCES subscriptions(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
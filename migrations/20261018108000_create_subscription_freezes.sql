@@ -0,0 +1,32 @@
+-- Límites de congelamiento por plan, por cada compra: cantidad de congelamientos y días en total (0 = no se permite)
+ALTER TABLE memberships
+    ADD COLUMN max_freezes INT NOT NULL DEFAULT 0 AFTER duration_days,
+    ADD COLUMN max_freeze_days INT NOT NULL DEFAULT 0 AFTER max_freezes;
+
+-- Congelamientos (lesión, viaje): entre starts_on y ends_on no se registran asistencias y al terminar
+-- el vencimiento se corre los días efectivamente congelados (frozen_days).
+-- Solo puede haber uno abierto por suscripción (open_marker es el id de la suscripción hasta que termina)
+CREATE TABLE IF NOT EXISTS subscription_freezes (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    subscription_id INT NOT NULL,
+    period_id INT DEFAULT NULL,
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    reason VARCHAR(255) NOT NULL,
+    approved_by INT DEFAULT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ended_at DATETIME DEFAULT NULL,
+    ended_by INT DEFAULT NULL,
+    frozen_days INT DEFAULT NULL,
+    open_marker INT AS (IF(ended_at IS NULL, subscription_id, NULL)) STORED UNIQUE,
+    INDEX idx_subscription_freezes_subscription (subscription_id, starts_on),
+    INDEX idx_subscription_freezes_pending (ended_at, ends_on),
+    CONSTRAINT fk_subscription_freezes_subscription FOREIGN KEY (subscription_id) REFERENCES subscriptions(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_subscription_freezes_period FOREIGN KEY (period_id) REFERENCES subscription_periods(id)
+        ON DELETE SET NULL,
+    CONSTRAINT fk_subscription_freezes_approved_by FOREIGN KEY (approved_by) REFERENCES users(id)
+        ON DELETE SET NULL,
+    CONSTRAINT fk_subscription_freezes_ended_by FOREIGN KEY (ended_by) REFERENCES users(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
@@ -0,0 +1,36 @@
+-- Días en que el gimnasio cierra (feriados, mantenimiento), para todas las disciplinas o para una sola.
+-- Los recurrentes se repiten todos los años en las mismas fechas
+CREATE TABLE IF NOT EXISTS closures (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    starts_on DATE NOT NULL,
+    ends_on DATE NOT NULL,
+    recurring BOOLEAN NOT NULL DEFAULT FALSE,
+    discipline_id INT DEFAULT NULL,
+    created_by INT DEFAULT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_closures_dates (starts_on, ends_on),
+    CONSTRAINT fk_closures_discipline FOREIGN KEY (discipline_id) REFERENCES disciplines(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_closures_created_by FOREIGN KEY (created_by) REFERENCES users(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
+
+-- Días que cada cierre (en cada año, para los recurrentes) le agregó a cada período.
+-- La clave única hace que aplicar un cierre dos veces no extienda dos veces; al borrarlo se descuentan
+CREATE TABLE IF NOT EXISTS closure_extensions (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    closure_id INT NOT NULL,
+    occurs_on DATE NOT NULL,
+    period_id INT NOT NULL,
+    subscription_id INT NOT NULL,
+    days INT NOT NULL,
+    applied_at DATETIME DEFAULT NULL,
+    UNIQUE KEY uq_closure_extensions_period (closure_id, occurs_on, period_id),
+    CONSTRAINT fk_closure_extensions_closure FOREIGN KEY (closure_id) REFERENCES closures(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_closure_extensions_period FOREIGN KEY (period_id) REFERENCES subscription_periods(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_closure_extensions_subscription FOREIGN KEY (subscription_id) REFERENCES subscriptions(id)
+        ON DELETE CASCADE
+) ENGINE=InnoDB;
@@ -0,0 +1,50 @@
+-- Grilla semanal: cada plantilla es una clase fija de una disciplina (día de la semana ISO, 1 = lunes, y hora UTC)
+CREATE TABLE IF NOT EXISTS class_templates (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    discipline_id INT NOT NULL,
+    weekday TINYINT NOT NULL,
+    starts_at TIME NOT NULL,
+    duration_minutes INT NOT NULL,
+    room VARCHAR(100) DEFAULT NULL,
+    trainer_id INT DEFAULT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    INDEX idx_class_templates_discipline (discipline_id, weekday),
+    CONSTRAINT fk_class_templates_discipline FOREIGN KEY (discipline_id) REFERENCES disciplines(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_class_templates_trainer FOREIGN KEY (trainer_id) REFERENCES users(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
+
+-- Clases concretas generadas desde las plantillas (una por plantilla y fecha, scheduled_on) o cargadas sueltas.
+-- Una clase movida conserva scheduled_on y guarda el horario original en original_starts_at
+CREATE TABLE IF NOT EXISTS class_sessions (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    template_id INT DEFAULT NULL,
+    discipline_id INT NOT NULL,
+    scheduled_on DATE NOT NULL,
+    starts_at DATETIME NOT NULL,
+    duration_minutes INT NOT NULL,
+    room VARCHAR(100) DEFAULT NULL,
+    trainer_id INT DEFAULT NULL,
+    status ENUM('scheduled', 'cancelled') NOT NULL DEFAULT 'scheduled',
+    original_starts_at DATETIME DEFAULT NULL,
+    notes VARCHAR(255) DEFAULT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_class_sessions_template_date (template_id, scheduled_on),
+    INDEX idx_class_sessions_starts_at (starts_at),
+    CONSTRAINT fk_class_sessions_template FOREIGN KEY (template_id) REFERENCES class_templates(id)
+        ON DELETE SET NULL,
+    CONSTRAINT fk_class_sessions_discipline FOREIGN KEY (discipline_id) REFERENCES disciplines(id)
+        ON DELETE CASCADE,
+    CONSTRAINT fk_class_sessions_trainer FOREIGN KEY (trainer_id) REFERENCES users(id)
+        ON DELETE SET NULL
+) ENGINE=InnoDB;
+
+-- La asistencia puede indicar a qué clase vino
+ALTER TABLE class_attendance
+    ADD COLUMN session_id INT DEFAULT NULL AFTER period_id,
+    ADD CONSTRAINT fk_class_attendance_session FOREIGN KEY (session_id) REFERENCES class_sessions(id)
+        ON DELETE SET NULL;
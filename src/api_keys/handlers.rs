@@ -0,0 +1,104 @@
+use sqlx::mysql::MySqlQueryResult;
+use sqlx::{self, MySqlPool};
+use crate::auth::handlers::hash_token;
+use super::models::api_keys::ApiKey;
+use super::models::requests::NewApiKeyRequest;
+
+
+pub async fn create_api_key(
+    pool: &MySqlPool,
+    req: &NewApiKeyRequest,
+    prefix: &str,
+    key: &str,
+    created_by: i32,
+) -> Result<u64, sqlx::Error> {
+    let ip_allowlist = (!req.ip_allowlist.is_empty()).then(|| req.ip_allowlist.join(","));
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO api_keys (name, prefix, key_hash, scopes, ip_allowlist, expires_at, created_by)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(&req.name)
+        .bind(prefix)
+        .bind(hash_token(key))
+        .bind(req.scopes.join(","))
+        .bind(ip_allowlist)
+        .bind(req.expires_at)
+        .bind(created_by)
+        .execute(pool)
+        .await?;
+
+    Ok(result.last_insert_id())
+}
+
+pub async fn obtain_api_keys(
+    pool: &MySqlPool,
+) -> Result<Vec<ApiKey>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM api_keys ORDER BY created_at DESC
+        "#)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(ApiKey::from_row).collect())
+}
+
+pub async fn obtain_api_key_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<ApiKey>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT * FROM api_keys WHERE id = ?
+        "#)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| ApiKey::from_row(&row)))
+}
+
+pub async fn obtain_api_key_by_prefix(
+    pool: &MySqlPool,
+    prefix: &str,
+) -> Result<Option<ApiKey>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT * FROM api_keys WHERE prefix = ?
+        "#)
+        .bind(prefix)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| ApiKey::from_row(&row)))
+}
+
+pub async fn touch_api_key(
+    pool: &MySqlPool,
+    id: i32,
+    ip_address: Option<String>,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE api_keys SET last_used_at = NOW(), last_used_ip = ? WHERE id = ?
+        "#)
+        .bind(ip_address)
+        .bind(id)
+        .execute(pool)
+        .await
+}
+
+pub async fn revoke_api_key(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE api_keys SET revoked_at = NOW() WHERE id = ? AND revoked_at IS NULL
+        "#)
+        .bind(id)
+        .execute(pool)
+        .await
+}
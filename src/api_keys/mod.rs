@@ -0,0 +1,18 @@
+pub mod models;
+pub mod handlers;
+pub mod services;
+
+use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api_keys")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
+            .service(services::new_api_key)
+            .service(services::get_api_keys)
+            .service(services::get_api_key_by_id)
+            .service(services::delete_api_key)
+    );
+}
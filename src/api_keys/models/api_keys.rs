@@ -0,0 +1,51 @@
+use chrono::NaiveDateTime;
+use sqlx::{Row, mysql::MySqlRow};
+
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub ip_allowlist: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub last_used_ip: Option<String>,
+    pub created_by: Option<i32>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+// Columnas guardadas como lista separada por comas
+fn split_list(value: Option<String>) -> Vec<String> {
+    value.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl ApiKey {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            name: row.get("name"),
+            prefix: row.get("prefix"),
+            key_hash: row.get("key_hash"),
+            scopes: split_list(row.get("scopes")),
+            ip_allowlist: split_list(row.get("ip_allowlist")),
+            expires_at: row.get("expires_at"),
+            last_used_at: row.get("last_used_at"),
+            last_used_ip: row.get("last_used_ip"),
+            created_by: row.get("created_by"),
+            revoked_at: row.get("revoked_at"),
+            created_at: row.get("created_at"),
+        }
+    }
+
+    pub fn is_usable(&self, now: NaiveDateTime) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+}
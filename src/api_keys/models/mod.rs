@@ -0,0 +1,3 @@
+pub mod api_keys;
+pub mod requests;
+pub mod responses;
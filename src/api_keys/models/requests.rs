@@ -0,0 +1,19 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[schema(example = json!({
+    "name": "Tablet recepción",
+    "scopes": ["attendance:write", "clients:read"],
+    "ip_allowlist": ["192.168.1.0/24"],
+    "expires_at": "2027-01-01T00:00:00"
+}))]
+pub struct NewApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub ip_allowlist: Vec<String>,
+    #[serde(default)]
+    pub expires_at: Option<NaiveDateTime>,
+}
@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use super::api_keys::ApiKey;
+
+// Vista pública de la API key: nunca expone el hash
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ApiKeyResponse {
+    pub id: i32,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub ip_allowlist: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub last_used_ip: Option<String>,
+    pub created_by: Option<i32>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.id,
+            name: api_key.name,
+            prefix: api_key.prefix,
+            scopes: api_key.scopes,
+            ip_allowlist: api_key.ip_allowlist,
+            expires_at: api_key.expires_at,
+            last_used_at: api_key.last_used_at,
+            last_used_ip: api_key.last_used_ip,
+            created_by: api_key.created_by,
+            revoked_at: api_key.revoked_at,
+            created_at: api_key.created_at,
+        }
+    }
+}
+
+// La key en claro solo se devuelve en esta respuesta
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CreatedApiKeyResponse {
+    pub key: String,
+    pub api_key: ApiKeyResponse,
+}
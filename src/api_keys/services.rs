@@ -0,0 +1,195 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use actix_web_grants::protect;
+use sqlx::MySqlPool;
+use crate::auth::api_keys::{generate_api_key, validate_ip_allowlist, validate_scopes};
+use crate::auth::models::jwt_models::Claims;
+use crate::errors::{access_denied, AppError};
+use super::handlers::{create_api_key, obtain_api_keys, obtain_api_key_by_id, revoke_api_key};
+use super::models::api_keys::ApiKey;
+use super::models::requests::NewApiKeyRequest;
+use super::models::responses::{ApiKeyResponse, CreatedApiKeyResponse};
+
+
+async fn find_api_key(pool: &MySqlPool, id: i32) -> Result<ApiKey, AppError> {
+    obtain_api_key_by_id(pool, id).await?
+        .ok_or_else(|| AppError::NotFound("API key not found".to_string()))
+}
+
+#[post("/")]
+#[protect("Admin", error = "access_denied")]
+pub async fn new_api_key(
+    pool: web::Data<MySqlPool>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<NewApiKeyRequest>,
+) -> Result<HttpResponse, AppError> {
+    if req.name.trim().is_empty() {
+        return Err(AppError::Validation {
+            message: "Name is required".to_string(),
+            details: Some(serde_json::json!({ "field": "name" })),
+        })
+    }
+    validate_scopes(&req.scopes).map_err(|message| AppError::Validation {
+        message,
+        details: Some(serde_json::json!({ "field": "scopes" })),
+    })?;
+    validate_ip_allowlist(&req.ip_allowlist).map_err(|message| AppError::Validation {
+        message,
+        details: Some(serde_json::json!({ "field": "ip_allowlist" })),
+    })?;
+
+    let (prefix, key) = generate_api_key();
+    let id = create_api_key(&pool, &req, &prefix, &key, claims.user_id as i32).await? as i32;
+    let api_key = find_api_key(&pool, id).await?;
+    tracing::info!("API key created: {} ({}) by {}", api_key.name, api_key.prefix, claims.sub);
+    Ok(HttpResponse::Created().json(CreatedApiKeyResponse {
+        key,
+        api_key: ApiKeyResponse::from(api_key),
+    }))
+}
+
+#[get("/")]
+#[protect("Admin", error = "access_denied")]
+pub async fn get_api_keys(
+    pool: web::Data<MySqlPool>,
+) -> Result<HttpResponse, AppError> {
+    let api_keys = obtain_api_keys(&pool).await?;
+    Ok(HttpResponse::Ok().json(api_keys.into_iter().map(ApiKeyResponse::from).collect::<Vec<_>>()))
+}
+
+#[get("/{id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn get_api_key_by_id(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let api_key = find_api_key(&pool, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(ApiKeyResponse::from(api_key)))
+}
+
+// Se revoca en lugar de borrar para conservar la auditoría de uso
+#[delete("/{id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn delete_api_key(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let api_key = find_api_key(&pool, id.into_inner()).await?;
+
+    revoke_api_key(&pool, api_key.id).await?;
+    tracing::info!("API key revoked: {} ({})", api_key.name, api_key.prefix);
+    Ok(HttpResponse::Ok().body("API key revoked successfully"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use chrono::{Duration, Utc};
+    use crate::api_keys::models::api_keys::ApiKey;
+    use crate::auth::api_keys::{
+        generate_api_key, parse_api_key, validate_scopes, validate_ip_allowlist, ip_allowed};
+
+    // Helper function para crear una API key de prueba
+    fn create_test_api_key() -> ApiKey {
+        ApiKey {
+            id: 1,
+            name: "Tablet recepción".to_string(),
+            prefix: "abcd1234".to_string(),
+            key_hash: "hash".to_string(),
+            scopes: vec!["attendance:write".to_string()],
+            ip_allowlist: Vec::new(),
+            expires_at: None,
+            last_used_at: None,
+            last_used_ip: None,
+            created_by: Some(1),
+            revoked_at: None,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn test_generated_key_parses_to_its_prefix() {
+        let (prefix, key) = generate_api_key();
+        assert!(key.starts_with("gh_"));
+        assert_eq!(parse_api_key(&key), Some(prefix.as_str()));
+
+        let (_, other) = generate_api_key();
+        assert_ne!(key, other);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_keys() {
+        assert_eq!(parse_api_key("gh_short_secret"), None);
+        assert_eq!(parse_api_key("eyJhbGciOiJIUzUxMiJ9.payload.signature"), None);
+        assert_eq!(parse_api_key("gh_abcd1234"), None);
+    }
+
+    #[test]
+    fn test_validate_scopes() {
+        assert!(validate_scopes(&["attendance:write".to_string(), "clients:read".to_string()]).is_ok());
+        assert!(validate_scopes(&[]).is_err());
+        // Los roles no son scopes: una key no puede hacerse pasar por Admin
+        assert!(validate_scopes(&["Admin".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_ip_allowlist() {
+        let ip = |value: &str| Some(value.parse::<IpAddr>().unwrap());
+        let allowlist = vec!["192.168.1.0/24".to_string(), "10.0.0.5".to_string(), "2001:db8::/32".to_string()];
+
+        assert!(validate_ip_allowlist(&allowlist).is_ok());
+        assert!(validate_ip_allowlist(&["192.168.1.0/33".to_string()]).is_err());
+        assert!(validate_ip_allowlist(&["not-an-ip".to_string()]).is_err());
+
+        assert!(ip_allowed(&allowlist, ip("192.168.1.77")));
+        assert!(ip_allowed(&allowlist, ip("10.0.0.5")));
+        assert!(ip_allowed(&allowlist, ip("2001:db8::1")));
+        assert!(!ip_allowed(&allowlist, ip("192.168.2.1")));
+        assert!(!ip_allowed(&allowlist, ip("10.0.0.6")));
+        assert!(!ip_allowed(&allowlist, None));
+        assert!(ip_allowed(&[], ip("8.8.8.8")));
+    }
+
+    #[test]
+    fn test_api_key_usable() {
+        let now = Utc::now().naive_utc();
+        let mut api_key = create_test_api_key();
+        assert!(api_key.is_usable(now));
+
+        api_key.expires_at = Some(now - Duration::minutes(1));
+        assert!(!api_key.is_usable(now));
+
+        api_key.expires_at = Some(now + Duration::days(1));
+        api_key.revoked_at = Some(now);
+        assert!(!api_key.is_usable(now));
+    }
+
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_manage_api_keys() {
+            let app = init_app(crate::api_keys::routes).await;
+
+            let req = test::TestRequest::get()
+                .uri("/api_keys/")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_malformed_api_key_is_unauthorized() {
+            let app = init_app(crate::clients::routes).await;
+
+            let req = test::TestRequest::get()
+                .uri("/clients/")
+                .insert_header(("X-API-Key", "gh_not-a-valid-key"))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::net::IpAddr;
+use rand::Rng;
+
+// Las keys tienen la forma gh_<prefijo>_<secreto>; el prefijo se guarda en claro para buscarlas
+pub const API_KEY_PREFIX: &str = "gh_";
+pub const API_KEY_HEADER: &str = "X-API-Key";
+const PREFIX_LEN: usize = 8;
+const SECRET_LEN: usize = 32;
+const KEY_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+// Scopes que puede recibir una API key; se usan como authorities en `protect`
+pub const SCOPES: &[&str] = &[
+    "clients:read",
+    "clients:write",
+    "subscriptions:read",
+    "subscriptions:write",
+    "attendance:write",
+    "payments:read",
+    "payments:write",
+];
+
+fn random_string(len: usize) -> String {
+    let mut rng = rand::rng();
+    (0..len)
+        .map(|_| KEY_ALPHABET[rng.random_range(0..KEY_ALPHABET.len())] as char)
+        .collect()
+}
+
+// Devuelve (prefijo, key completa). La key completa se muestra una única vez al crearla
+pub fn generate_api_key() -> (String, String) {
+    let prefix = random_string(PREFIX_LEN);
+    let key = format!("{}{}_{}", API_KEY_PREFIX, prefix, random_string(SECRET_LEN));
+    (prefix, key)
+}
+
+// Extrae el prefijo de una key con formato válido
+pub fn parse_api_key(key: &str) -> Option<&str> {
+    let (prefix, secret) = key.strip_prefix(API_KEY_PREFIX)?.split_once('_')?;
+    let valid = |part: &str, len: usize| part.len() == len && part.bytes().all(|b| KEY_ALPHABET.contains(&b));
+    (valid(prefix, PREFIX_LEN) && valid(secret, SECRET_LEN)).then_some(prefix)
+}
+
+pub fn validate_scopes(scopes: &[String]) -> Result<(), String> {
+    if scopes.is_empty() {
+        return Err("At least one scope is required".to_string());
+    }
+    match scopes.iter().find(|scope| !SCOPES.contains(&scope.as_str())) {
+        Some(scope) => Err(format!("Unknown scope: {}", scope)),
+        None => Ok(()),
+    }
+}
+
+// Acepta una IP exacta o un rango CIDR (IPv4 o IPv6)
+fn parse_ip_entry(entry: &str) -> Option<(IpAddr, u32)> {
+    match entry.split_once('/') {
+        None => {
+            let ip: IpAddr = entry.parse().ok()?;
+            let bits = if ip.is_ipv4() { 32 } else { 128 };
+            Some((ip, bits))
+        },
+        Some((network, bits)) => {
+            let network: IpAddr = network.parse().ok()?;
+            let bits: u32 = bits.parse().ok()?;
+            let max_bits = if network.is_ipv4() { 32 } else { 128 };
+            (bits <= max_bits).then_some((network, bits))
+        }
+    }
+}
+
+pub fn validate_ip_allowlist(entries: &[String]) -> Result<(), String> {
+    match entries.iter().find(|entry| parse_ip_entry(entry).is_none()) {
+        Some(entry) => Err(format!("Invalid IP or CIDR range: {}", entry)),
+        None => Ok(()),
+    }
+}
+
+fn ip_in_range(ip: IpAddr, network: IpAddr, bits: u32) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - bits).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        },
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - bits).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        },
+        _ => false,
+    }
+}
+
+// Lista vacía = cualquier IP
+pub fn ip_allowed(allowlist: &[String], ip: Option<IpAddr>) -> bool {
+    if allowlist.is_empty() {
+        return true;
+    }
+    let Some(ip) = ip else {
+        return false;
+    };
+    allowlist.iter()
+        .filter_map(|entry| parse_ip_entry(entry))
+        .any(|(network, bits)| ip_in_range(ip, network, bits))
+}
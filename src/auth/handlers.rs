@@ -1,14 +1,31 @@
 use sqlx::mysql::MySqlQueryResult;
 use sqlx::{self, MySqlPool};
 use crate::auth::models::users::{User, UserRole};
-use argon2::{Argon2, PasswordHasher};
+use crate::auth::models::jwt_models::Claims;
+use crate::auth::models::refresh_tokens::RefreshToken;
+use crate::auth::models::login_attempts::{LoginAttempt, LoginAttemptResult, FailureStats};
+use crate::auth::mfa::normalize_recovery_code;
+use crate::errors::AppError;
+use sha2::{Digest, Sha256};
+use argon2::{Algorithm, Argon2, Params, PasswordHasher, Version};
 use argon2::password_hash::{SaltString, PasswordHash, PasswordVerifier, rand_core::OsRng};
 
 
-fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+// Parámetros vigentes de argon2; los hashes creados con otros parámetros se regeneran al loguear
+const ARGON2_M_COST: u32 = 64 * 1024;
+const ARGON2_T_COST: u32 = 3;
+const ARGON2_P_COST: u32 = 1;
+
+fn argon2_hasher() -> Argon2<'static> {
+    let params = Params::new(ARGON2_M_COST, ARGON2_T_COST, ARGON2_P_COST, None)
+        .expect("Invalid argon2 params");
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+}
+
+fn hash_password(password: &str) -> Result<String, AppError> {
     let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
-    let password_hash = argon2.hash_password(password.as_bytes(), &salt)?;
+    let password_hash = argon2_hasher().hash_password(password.as_bytes(), &salt)
+        .map_err(|e| AppError::Internal(format!("Error hashing password: {}", e)))?;
     Ok(password_hash.to_string())
 }
 
@@ -22,21 +39,34 @@ pub fn verify_password(password: &str, hash: &str) -> bool {
     }
 }
 
+// Hash con los parámetros vigentes que no corresponde a ninguna contraseña: se verifica contra él
+// cuando el usuario no existe para que el tiempo de respuesta no revele qué usuarios hay
+pub const DUMMY_PASSWORD_HASH: &str =
+    "$argon2id$v=19$m=65536,t=3,p=1$c29tZXNhbHQ$AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+
+pub fn needs_rehash(hash: &str) -> bool {
+    let Ok(ph) = PasswordHash::new(hash) else {
+        return true;
+    };
+    let Ok(params) = Params::try_from(&ph) else {
+        return true;
+    };
+    ph.algorithm != Algorithm::Argon2id.ident()
+        || ph.version != Some(Version::V0x13.into())
+        || params.m_cost() != ARGON2_M_COST
+        || params.t_cost() != ARGON2_T_COST
+        || params.p_cost() != ARGON2_P_COST
+}
+
 pub async fn create_user_in_db(
     pool: &MySqlPool,
     username: &str,
     password: &str,
     role: &str,
-) -> Result<MySqlQueryResult, sqlx::Error> {
-    let hashed_password = match hash_password(password) {
-        Ok(hash) => hash,
-        Err(e) => {
-            tracing::error!("Error hashing password: {}", e);
-            return Err(sqlx::Error::ColumnNotFound("Error hashing password".to_string()));
-        }
-    };
+) -> Result<MySqlQueryResult, AppError> {
+    let hashed_password = hash_password(password)?;
 
-    let result = sqlx::query!(
+    sqlx::query!(
         r#"
         INSERT INTO users (username, hash, role) VALUES (?, ?, ?)
         "#,
@@ -45,31 +75,397 @@ pub async fn create_user_in_db(
         role
     )
     .execute(pool)
-    .await;
+    .await
+    .map_err(|e| match AppError::from(e) {
+        AppError::Conflict(_) => AppError::Conflict("Username already exists".to_string()),
+        e => e,
+    })
+}
+
+pub async fn update_user_password(
+    pool: &MySqlPool,
+    id: i32,
+    password: &str,
+) -> Result<MySqlQueryResult, AppError> {
+    let hashed_password = hash_password(password)?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE users SET hash = ? WHERE id = ?
+        "#
+    )
+    .bind(hashed_password)
+    .bind(id)
+    .execute(pool)
+    .await?;
 
-    result
+    Ok(result)
 }
 
 pub async fn get_user_by_username(
     pool: &MySqlPool,
     username: &str,
 ) -> Result<Option<User>, sqlx::Error> {
-    let user = sqlx::query_as!(
-        User,
-        r#"
-        SELECT
-            id,
-            username,
-            hash,
-            role as "role: UserRole",
-            created_at,
-            updated_at
-        FROM users
-        WHERE username = ?
+    let row = sqlx::query(
+        r#"
+        SELECT * FROM users WHERE username = ?
+        "#)
+        .bind(username)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| User::from_row(&row)))
+}
+
+// Crea el primer Admin solo si la tabla está vacía, en una única sentencia para que dos bootstraps
+// simultáneos no creen dos. false si ya había usuarios (o si otro bootstrap ganó la carrera)
+pub async fn create_first_admin(
+    pool: &MySqlPool,
+    username: &str,
+    password: &str,
+) -> Result<bool, AppError> {
+    let hashed_password = hash_password(password)?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO users (username, hash, role)
+        SELECT ?, ?, ? FROM DUAL
+        WHERE NOT EXISTS (SELECT 1 FROM users)
         "#,
-        username
     )
-    .fetch_optional(pool)
-    .await?;
-    Ok(user)
+    .bind(username)
+    .bind(hashed_password)
+    .bind(UserRole::Admin.as_str())
+    .execute(pool)
+    .await;
+
+    match result {
+        Ok(result) => Ok(result.rows_affected() == 1),
+        // Con la tabla vacía los dos inserts se bloquean entre sí y MySQL aborta uno por deadlock
+        Err(sqlx::Error::Database(db_error)) if db_error.code().as_deref() == Some("40001") => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// REFRESH TOKEN HANDLERS //////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+// Los refresh tokens se guardan hasheados: un dump de la tabla no permite reutilizarlos
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+pub async fn store_refresh_token(
+    pool: &MySqlPool,
+    claims: &Claims,
+    token: &str,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    let expires_at = chrono::DateTime::from_timestamp(claims.exp as i64, 0)
+        .unwrap_or_default()
+        .naive_utc();
+
+    sqlx::query(
+        r#"
+        INSERT INTO refresh_tokens (jti, family_id, user_id, token_hash, expires_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&claims.jti)
+    .bind(&claims.family_id)
+    .bind(claims.user_id as i32)
+    .bind(hash_token(token))
+    .bind(expires_at)
+    .execute(pool)
+    .await
+}
+
+pub async fn get_refresh_token_by_jti(
+    pool: &MySqlPool,
+    jti: &str,
+) -> Result<Option<RefreshToken>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT * FROM refresh_tokens WHERE jti = ?
+        "#)
+        .bind(jti)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| RefreshToken::from_row(&row)))
+}
+
+// Marca el token como usado solo si seguía vigente; devuelve false si otro request lo usó antes
+pub async fn mark_refresh_token_used(
+    pool: &MySqlPool,
+    jti: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE refresh_tokens
+        SET used_at = NOW()
+        WHERE jti = ? AND used_at IS NULL AND revoked_at IS NULL
+        "#)
+        .bind(jti)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn revoke_token_family(
+    pool: &MySqlPool,
+    family_id: &str,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE refresh_tokens
+        SET revoked_at = NOW()
+        WHERE family_id = ? AND revoked_at IS NULL
+        "#)
+        .bind(family_id)
+        .execute(pool)
+        .await
+}
+
+pub async fn revoke_user_tokens(
+    pool: &MySqlPool,
+    user_id: i32,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE refresh_tokens
+        SET revoked_at = NOW()
+        WHERE user_id = ? AND revoked_at IS NULL
+        "#)
+        .bind(user_id)
+        .execute(pool)
+        .await
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// LOGIN ATTEMPT HANDLERS //////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+pub async fn record_login_attempt(
+    pool: &MySqlPool,
+    username: &str,
+    ip_address: &str,
+    result: LoginAttemptResult,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO login_attempts (username, ip_address, result) VALUES (?, ?, ?)
+        "#)
+        .bind(username)
+        .bind(ip_address)
+        .bind(result.as_str())
+        .execute(pool)
+        .await
+}
+
+pub async fn get_username_failures(
+    pool: &MySqlPool,
+    username: &str,
+    since: chrono::NaiveDateTime,
+) -> Result<FailureStats, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT COUNT(*) AS failures, MAX(attempted_at) AS last_failure
+        FROM login_attempts
+        WHERE username = ? AND result = 'Failure' AND cleared = false AND attempted_at >= ?
+        "#)
+        .bind(username)
+        .bind(since)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(FailureStats::from_row(&row))
+}
+
+// Los fallos por IP no se limpian con un login exitoso ni con el desbloqueo de un usuario
+pub async fn get_ip_failures(
+    pool: &MySqlPool,
+    ip_address: &str,
+    since: chrono::NaiveDateTime,
+) -> Result<FailureStats, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT COUNT(*) AS failures, MAX(attempted_at) AS last_failure
+        FROM login_attempts
+        WHERE ip_address = ? AND result = 'Failure' AND attempted_at >= ?
+        "#)
+        .bind(ip_address)
+        .bind(since)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(FailureStats::from_row(&row))
+}
+
+pub async fn clear_login_failures(
+    pool: &MySqlPool,
+    username: &str,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE login_attempts
+        SET cleared = true
+        WHERE username = ? AND result = 'Failure' AND cleared = false
+        "#)
+        .bind(username)
+        .execute(pool)
+        .await
+}
+
+pub async fn get_login_attempts_by_username(
+    pool: &MySqlPool,
+    username: &str,
+    limit: i64,
+) -> Result<Vec<LoginAttempt>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM login_attempts
+        WHERE username = ?
+        ORDER BY attempted_at DESC, id DESC
+        LIMIT ?
+        "#)
+        .bind(username)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(LoginAttempt::from_row).collect())
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// MFA HANDLERS ////////////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+// Guarda un secreto nuevo sin habilitarlo: se habilita recién al confirmar un código válido
+pub async fn set_mfa_secret(
+    pool: &MySqlPool,
+    user_id: i32,
+    secret: &str,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET mfa_secret = ?, mfa_enabled = false, mfa_last_step = NULL
+        WHERE id = ?
+        "#)
+        .bind(secret)
+        .bind(user_id)
+        .execute(pool)
+        .await
+}
+
+pub async fn enable_mfa(
+    pool: &MySqlPool,
+    user_id: i32,
+    step: i64,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET mfa_enabled = true, mfa_last_step = ?
+        WHERE id = ? AND mfa_secret IS NOT NULL
+        "#)
+        .bind(step)
+        .bind(user_id)
+        .execute(pool)
+        .await
+}
+
+// Registra el paso TOTP usado; devuelve false si ese código (o uno posterior) ya se usó
+pub async fn consume_mfa_step(
+    pool: &MySqlPool,
+    user_id: i32,
+    step: i64,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE users
+        SET mfa_last_step = ?
+        WHERE id = ? AND (mfa_last_step IS NULL OR mfa_last_step < ?)
+        "#)
+        .bind(step)
+        .bind(user_id)
+        .bind(step)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn disable_mfa(
+    pool: &MySqlPool,
+    user_id: i32,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM mfa_recovery_codes WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let result = sqlx::query(
+        r#"
+        UPDATE users
+        SET mfa_secret = NULL, mfa_enabled = false, mfa_last_step = NULL
+        WHERE id = ?
+        "#)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(result)
+}
+
+pub async fn replace_recovery_codes(
+    pool: &MySqlPool,
+    user_id: i32,
+    codes: &[String],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM mfa_recovery_codes WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for code in codes {
+        sqlx::query(
+            r#"
+            INSERT INTO mfa_recovery_codes (user_id, code_hash) VALUES (?, ?)
+            "#)
+            .bind(user_id)
+            .bind(hash_token(&normalize_recovery_code(code)))
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await
+}
+
+// Cada código de recuperación sirve una sola vez
+pub async fn use_recovery_code(
+    pool: &MySqlPool,
+    user_id: i32,
+    code: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE mfa_recovery_codes
+        SET used_at = NOW()
+        WHERE user_id = ? AND code_hash = ? AND used_at IS NULL
+        LIMIT 1
+        "#)
+        .bind(user_id)
+        .bind(hash_token(&normalize_recovery_code(code)))
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() == 1)
 }
@@ -1,39 +1,33 @@
 use actix_web::web;
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
-use chrono::{Utc, Duration};
-use crate::auth::models::jwt_models::{Claims, TokenType};
+use chrono::Utc;
+use jsonwebtoken::{decode, decode_header, encode, errors::ErrorKind, Header, Validation};
+use crate::auth::keyring::Keyring;
+use crate::auth::models::jwt_models::Claims;
 
 
-pub fn generate_token(
-        iss: String, sub: String, duration: i64,
-        user_id: usize, role: String, 
-        token_type: TokenType, key: String) -> String{
-    let header = Header::new(Algorithm::HS512);
-    let encoding_key = EncodingKey::from_secret(key.as_ref());
+pub fn generate_token(claims: &Claims, keyring: &Keyring) -> String {
+    let (key, encoding_key) = keyring.signing_key();
+    let mut header = Header::new(key.algorithm);
+    header.kid = Some(key.kid.clone());
 
-    let exp = (Utc::now() + Duration::minutes(duration)).timestamp() as usize;
-    let iat = Utc::now().timestamp() as usize;
-
-    let claims = Claims {
-        iss,
-        sub,
-        exp,
-        iat,
-        token_type,
-        user_id,
-        role
-    };
-
-    encode(&header, &claims, &encoding_key).unwrap()
+    encode(&header, claims, encoding_key).unwrap()
 }
 
 pub fn validate_token(
         token: String,
         data: web::Data<crate::config::Config>) -> Result<Claims, jsonwebtoken::errors::Error> {
-    let validation = Validation::new(Algorithm::HS512);
-    let decoding_key = DecodingKey::from_secret(data.jwt_secret.as_ref());
+    let header = decode_header(&token)?;
+    // Clave según el `kid` del header; las retiradas solo se aceptan durante el período de gracia
+    let Some(key) = data.keyring.verification_key(header.kid.as_deref(), Utc::now()) else {
+        tracing::error!("Unknown or expired signing key: {:?}", header.kid);
+        return Err(ErrorKind::InvalidSignature.into())
+    };
+    let mut validation = Validation::new(key.algorithm);
+    validation.set_issuer(&[&data.tokens.issuer]);
+    validation.set_audience(&[&data.tokens.audience]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud"]);
 
-    match decode::<Claims>(&token, &decoding_key, &validation) {
+    match decode::<Claims>(&token, key.decoding_key(), &validation) {
         Ok(token_data) => {
             tracing::info!("Token is valid");
             Ok(token_data.claims)
@@ -43,4 +37,4 @@ pub fn validate_token(
             Err(err)
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,287 @@
+use std::{fmt, fs, sync::Arc};
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+    OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType};
+use rsa::{pkcs1::DecodeRsaPublicKey, pkcs8::DecodePublicKey, traits::PublicKeyParts, RsaPublicKey};
+use serde::Deserialize;
+use crate::auth::token_policy::TokenPolicy;
+
+// Los tokens emitidos antes del keyring no tienen `kid`: se validan con esta clave
+pub const DEFAULT_KID: &str = "default";
+// Prefijo DER (SubjectPublicKeyInfo) de una clave pública Ed25519; le siguen los 32 bytes de la clave
+const ED25519_SPKI_PREFIX: [u8; 12] = [0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum JwtAlgorithm {
+    HS512,
+    RS256,
+    EdDSA,
+}
+
+impl From<JwtAlgorithm> for Algorithm {
+    fn from(algorithm: JwtAlgorithm) -> Self {
+        match algorithm {
+            JwtAlgorithm::HS512 => Algorithm::HS512,
+            JwtAlgorithm::RS256 => Algorithm::RS256,
+            JwtAlgorithm::EdDSA => Algorithm::EdDSA,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct JwtKeyConfig {
+    pub kid: String,
+    pub algorithm: JwtAlgorithm,
+    // Solo HS512
+    #[serde(default)]
+    pub secret: Option<String>,
+    // Solo RS256/EdDSA; sin clave privada la clave únicamente verifica
+    #[serde(default)]
+    pub private_key_path: Option<String>,
+    #[serde(default)]
+    pub public_key_path: Option<String>,
+    // Desde cuándo la clave dejó de firmar; se sigue aceptando durante el período de gracia
+    #[serde(default)]
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct JwtKeysConfig {
+    // Clave con la que se firman los tokens nuevos; por defecto la primera de la lista
+    pub active_kid: Option<String>,
+    pub grace_period_hours: i64,
+    // Vacío = una única clave HS512 con `jwt_secret` (comportamiento histórico)
+    pub keys: Vec<JwtKeyConfig>,
+}
+
+impl Default for JwtKeysConfig {
+    fn default() -> Self {
+        Self {
+            active_kid: None,
+            // Igual a la duración del refresh token para no cortar sesiones al rotar
+            grace_period_hours: 24,
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl JwtKeysConfig {
+    // Una clave retirada tiene que seguir validando los refresh tokens que firmó hasta que vencen
+    pub fn check_grace_period(&self, tokens: &TokenPolicy) -> Result<(), String> {
+        let longest_refresh = tokens.max_refresh_minutes();
+        if self.grace_period_hours * 60 < longest_refresh {
+            return Err(format!(
+                "grace_period_hours ({}) is shorter than the longest refresh token lifetime ({} minutes)",
+                self.grace_period_hours, longest_refresh));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct SigningKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    encoding_key: Option<EncodingKey>,
+    decoding_key: DecodingKey,
+    jwk: Option<Jwk>,
+    retired_at: Option<DateTime<Utc>>,
+}
+
+impl SigningKey {
+    fn load(key: &JwtKeyConfig) -> Result<Self, String> {
+        let (encoding_key, decoding_key, jwk) = match key.algorithm {
+            JwtAlgorithm::HS512 => {
+                let secret = key.secret.as_deref()
+                    .ok_or_else(|| format!("key '{}': HS512 requires a secret", key.kid))?;
+                (
+                    Some(EncodingKey::from_secret(secret.as_bytes())),
+                    DecodingKey::from_secret(secret.as_bytes()),
+                    None,
+                )
+            },
+            JwtAlgorithm::RS256 | JwtAlgorithm::EdDSA => {
+                let public_pem = read_pem(&key.kid, key.public_key_path.as_deref(), "public_key_path")?;
+                let encoding_key = match key.private_key_path.as_deref() {
+                    Some(path) => {
+                        let private_pem = read_pem(&key.kid, Some(path), "private_key_path")?;
+                        let encoding_key = if key.algorithm == JwtAlgorithm::RS256 {
+                            EncodingKey::from_rsa_pem(&private_pem)
+                        } else {
+                            EncodingKey::from_ed_pem(&private_pem)
+                        };
+                        Some(encoding_key.map_err(|e| format!("key '{}': invalid private key: {}", key.kid, e))?)
+                    },
+                    None => None,
+                };
+                let (decoding_key, jwk) = if key.algorithm == JwtAlgorithm::RS256 {
+                    (DecodingKey::from_rsa_pem(&public_pem), rsa_jwk(&key.kid, &public_pem)?)
+                } else {
+                    (DecodingKey::from_ed_pem(&public_pem), ed25519_jwk(&key.kid, &public_pem)?)
+                };
+                let decoding_key = decoding_key
+                    .map_err(|e| format!("key '{}': invalid public key: {}", key.kid, e))?;
+                (encoding_key, decoding_key, Some(jwk))
+            },
+        };
+
+        Ok(Self {
+            kid: key.kid.clone(),
+            algorithm: key.algorithm.into(),
+            encoding_key,
+            decoding_key,
+            jwk,
+            retired_at: key.retired_at,
+        })
+    }
+
+    pub fn decoding_key(&self) -> &DecodingKey {
+        &self.decoding_key
+    }
+}
+
+fn read_pem(kid: &str, path: Option<&str>, field: &str) -> Result<Vec<u8>, String> {
+    let path = path.ok_or_else(|| format!("key '{}': {} is required", kid, field))?;
+    fs::read(path).map_err(|e| format!("key '{}': cannot read {}: {}", kid, path, e))
+}
+
+fn pem_to_der(pem: &[u8]) -> Option<Vec<u8>> {
+    let body: String = std::str::from_utf8(pem).ok()?
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect();
+    STANDARD.decode(body.trim()).ok()
+}
+
+fn public_jwk_common(kid: &str, algorithm: KeyAlgorithm) -> CommonParameters {
+    CommonParameters {
+        public_key_use: Some(PublicKeyUse::Signature),
+        key_id: Some(kid.to_string()),
+        key_algorithm: Some(algorithm),
+        ..Default::default()
+    }
+}
+
+fn rsa_jwk(kid: &str, pem: &[u8]) -> Result<Jwk, String> {
+    let pem = std::str::from_utf8(pem).map_err(|_| format!("key '{}': invalid PEM", kid))?;
+    let public_key = RsaPublicKey::from_public_key_pem(pem)
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(pem))
+        .map_err(|e| format!("key '{}': invalid RSA public key: {}", kid, e))?;
+    Ok(Jwk {
+        common: public_jwk_common(kid, KeyAlgorithm::RS256),
+        algorithm: AlgorithmParameters::RSA(RSAKeyParameters {
+            key_type: RSAKeyType::RSA,
+            n: URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be()),
+            e: URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be()),
+        }),
+    })
+}
+
+fn ed25519_jwk(kid: &str, pem: &[u8]) -> Result<Jwk, String> {
+    let der = pem_to_der(pem).ok_or_else(|| format!("key '{}': invalid PEM", kid))?;
+    if der.len() != ED25519_SPKI_PREFIX.len() + 32 || !der.starts_with(&ED25519_SPKI_PREFIX) {
+        return Err(format!("key '{}': not an Ed25519 public key", kid));
+    }
+    Ok(Jwk {
+        common: public_jwk_common(kid, KeyAlgorithm::EdDSA),
+        algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+            key_type: OctetKeyPairType::OctetKeyPair,
+            curve: EllipticCurve::Ed25519,
+            x: URL_SAFE_NO_PAD.encode(&der[ED25519_SPKI_PREFIX.len()..]),
+        }),
+    })
+}
+
+// Claves cargadas al iniciar: una activa para firmar y las retiradas que siguen validando
+#[derive(Clone, Default)]
+pub struct Keyring {
+    active_kid: String,
+    grace_period: Duration,
+    keys: Arc<Vec<SigningKey>>,
+}
+
+impl fmt::Debug for Keyring {
+    // Nunca imprimir el material de las claves
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keyring")
+            .field("active_kid", &self.active_kid)
+            .field("kids", &self.keys.iter().map(|key| &key.kid).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Keyring {
+    pub fn load(config: &JwtKeysConfig, jwt_secret: &str) -> Result<Self, String> {
+        let key_configs = if config.keys.is_empty() {
+            vec![JwtKeyConfig {
+                kid: DEFAULT_KID.to_string(),
+                algorithm: JwtAlgorithm::HS512,
+                secret: Some(jwt_secret.to_string()),
+                private_key_path: None,
+                public_key_path: None,
+                retired_at: None,
+            }]
+        } else {
+            config.keys.clone()
+        };
+
+        let keys = key_configs.iter()
+            .map(SigningKey::load)
+            .collect::<Result<Vec<_>, _>>()?;
+        for (i, key) in keys.iter().enumerate() {
+            if keys[..i].iter().any(|other| other.kid == key.kid) {
+                return Err(format!("duplicated kid '{}'", key.kid));
+            }
+        }
+
+        let active_kid = config.active_kid.clone().unwrap_or_else(|| keys[0].kid.clone());
+        let active = keys.iter().find(|key| key.kid == active_kid)
+            .ok_or_else(|| format!("active kid '{}' is not in the keyring", active_kid))?;
+        if active.encoding_key.is_none() {
+            return Err(format!("active key '{}' has no private key", active_kid));
+        }
+        if active.retired_at.is_some() {
+            return Err(format!("active key '{}' is retired", active_kid));
+        }
+
+        Ok(Self {
+            active_kid,
+            grace_period: Duration::hours(config.grace_period_hours),
+            keys: Arc::new(keys),
+        })
+    }
+
+    // Clave y material para firmar tokens nuevos
+    pub fn signing_key(&self) -> (&SigningKey, &EncodingKey) {
+        let key = self.keys.iter()
+            .find(|key| key.kid == self.active_kid)
+            .expect("Keyring not loaded");
+        (key, key.encoding_key.as_ref().expect("Active key without private key"))
+    }
+
+    fn is_accepted(&self, key: &SigningKey, now: DateTime<Utc>) -> bool {
+        key.retired_at.is_none_or(|retired_at| now < retired_at + self.grace_period)
+    }
+
+    // Clave para validar un token según su `kid`; None si no existe o venció su período de gracia
+    pub fn verification_key(&self, kid: Option<&str>, now: DateTime<Utc>) -> Option<&SigningKey> {
+        let kid = kid.unwrap_or(DEFAULT_KID);
+        self.keys.iter()
+            .find(|key| key.kid == kid)
+            .filter(|key| self.is_accepted(key, now))
+    }
+
+    // Claves públicas vigentes para que otros servicios validen los tokens (las HS512 nunca se publican)
+    pub fn jwks(&self, now: DateTime<Utc>) -> JwkSet {
+        JwkSet {
+            keys: self.keys.iter()
+                .filter(|key| self.is_accepted(key, now))
+                .filter_map(|key| key.jwk.clone())
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{Duration, NaiveDateTime};
+use serde::Deserialize;
+use crate::auth::models::login_attempts::FailureStats;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LoginThrottle {
+    // Fallos consecutivos de un usuario antes de bloquear la cuenta
+    pub max_failures: i64,
+    // Fallos desde una misma IP (cualquier usuario) antes de bloquearla
+    pub max_failures_per_ip: i64,
+    pub lockout_minutes: i64,
+    // Ventana en la que se cuentan los fallos
+    pub window_minutes: i64,
+    pub backoff_base_seconds: i64,
+    pub backoff_max_seconds: i64,
+}
+
+impl Default for LoginThrottle {
+    fn default() -> Self {
+        Self {
+            max_failures: 5,
+            max_failures_per_ip: 20,
+            lockout_minutes: 15,
+            window_minutes: 15,
+            backoff_base_seconds: 1,
+            backoff_max_seconds: 60,
+        }
+    }
+}
+
+impl LoginThrottle {
+    pub fn window_start(&self, now: NaiveDateTime) -> NaiveDateTime {
+        now - Duration::minutes(self.window_minutes)
+    }
+
+    // Espera exigida tras `failures` fallos: backoff exponencial y bloqueo al superar el límite
+    pub fn required_wait(&self, failures: i64, max_failures: i64) -> Duration {
+        if failures <= 0 {
+            return Duration::zero();
+        }
+        if failures >= max_failures {
+            return Duration::minutes(self.lockout_minutes);
+        }
+        let exponent = (failures - 1).min(30) as u32;
+        let seconds = self.backoff_base_seconds
+            .saturating_mul(2_i64.saturating_pow(exponent))
+            .min(self.backoff_max_seconds);
+        Duration::seconds(seconds)
+    }
+
+    // Segundos que faltan para poder reintentar, o None si el intento está permitido
+    pub fn retry_after(&self, stats: &FailureStats, max_failures: i64, now: NaiveDateTime) -> Option<i64> {
+        let last_failure = stats.last_failure?;
+        let allowed_at = last_failure + self.required_wait(stats.failures, max_failures);
+        if allowed_at > now {
+            Some((allowed_at - now).num_seconds().max(1))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use base32::Alphabet;
+use hmac::{Hmac, Mac};
+use rand::{Rng, RngCore};
+use serde::Deserialize;
+use sha1::Sha1;
+
+// TOTP según RFC 6238: pasos de 30 segundos, 6 dígitos, HMAC-SHA1 (lo que esperan las apps autenticadoras)
+const TOTP_STEP_SECONDS: i64 = 30;
+const TOTP_DIGITS: u32 = 6;
+// Pasos de tolerancia hacia atrás y adelante por desfase de reloj del celular
+const TOTP_SKEW_STEPS: i64 = 1;
+const SECRET_BYTES: usize = 20;
+const RECOVERY_CODES: usize = 10;
+const RECOVERY_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MfaPolicy {
+    // Si está activo, los Admin no obtienen tokens hasta enrolar un segundo factor
+    pub require_for_admins: bool,
+    // Nombre que muestra la app autenticadora
+    pub issuer: String,
+    pub pending_token_minutes: i64,
+}
+
+impl Default for MfaPolicy {
+    fn default() -> Self {
+        Self {
+            require_for_admins: false,
+            issuer: "Gym Helper".to_string(),
+            pending_token_minutes: 5,
+        }
+    }
+}
+
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    rand::rng().fill_bytes(&mut bytes);
+    base32::encode(Alphabet::Rfc4648 { padding: false }, &bytes)
+}
+
+fn percent_encode(value: &str) -> String {
+    value.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// URI para generar el QR que escanean Google Authenticator, Authy, etc.
+pub fn otpauth_uri(issuer: &str, username: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(username),
+        secret,
+        percent_encode(issuer),
+        TOTP_DIGITS,
+        TOTP_STEP_SECONDS,
+    )
+}
+
+pub fn totp_code(secret: &[u8], step: i64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    binary % 10_u32.pow(TOTP_DIGITS)
+}
+
+pub fn current_step(unix_time: i64) -> i64 {
+    unix_time / TOTP_STEP_SECONDS
+}
+
+// Devuelve el paso que coincidió para poder rechazar el reuso del mismo código
+pub fn verify_totp(secret: &str, code: &str, unix_time: i64) -> Option<i64> {
+    let secret = base32::decode(Alphabet::Rfc4648 { padding: false }, secret)?;
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+    let step = current_step(unix_time);
+
+    (step - TOTP_SKEW_STEPS..=step + TOTP_SKEW_STEPS)
+        .find(|candidate| totp_code(&secret, *candidate) == code)
+}
+
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::rng();
+    (0..RECOVERY_CODES)
+        .map(|_| {
+            let chars: String = (0..10)
+                .map(|_| RECOVERY_ALPHABET[rng.random_range(0..RECOVERY_ALPHABET.len())] as char)
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+// Los códigos se aceptan sin importar mayúsculas ni guiones
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
@@ -1,37 +1,104 @@
-use actix_web::{web, dev::ServiceRequest, Error, error};
+use actix_web::{web, dev::ServiceRequest, Error, HttpMessage};
 use actix_web_httpauth::extractors::bearer::BearerAuth;
+use chrono::Utc;
+use sqlx::MySqlPool;
+use subtle::ConstantTimeEq;
 use crate::auth::jwt::validate_token;
+use crate::auth::api_keys::{parse_api_key, ip_allowed, API_KEY_HEADER, API_KEY_PREFIX};
+use crate::auth::handlers::hash_token;
+use crate::api_keys::handlers::{obtain_api_key_by_prefix, touch_api_key};
+use crate::errors::AppError;
 use tracing::{info, error};
 use super::models::jwt_models::TokenType;
 use actix_web_grants::authorities::AttachAuthorities;
 
+// La API key puede llegar en el header X-API-Key o como bearer token con prefijo gh_
+fn api_key_from_request(req: &ServiceRequest, credentials: Option<&BearerAuth>) -> Option<String> {
+    if let Some(value) = req.headers().get(API_KEY_HEADER) {
+        return Some(value.to_str().unwrap_or_default().to_string());
+    }
+    credentials
+        .map(|credentials| credentials.token())
+        .filter(|token| token.starts_with(API_KEY_PREFIX))
+        .map(str::to_string)
+}
+
+async fn api_key_middleware(
+    req: ServiceRequest,
+    key: String,
+) -> Result<ServiceRequest, (Error, ServiceRequest)> {
+    let Some(prefix) = parse_api_key(&key) else {
+        error!("Malformed API key");
+        return Err((AppError::Unauthorized("Invalid API key".to_string()).into(), req));
+    };
+    let pool = req.app_data::<web::Data<MySqlPool>>().unwrap().clone();
+
+    let api_key = match obtain_api_key_by_prefix(&pool, prefix).await {
+        // Comparación en tiempo constante: el tiempo de respuesta no revela cuánto del hash coincide
+        Ok(Some(api_key)) if bool::from(api_key.key_hash.as_bytes().ct_eq(hash_token(&key).as_bytes())) => api_key,
+        Ok(_) => {
+            error!("Unknown API key: {}", prefix);
+            return Err((AppError::Unauthorized("Invalid API key".to_string()).into(), req));
+        },
+        Err(e) => {
+            error!("Error fetching API key: {}", e);
+            return Err((AppError::from(e).into(), req));
+        }
+    };
+    if !api_key.is_usable(Utc::now().naive_utc()) {
+        error!("Revoked or expired API key: {}", api_key.prefix);
+        return Err((AppError::Unauthorized("Invalid API key".to_string()).into(), req));
+    }
+
+    let ip = req.peer_addr().map(|addr| addr.ip());
+    if !ip_allowed(&api_key.ip_allowlist, ip) {
+        error!("API key {} used from a non allowed IP: {:?}", api_key.prefix, ip);
+        return Err((AppError::Forbidden("IP not allowed for this API key".to_string()).into(), req));
+    }
+
+    if let Err(e) = touch_api_key(&pool, api_key.id, ip.map(|ip| ip.to_string())).await {
+        error!("Error updating API key last use: {}", e);
+    }
+    info!("API key is valid: {}", api_key.name);
+    // Los scopes funcionan como authorities: `protect` los chequea igual que los roles
+    req.attach(api_key.scopes.clone());
+    req.extensions_mut().insert(api_key);
+    Ok(req)
+}
+
 pub async fn auth_middleware(
     req: ServiceRequest,
     credentials: Option<BearerAuth>,
 ) -> Result<ServiceRequest, (Error, ServiceRequest)> {
+    if let Some(key) = api_key_from_request(&req, credentials.as_ref()) {
+        return api_key_middleware(req, key).await;
+    }
+
     let Some(credentials) = credentials else {
-        return Err((error::ErrorBadRequest("Missing token"), req));
+        return Err((AppError::Unauthorized("Missing token".to_string()).into(), req));
     };
 
     let token = credentials.token();
 
     match validate_token(
-        token.to_owned(), 
+        token.to_owned(),
         req.app_data::<web::Data<crate::config::Config>>().unwrap().clone()) {
             Ok(claims) => match claims.token_type {
                 TokenType::Access => {
                     info!("Access token is valid");
                     req.attach(vec![claims.role.clone()]);
-                    return Ok(req);
+                    // Los handlers pueden identificar al usuario con web::ReqData<Claims>
+                    req.extensions_mut().insert(claims);
+                    Ok(req)
                 },
-                TokenType::Refresh => {
+                TokenType::Refresh | TokenType::MfaPending => {
                     error!("Invalid token type: {:?}", claims.token_type);
-                    return Err((error::ErrorUnauthorized("Invalid token type"), req));
+                    Err((AppError::Unauthorized("Invalid token type".to_string()).into(), req))
                 }
             },
             Err(e) => {
                 error!("Token validation failed: {}", e);
-                return Err((error::ErrorUnauthorized("Invalid token"), req));
+                Err((AppError::Unauthorized("Invalid token".to_string()).into(), req))
             }
         }
-}
\ No newline at end of file
+}
@@ -1,16 +1,46 @@
+pub mod api_keys;
 pub mod jwt;
+pub mod keyring;
 pub mod models;
 pub mod handlers;
 pub mod middleware;
+pub mod login_throttle;
+pub mod mfa;
+pub mod password_policy;
 pub mod services;
+pub mod token_policy;
+#[cfg(test)]
+pub mod test_utils;
 
 use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use middleware::auth_middleware;
 
 pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(services::jwks);
     cfg.service(
         web::scope("/auth")
-            .service(services::register)
+            .service(services::bootstrap)
             .service(services::login)
             .service(services::refresh)
+            .service(services::logout)
+            .service(
+                web::scope("/mfa")
+                    .service(services::mfa_enroll)
+                    .service(services::mfa_confirm)
+                    .service(services::mfa_login)
+                    .service(services::mfa_regenerate_recovery_codes)
+                    .service(services::mfa_disable)
+            )
+            .service(
+                web::scope("/password")
+                    .wrap(HttpAuthentication::with_fn(auth_middleware))
+                    .service(services::change_password)
+            )
+            .service(
+                web::scope("/sessions")
+                    .wrap(HttpAuthentication::with_fn(auth_middleware))
+                    .service(services::revoke_user_sessions)
+            )
     );
 }
\ No newline at end of file
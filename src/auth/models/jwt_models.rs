@@ -1,18 +1,53 @@
 use serde::{Serialize, Deserialize};
+use chrono::{Utc, Duration};
+use uuid::Uuid;
 
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
 pub enum TokenType {
     Access,
     Refresh,
+    // Password correcta pero falta el segundo factor: solo sirve en /auth/mfa
+    MfaPending,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Claims {
     pub iss: String,
+    pub aud: String,
     pub sub: String,
     pub exp: usize,
     pub iat: usize,
+    pub jti: String,
+    // Familia de refresh tokens: se mantiene en cada rotación desde el login original
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_id: Option<String>,
     pub user_id: usize,
     pub token_type: TokenType,
     pub role: String,
 }
+
+impl Claims {
+    pub fn new(
+            iss: String, aud: String, sub: String, duration: i64,
+            user_id: usize, role: String,
+            token_type: TokenType) -> Self {
+        let now = Utc::now();
+        Self {
+            iss,
+            aud,
+            sub,
+            exp: (now + Duration::minutes(duration)).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            jti: Uuid::new_v4().to_string(),
+            family_id: None,
+            user_id,
+            token_type,
+            role,
+        }
+    }
+
+    pub fn with_family(mut self, family_id: String) -> Self {
+        self.family_id = Some(family_id);
+        self
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Serialize, Deserialize};
+use chrono::NaiveDateTime;
+use sqlx::{Row, mysql::MySqlRow};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, ToSchema)]
+pub enum LoginAttemptResult {
+    Success,
+    Failure,
+    Locked,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct LoginAttempt {
+    pub id: i32,
+    pub username: String,
+    pub ip_address: String,
+    pub result: LoginAttemptResult,
+    pub cleared: bool,
+    pub attempted_at: NaiveDateTime,
+}
+
+// Fallos recientes (no desbloqueados) de un usuario o una IP
+#[derive(Debug, Default)]
+pub struct FailureStats {
+    pub failures: i64,
+    pub last_failure: Option<NaiveDateTime>,
+}
+
+impl LoginAttemptResult {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginAttemptResult::Success => "Success",
+            LoginAttemptResult::Failure => "Failure",
+            LoginAttemptResult::Locked => "Locked",
+        }
+    }
+}
+
+impl From<String> for LoginAttemptResult {
+    fn from(result: String) -> Self {
+        match result.as_str() {
+            "Success" => LoginAttemptResult::Success,
+            "Locked" => LoginAttemptResult::Locked,
+            _ => LoginAttemptResult::Failure,
+        }
+    }
+}
+
+impl LoginAttempt {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            username: row.get("username"),
+            ip_address: row.get("ip_address"),
+            result: LoginAttemptResult::from(row.get::<String, _>("result")),
+            cleared: row.get::<i8, _>("cleared") != 0,
+            attempted_at: row.get("attempted_at"),
+        }
+    }
+}
+
+impl FailureStats {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            failures: row.get("failures"),
+            last_failure: row.get("last_failure"),
+        }
+    }
+}
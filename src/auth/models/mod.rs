@@ -1,4 +1,6 @@
 pub mod users;
 pub mod requests;
 pub mod responses;
-pub mod jwt_models;
\ No newline at end of file
+pub mod jwt_models;
+pub mod refresh_tokens;
+pub mod login_attempts;
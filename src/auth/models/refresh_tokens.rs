@@ -0,0 +1,37 @@
+use serde::{Serialize, Deserialize};
+use chrono::NaiveDateTime;
+use sqlx::{Row, mysql::MySqlRow};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RefreshToken {
+    pub id: i32,
+    pub jti: String,
+    pub family_id: String,
+    pub user_id: i32,
+    pub token_hash: String,
+    pub expires_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl RefreshToken {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            jti: row.get("jti"),
+            family_id: row.get("family_id"),
+            user_id: row.get("user_id"),
+            token_hash: row.get("token_hash"),
+            expires_at: row.get("expires_at"),
+            used_at: row.get("used_at"),
+            revoked_at: row.get("revoked_at"),
+            created_at: row.get("created_at"),
+        }
+    }
+
+    // Un token ya rotado o revocado que vuelve a presentarse indica robo del token
+    pub fn is_reused(&self) -> bool {
+        self.used_at.is_some() || self.revoked_at.is_some()
+    }
+}
@@ -1,9 +1,25 @@
 use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+use validator::Validate;
+use crate::validation::validate_username;
 use super::users::UserRole;
 
 #[derive(Deserialize, Debug)]
+pub struct BootstrapRequest {
+    pub setup_token: String,
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Deserialize, Debug, ToSchema, Validate)]
+#[schema(example = json!({"username": "profe_juan", "password": "contraseña", "role": "Trainer"}))]
 pub struct RegisterRequest {
+    #[validate(
+        length(min = 3, max = 50, message = "Username must have between 3 and 50 characters"),
+        custom(function = "validate_username"))]
+    #[schema(min_length = 3, max_length = 50, pattern = r"^[A-Za-z0-9._-]+$")]
     pub username: String,
+    // Las reglas de la contraseña dependen de `password_policy`
     pub password: String,
     pub role: UserRole,
 }
@@ -12,4 +28,21 @@ pub struct RegisterRequest {
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MfaCodeRequest {
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MfaLoginRequest {
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
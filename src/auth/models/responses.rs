@@ -4,9 +4,32 @@ use serde::{Serialize, Deserialize};
 pub struct LoginResult {
     pub token: String,
     pub refresh: String,
+    // Segundos de vida del access token, para refrescarlo antes de que venza
+    pub expires_in: i64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RefreshResult{
     pub token: String,
-}
\ No newline at end of file
+    pub refresh: String,
+    // Segundos de vida del access token, para refrescarlo antes de que venza
+    pub expires_in: i64,
+}
+
+// Respuesta del login cuando falta el segundo factor
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MfaChallengeResult {
+    pub mfa_token: String,
+    pub enrollment_required: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MfaEnrollmentResult {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecoveryCodesResult {
+    pub recovery_codes: Vec<String>,
+}
@@ -1,8 +1,9 @@
 use serde::{Serialize, Deserialize};
 use chrono::NaiveDateTime;
-use sqlx::{FromRow, Type};
+use sqlx::{Row, Type, mysql::MySqlRow};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Type)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Type, ToSchema)]
 #[sqlx(type_name = "ENUM('Admin', 'Trainer')")]
 #[sqlx(rename_all = "PascalCase")]
 pub enum UserRole {
@@ -10,16 +11,46 @@ pub enum UserRole {
     Trainer,
 }
 
-#[derive(Serialize, Deserialize, FromRow, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct User {
     pub id: i32,
     pub username: String,
     pub hash: String,
     pub role: UserRole,
+    pub active: bool,
+    pub mfa_secret: Option<String>,
+    pub mfa_enabled: bool,
+    pub mfa_last_step: Option<i64>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
 
+impl User {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            username: row.get("username"),
+            hash: row.get("hash"),
+            role: UserRole::from(row.get::<String, _>("role")),
+            active: row.get::<i8, _>("active") != 0,
+            mfa_secret: row.get("mfa_secret"),
+            mfa_enabled: row.get::<i8, _>("mfa_enabled") != 0,
+            mfa_last_step: row.get("mfa_last_step"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+}
+
+impl UserRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserRole::Admin => "Admin",
+            UserRole::Trainer => "Trainer",
+        }
+    }
+}
+
 impl From<String> for UserRole {
     fn from(role: String) -> Self {
         match role.as_str() {
@@ -28,4 +59,4 @@ impl From<String> for UserRole {
             _ => UserRole::Trainer,
         }
     }
-}
\ No newline at end of file
+}
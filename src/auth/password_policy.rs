@@ -0,0 +1,43 @@
+use serde::Deserialize;
+use crate::errors::AppError;
+
+// Lista local de contraseñas filtradas/comunes, una por línea
+const COMMON_PASSWORDS: &str = include_str!("common_passwords.txt");
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub reject_common: bool,
+    pub reject_username: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            reject_common: true,
+            reject_username: true,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn validate(&self, username: &str, password: &str) -> Result<(), AppError> {
+        let invalid = |message: String| AppError::Validation {
+            message,
+            details: Some(serde_json::json!({ "field": "password" })),
+        };
+        if password.chars().count() < self.min_length {
+            return Err(invalid(format!("Password must be at least {} characters long", self.min_length)));
+        }
+        let lowered = password.to_lowercase();
+        if self.reject_username && lowered == username.to_lowercase() {
+            return Err(invalid("Password must not be equal to the username".to_string()));
+        }
+        if self.reject_common && COMMON_PASSWORDS.lines().any(|common| common.trim() == lowered) {
+            return Err(invalid("Password is too common".to_string()));
+        }
+        Ok(())
+    }
+}
@@ -1,116 +1,1008 @@
-use actix_web::{post, web, HttpResponse};
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
+use actix_web::http::header;
+use chrono::Utc;
+use actix_web_grants::protect;
 use actix_web_httpauth::extractors::bearer::BearerAuth;
 use sqlx::MySqlPool;
-use crate::auth::models::jwt_models::TokenType;
-use crate::auth::models::requests::{RegisterRequest, LoginRequest};
-use crate::auth::models::responses::{LoginResult, RefreshResult};
-use crate::auth::models::users::UserRole;
+use uuid::Uuid;
+use crate::auth::models::jwt_models::{Claims, TokenType};
+use crate::auth::models::requests::{
+    BootstrapRequest, LoginRequest, ChangePasswordRequest, MfaCodeRequest, MfaLoginRequest};
+use crate::auth::models::responses::{
+    LoginResult, RefreshResult, MfaChallengeResult, MfaEnrollmentResult, RecoveryCodesResult};
+use crate::auth::models::users::{User, UserRole};
+use crate::auth::models::login_attempts::LoginAttemptResult;
 use crate::auth::jwt::{generate_token, validate_token};
-use crate::auth::handlers::{create_user_in_db, get_user_by_username, verify_password};
-use jsonwebtoken::errors::ErrorKind;
+use crate::auth::handlers::{
+    get_user_by_username, verify_password, create_first_admin,
+    needs_rehash, update_user_password, DUMMY_PASSWORD_HASH,
+    record_login_attempt, get_username_failures, get_ip_failures, clear_login_failures,
+    store_refresh_token, get_refresh_token_by_jti, mark_refresh_token_used,
+    revoke_token_family, revoke_user_tokens, hash_token,
+    set_mfa_secret, enable_mfa, consume_mfa_step, disable_mfa,
+    replace_recovery_codes, use_recovery_code};
+use crate::auth::mfa::{generate_secret, otpauth_uri, verify_totp, generate_recovery_codes};
+use crate::config::Config;
+use crate::errors::{access_denied, AppError};
 
 
-#[post("/register")]
-pub async fn register(
-    pool: web::Data<MySqlPool>,
-    req: web::Json<RegisterRequest>,
-) -> HttpResponse {
+// El registro de intentos es auditoría: si falla no debe impedir el login
+async fn audit_login_attempt(
+    pool: &MySqlPool,
+    username: &str,
+    ip_address: &str,
+    result: LoginAttemptResult,
+) {
+    if let Err(err) = record_login_attempt(pool, username, ip_address, result).await {
+        tracing::error!("Error recording login attempt: {}", err);
+    }
+}
 
-    let role = match req.role {
-        UserRole::Admin => "Admin",
-        UserRole::Trainer => "Trainer",
-    };
+fn client_ip(http_req: &HttpRequest) -> String {
+    http_req.peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// Backoff exponencial y bloqueo temporal por usuario y por IP
+async fn check_login_throttle(
+    pool: &MySqlPool,
+    config: &Config,
+    username: &str,
+    ip_address: &str,
+) -> Result<(), AppError> {
+    let throttle = &config.login_throttle;
+    let now = Utc::now().naive_utc();
+    let since = throttle.window_start(now);
+
+    let user_failures = get_username_failures(pool, username, since).await?;
+    let ip_failures = get_ip_failures(pool, ip_address, since).await?;
+
+    let retry_after = throttle.retry_after(&user_failures, throttle.max_failures, now)
+        .max(throttle.retry_after(&ip_failures, throttle.max_failures_per_ip, now));
+    if let Some(seconds) = retry_after {
+        tracing::warn!("Login throttled for user: {}, ip: {}", username, ip_address);
+        audit_login_attempt(pool, username, ip_address, LoginAttemptResult::Locked).await;
+        return Err(AppError::TooManyRequests {
+            message: "Too many failed login attempts, try again later".to_string(),
+            retry_after: seconds,
+        })
+    }
+    Ok(())
+}
+
+// Último paso del login (con o sin segundo factor): audita, limpia fallos y emite los tokens
+async fn complete_login(
+    pool: &MySqlPool,
+    config: &Config,
+    user: &User,
+    ip_address: &str,
+) -> Result<HttpResponse, AppError> {
+    audit_login_attempt(pool, &user.username, ip_address, LoginAttemptResult::Success).await;
+    if let Err(err) = clear_login_failures(pool, &user.username).await {
+        tracing::error!("Error clearing login failures: {}", err);
+    }
+
+    let family_id = Uuid::new_v4().to_string();
+    let (token, refresh_token, expires_in) = issue_tokens(
+        pool, config, user.id as usize, &user.username,
+        user.role.as_str(), family_id).await?;
+    tracing::info!("User logged in: {}", user.username);
+    Ok(HttpResponse::Ok().json(LoginResult { token, refresh: refresh_token, expires_in }))
+}
 
-    match create_user_in_db(&pool, &req.username, &req.password, role).await {
-        Ok(_) => {
-            tracing::info!("User created successfully");
-            HttpResponse::Created().body("User created successfully")
+// Valida el bearer token para los endpoints que no pasan por el middleware (ej. /auth/mfa)
+fn bearer_claims(
+    bearer: Option<BearerAuth>,
+    config: &web::Data<Config>,
+    allowed: &[TokenType],
+) -> Result<Claims, AppError> {
+    let Some(bearer) = bearer else {
+        return Err(AppError::Unauthorized("Missing token".to_string()))
+    };
+    match validate_token(bearer.token().to_string(), config.clone()) {
+        Ok(claims) if allowed.contains(&claims.token_type) => Ok(claims),
+        Ok(claims) => {
+            tracing::error!("Invalid token type: {:?}", claims.token_type);
+            Err(AppError::Unauthorized("Invalid token type".to_string()))
         },
-        Err(e) => {
-            tracing::error!("Error creating user: {}", e);
-            HttpResponse::InternalServerError().body("Error creating user")
-        }
+        Err(_) => Err(AppError::Unauthorized("Invalid token".to_string())),
+    }
+}
+
+async fn find_user(pool: &MySqlPool, username: &str) -> Result<User, AppError> {
+    match get_user_by_username(pool, username).await? {
+        Some(user) if user.active => Ok(user),
+        _ => Err(AppError::Unauthorized("Invalid token".to_string())),
+    }
+}
+
+// Genera el par access/refresh y persiste el refresh token dentro de su familia.
+// Devuelve además los segundos de vida del access token
+async fn issue_tokens(
+    pool: &MySqlPool,
+    config: &Config,
+    user_id: usize,
+    username: &str,
+    role: &str,
+    family_id: String,
+) -> Result<(String, String, i64), sqlx::Error> {
+    let lifetime = config.tokens.lifetime(role);
+    let access_claims = Claims::new(
+        config.tokens.issuer.clone(),
+        config.tokens.audience.clone(),
+        username.to_string(),
+        lifetime.access_minutes,
+        user_id,
+        role.to_string(),
+        TokenType::Access,
+    );
+    let refresh_claims = Claims::new(
+        config.tokens.issuer.clone(),
+        config.tokens.audience.clone(),
+        username.to_string(),
+        lifetime.refresh_minutes,
+        user_id,
+        role.to_string(),
+        TokenType::Refresh,
+    ).with_family(family_id);
+
+    let token = generate_token(&access_claims, &config.keyring);
+    let refresh_token = generate_token(&refresh_claims, &config.keyring);
+    store_refresh_token(pool, &refresh_claims, &refresh_token).await?;
+
+    Ok((token, refresh_token, lifetime.access_minutes * 60))
+}
+
+// Crea el primer Admin: solo funciona con el setup token de la config y mientras no exista ningún usuario
+#[post("/bootstrap")]
+pub async fn bootstrap(
+    pool: web::Data<MySqlPool>,
+    req: web::Json<BootstrapRequest>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let Some(setup_token) = data.setup_token.as_deref() else {
+        tracing::error!("Bootstrap attempted without setup token configured");
+        return Err(AppError::Forbidden("Bootstrap is disabled".to_string()))
+    };
+    if hash_token(setup_token) != hash_token(&req.setup_token) {
+        tracing::error!("Bootstrap attempted with invalid setup token");
+        return Err(AppError::Forbidden("Invalid setup token".to_string()))
     }
+
+    data.password_policy.validate(&req.username, &req.password)?;
+
+    if !create_first_admin(&pool, &req.username, &req.password).await? {
+        tracing::error!("Bootstrap attempted but users already exist");
+        return Err(AppError::Conflict("Bootstrap already completed".to_string()))
+    }
+    tracing::info!("Bootstrap admin created: {}", req.username);
+    Ok(HttpResponse::Created().body("User created successfully"))
 }
 
 #[post("/login")]
 async fn login(
     pool: web::Data<MySqlPool>,
+    http_req: HttpRequest,
     req: web::Json<LoginRequest>,
-    data: web::Data<crate::config::Config>,
-) -> HttpResponse {
-
-    match get_user_by_username(&pool, &req.username).await {
-        Ok(Some(user)) => {
-            if verify_password(&req.password, &user.hash) {
-                let token = generate_token(
-                    "Gym_Helper".to_string(),
-                    user.username.clone(),
-                    30,
-                    user.id as usize,
-                    format!("{:?}", user.role),
-                    TokenType::Access,
-                    data.jwt_secret.clone(),
-                );
-                let refresh_token = generate_token(
-                    "Gym_Helper".to_string(),
-                    user.username.clone(),
-                    1440,
-                    user.id as usize,
-                    format!("{:?}", user.role),
-                    TokenType::Refresh,
-                    data.jwt_secret.clone(),
-                );
-                let response = LoginResult {
-                    token: token,
-                    refresh: refresh_token,
-                };
-                tracing::info!("User logged in: {}", req.username);
-                HttpResponse::Ok().json(response)
-            } else {
-                tracing::info!("Invalid password for user: {}", req.username);
-                HttpResponse::Unauthorized().body("Invalid password")
-            }
-        },
-        Ok(None) => {
-            tracing::error!("User not found: {}", req.username);
-            HttpResponse::Unauthorized().body("Invalid username or password")
-        },
-        Err(err) => {
-            tracing::error!("Error fetching user: {}", err);
-            HttpResponse::InternalServerError().body("Error fetching user")
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let ip_address = client_ip(&http_req);
+    check_login_throttle(&pool, &data, &req.username, &ip_address).await?;
+
+    let user = get_user_by_username(&pool, &req.username).await?;
+
+    // Siempre se verifica un hash para que usuario inexistente y contraseña incorrecta tarden lo mismo
+    let hash = user.as_ref().map_or(DUMMY_PASSWORD_HASH, |user| user.hash.as_str());
+    let password_ok = verify_password(&req.password, hash);
+    let user = match user {
+        Some(user) if password_ok && user.active => user,
+        _ => {
+            tracing::info!("Failed login for user: {}, ip: {}", req.username, ip_address);
+            audit_login_attempt(&pool, &req.username, &ip_address, LoginAttemptResult::Failure).await;
+            return Err(AppError::Unauthorized("Invalid username or password".to_string()))
+        }
+    };
+
+    if needs_rehash(&user.hash) {
+        match update_user_password(&pool, user.id, &req.password).await {
+            Ok(_) => tracing::info!("Password hash upgraded for user: {}", user.username),
+            Err(err) => tracing::error!("Error upgrading password hash: {}", err),
         }
     }
+
+    // Con segundo factor los fallos no se limpian hasta validar el código, así no se puede
+    // alternar password correcta y códigos TOTP para esquivar el bloqueo
+    let enrollment_required = !user.mfa_enabled
+        && data.mfa.require_for_admins
+        && user.role == UserRole::Admin;
+    if user.mfa_enabled || enrollment_required {
+        let claims = Claims::new(
+            data.tokens.issuer.clone(),
+            data.tokens.audience.clone(),
+            user.username.clone(),
+            data.mfa.pending_token_minutes,
+            user.id as usize,
+            user.role.as_str().to_string(),
+            TokenType::MfaPending,
+        );
+        tracing::info!("MFA required for user: {}", user.username);
+        return Ok(HttpResponse::Ok().json(MfaChallengeResult {
+            mfa_token: generate_token(&claims, &data.keyring),
+            enrollment_required,
+        }))
+    }
+
+    complete_login(&pool, &data, &user, &ip_address).await
 }
 
 #[post("/refresh_token")]
 pub async fn refresh(
+        pool: web::Data<MySqlPool>,
         refresh_jwt: Option<BearerAuth>,
-        data: web::Data<crate::config::Config>) -> HttpResponse {
+        data: web::Data<Config>) -> Result<HttpResponse, AppError> {
     let Some(refresh_jwt) = refresh_jwt else {
-        return HttpResponse::Forbidden().body("Missing refresh token")
-    };
-
-    validate_token(refresh_jwt.token().to_string(), data.clone())
-        .and_then(|claims| {
-            if claims.token_type == TokenType::Refresh {
-                let new_token = generate_token(
-                    claims.iss,
-                    claims.sub.clone(),
-                    30,
-                    claims.user_id,
-                    claims.role,
-                    TokenType::Access,
-                    data.jwt_secret.clone(),
-                );
-                tracing::info!("New token generated for user: {}", claims.sub);
-                Ok(HttpResponse::Ok().json(RefreshResult{token: new_token}))
-            } else {
-                Err(jsonwebtoken::errors::Error::from(ErrorKind::InvalidToken))
-            }
-        })
-        .unwrap_or_else(|err| {
+        return Err(AppError::Unauthorized("Missing refresh token".to_string()))
+    };
+    let token = refresh_jwt.token();
+
+    let claims = match validate_token(token.to_string(), data.clone()) {
+        Ok(claims) if claims.token_type == TokenType::Refresh => claims,
+        Ok(claims) => {
+            tracing::error!("Invalid token type for refresh: {:?}", claims.token_type);
+            return Err(AppError::Unauthorized("Invalid refresh token".to_string()))
+        },
+        Err(err) => {
             tracing::error!("Error generating new token: {}", err);
-            HttpResponse::Unauthorized().body("Error generating new token")
-        })
+            return Err(AppError::Unauthorized("Invalid refresh token".to_string()))
+        }
+    };
+
+    let stored = match get_refresh_token_by_jti(&pool, &claims.jti).await? {
+        Some(stored) if stored.token_hash == hash_token(token) => stored,
+        _ => {
+            tracing::error!("Unknown refresh token for user: {}", claims.sub);
+            return Err(AppError::Unauthorized("Invalid refresh token".to_string()))
+        }
+    };
+
+    // El rol y el estado se toman de la base: un usuario desactivado o con rol
+    // cambiado no conserva los permisos del token anterior
+    let user = find_user(&pool, &claims.sub).await?;
+
+    // Reuso de un token ya rotado: se revoca toda la familia por posible robo
+    let rotated = !stored.is_reused()
+        && mark_refresh_token_used(&pool, &stored.jti).await.unwrap_or(false);
+    if !rotated {
+        tracing::warn!("Refresh token reuse detected for user: {}, family: {}", claims.sub, stored.family_id);
+        if let Err(err) = revoke_token_family(&pool, &stored.family_id).await {
+            tracing::error!("Error revoking token family: {}", err);
+        }
+        return Err(AppError::Unauthorized("Refresh token reuse detected".to_string()))
+    }
+
+    let (token, refresh_token, expires_in) = issue_tokens(
+        &pool, &data, user.id as usize, &user.username, user.role.as_str(), stored.family_id).await?;
+    tracing::info!("New token generated for user: {}", user.username);
+    Ok(HttpResponse::Ok().json(RefreshResult { token, refresh: refresh_token, expires_in }))
+}
+
+#[post("/logout")]
+pub async fn logout(
+        pool: web::Data<MySqlPool>,
+        refresh_jwt: Option<BearerAuth>,
+        data: web::Data<Config>) -> Result<HttpResponse, AppError> {
+    let Some(refresh_jwt) = refresh_jwt else {
+        return Err(AppError::Unauthorized("Missing refresh token".to_string()))
+    };
+
+    let family_id = match validate_token(refresh_jwt.token().to_string(), data) {
+        Ok(Claims { token_type: TokenType::Refresh, family_id: Some(family_id), .. }) => family_id,
+        Ok(_) | Err(_) => {
+            tracing::error!("Invalid refresh token on logout");
+            return Err(AppError::Unauthorized("Invalid refresh token".to_string()))
+        }
+    };
+
+    revoke_token_family(&pool, &family_id).await?;
+    tracing::info!("Session closed, family: {}", family_id);
+    Ok(HttpResponse::Ok().body("Logged out successfully"))
+}
+
+#[put("")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn change_password(
+    pool: web::Data<MySqlPool>,
+    claims: web::ReqData<Claims>,
+    req: web::Json<ChangePasswordRequest>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let user = get_user_by_username(&pool, &claims.sub).await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    if !verify_password(&req.current_password, &user.hash) {
+        tracing::info!("Invalid current password for user: {}", user.username);
+        return Err(AppError::Unauthorized("Invalid current password".to_string()))
+    }
+    if req.current_password == req.new_password {
+        return Err(AppError::validation("New password must be different from the current one"))
+    }
+    data.password_policy.validate(&user.username, &req.new_password)?;
+
+    update_user_password(&pool, user.id, &req.new_password).await?;
+    // El resto de las sesiones abiertas deben volver a loguearse
+    if let Err(err) = revoke_user_tokens(&pool, user.id).await {
+        tracing::error!("Error revoking sessions: {}", err);
+    }
+    tracing::info!("Password changed for user: {}", user.username);
+    Ok(HttpResponse::Ok().body("Password changed successfully"))
+}
+
+// Endpoint para admins: cierra todas las sesiones de un usuario (ej. celular robado)
+#[delete("/{user_id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn revoke_user_sessions(
+    pool: web::Data<MySqlPool>,
+    user_id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let user_id = user_id.into_inner();
+    let result = revoke_user_tokens(&pool, user_id).await?;
+    tracing::info!("Revoked {} refresh tokens for user: {}", result.rows_affected(), user_id);
+    Ok(HttpResponse::Ok().body("Sessions revoked successfully"))
+}
+
+// Claves públicas (RS256/EdDSA) para que otros servicios validen los tokens sin compartir secretos
+#[get("/.well-known/jwks.json")]
+pub async fn jwks(data: web::Data<Config>) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "public, max-age=300"))
+        .json(data.keyring.jwks(Utc::now()))
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// MFA ENDPOINTS ///////////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+// Inicia el enrolamiento TOTP: acepta un access token o el token pendiente del login (Admin obligado a enrolar)
+#[post("/enroll")]
+pub async fn mfa_enroll(
+    pool: web::Data<MySqlPool>,
+    bearer: Option<BearerAuth>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let claims = bearer_claims(bearer, &data, &[TokenType::Access, TokenType::MfaPending])?;
+    let user = find_user(&pool, &claims.sub).await?;
+    if user.mfa_enabled {
+        return Err(AppError::Conflict("MFA already enabled".to_string()))
+    }
+
+    let secret = generate_secret();
+    set_mfa_secret(&pool, user.id, &secret).await?;
+    tracing::info!("MFA enrollment started for user: {}", user.username);
+    Ok(HttpResponse::Ok().json(MfaEnrollmentResult {
+        otpauth_uri: otpauth_uri(&data.mfa.issuer, &user.username, &secret),
+        secret,
+    }))
+}
+
+// Confirma el enrolamiento con un primer código y entrega los códigos de recuperación (una única vez)
+#[post("/confirm")]
+pub async fn mfa_confirm(
+    pool: web::Data<MySqlPool>,
+    bearer: Option<BearerAuth>,
+    req: web::Json<MfaCodeRequest>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let claims = bearer_claims(bearer, &data, &[TokenType::Access, TokenType::MfaPending])?;
+    let user = find_user(&pool, &claims.sub).await?;
+    if user.mfa_enabled {
+        return Err(AppError::Conflict("MFA already enabled".to_string()))
+    }
+    let Some(secret) = user.mfa_secret.as_deref() else {
+        return Err(AppError::BadRequest("MFA enrollment not started".to_string()))
+    };
+    let Some(step) = verify_totp(secret, &req.code, Utc::now().timestamp()) else {
+        tracing::info!("Invalid MFA confirmation code for user: {}", user.username);
+        return Err(AppError::Unauthorized("Invalid MFA code".to_string()))
+    };
+
+    let recovery_codes = generate_recovery_codes();
+    enable_mfa(&pool, user.id, step).await?;
+    replace_recovery_codes(&pool, user.id, &recovery_codes).await?;
+    tracing::info!("MFA enabled for user: {}", user.username);
+    Ok(HttpResponse::Ok().json(RecoveryCodesResult { recovery_codes }))
+}
+
+// Segundo paso del login: canjea el token pendiente y un código TOTP o de recuperación por los tokens reales
+#[post("/login")]
+pub async fn mfa_login(
+    pool: web::Data<MySqlPool>,
+    http_req: HttpRequest,
+    bearer: Option<BearerAuth>,
+    req: web::Json<MfaLoginRequest>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let claims = bearer_claims(bearer, &data, &[TokenType::MfaPending])?;
+    let ip_address = client_ip(&http_req);
+    check_login_throttle(&pool, &data, &claims.sub, &ip_address).await?;
+    let user = find_user(&pool, &claims.sub).await?;
+    let Some(secret) = user.mfa_secret.as_deref().filter(|_| user.mfa_enabled) else {
+        return Err(AppError::Forbidden("MFA enrollment required".to_string()))
+    };
+
+    let verified = match (&req.code, &req.recovery_code) {
+        (Some(code), _) => match verify_totp(secret, code, Utc::now().timestamp()) {
+            Some(step) => consume_mfa_step(&pool, user.id, step).await?,
+            None => false,
+        },
+        (None, Some(recovery_code)) => use_recovery_code(&pool, user.id, recovery_code).await?,
+        (None, None) => return Err(AppError::validation("Missing MFA code")),
+    };
+    if !verified {
+        tracing::info!("Invalid MFA code for user: {}, ip: {}", user.username, ip_address);
+        audit_login_attempt(&pool, &user.username, &ip_address, LoginAttemptResult::Failure).await;
+        return Err(AppError::Unauthorized("Invalid MFA code".to_string()))
+    }
+    complete_login(&pool, &data, &user, &ip_address).await
+}
+
+#[post("/recovery_codes")]
+pub async fn mfa_regenerate_recovery_codes(
+    pool: web::Data<MySqlPool>,
+    bearer: Option<BearerAuth>,
+    req: web::Json<MfaCodeRequest>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let claims = bearer_claims(bearer, &data, &[TokenType::Access])?;
+    let user = find_user(&pool, &claims.sub).await?;
+    let Some(secret) = user.mfa_secret.as_deref().filter(|_| user.mfa_enabled) else {
+        return Err(AppError::BadRequest("MFA is not enabled".to_string()))
+    };
+    let verified = match verify_totp(secret, &req.code, Utc::now().timestamp()) {
+        Some(step) => consume_mfa_step(&pool, user.id, step).await?,
+        None => false,
+    };
+    if !verified {
+        return Err(AppError::Unauthorized("Invalid MFA code".to_string()))
+    }
+
+    let recovery_codes = generate_recovery_codes();
+    replace_recovery_codes(&pool, user.id, &recovery_codes).await?;
+    tracing::info!("Recovery codes regenerated for user: {}", user.username);
+    Ok(HttpResponse::Ok().json(RecoveryCodesResult { recovery_codes }))
+}
+
+#[post("/disable")]
+pub async fn mfa_disable(
+    pool: web::Data<MySqlPool>,
+    bearer: Option<BearerAuth>,
+    req: web::Json<MfaCodeRequest>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let claims = bearer_claims(bearer, &data, &[TokenType::Access])?;
+    let user = find_user(&pool, &claims.sub).await?;
+    if data.mfa.require_for_admins && user.role == UserRole::Admin {
+        return Err(AppError::Forbidden("MFA is required for admins".to_string()))
+    }
+    let Some(secret) = user.mfa_secret.as_deref().filter(|_| user.mfa_enabled) else {
+        return Err(AppError::BadRequest("MFA is not enabled".to_string()))
+    };
+    // El código se consume como en el login: uno ya usado no sirve para desactivar el 2FA
+    let verified = match verify_totp(secret, &req.code, Utc::now().timestamp()) {
+        Some(step) => consume_mfa_step(&pool, user.id, step).await?,
+        None => false,
+    };
+    if !verified {
+        return Err(AppError::Unauthorized("Invalid MFA code".to_string()))
+    }
+
+    disable_mfa(&pool, user.id).await?;
+    tracing::info!("MFA disabled for user: {}", user.username);
+    Ok(HttpResponse::Ok().body("MFA disabled successfully"))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{web, http::StatusCode};
+    use actix_web::test::{call_service, TestRequest};
+    use chrono::Utc;
+    use crate::auth::handlers::{hash_token, needs_rehash};
+    use crate::auth::password_policy::PasswordPolicy;
+    use crate::auth::login_throttle::LoginThrottle;
+    use crate::auth::models::login_attempts::FailureStats;
+    use crate::auth::handlers::DUMMY_PASSWORD_HASH;
+    use crate::auth::jwt::{generate_token, validate_token};
+    use crate::auth::models::jwt_models::{Claims, TokenType};
+    use crate::auth::models::refresh_tokens::RefreshToken;
+    use crate::auth::test_utils::{bearer, init_app, test_config};
+    use crate::auth::token_policy::{TokenLifetime, TokenPolicy};
+    use crate::auth::keyring::{JwtAlgorithm, JwtKeyConfig, JwtKeysConfig, Keyring};
+    use crate::auth::mfa::{
+        totp_code, verify_totp, otpauth_uri, generate_secret,
+        generate_recovery_codes, normalize_recovery_code};
+
+    // Helper function para armar la config de una clave de prueba
+    fn key_config(kid: &str, algorithm: JwtAlgorithm, retired_hours_ago: Option<i64>) -> JwtKeyConfig {
+        let test_key = |name: &str| Some(format!("{}/src/auth/test_keys/{}", env!("CARGO_MANIFEST_DIR"), name));
+        let (secret, private_key_path, public_key_path) = match algorithm {
+            JwtAlgorithm::HS512 => (Some(format!("{}-secret", kid)), None, None),
+            JwtAlgorithm::RS256 => (None, test_key("rsa_private.pem"), test_key("rsa_public.pem")),
+            JwtAlgorithm::EdDSA => (None, test_key("ed25519_private.pem"), test_key("ed25519_public.pem")),
+        };
+        JwtKeyConfig {
+            kid: kid.to_string(),
+            algorithm,
+            secret,
+            private_key_path,
+            public_key_path,
+            retired_at: retired_hours_ago.map(|hours| Utc::now() - chrono::Duration::hours(hours)),
+        }
+    }
+
+    // Helper function para crear una config con el keyring indicado
+    fn config_with_keys(active_kid: &str, keys: Vec<JwtKeyConfig>) -> crate::config::Config {
+        let mut config = test_config();
+        config.jwt_keys = JwtKeysConfig {
+            active_kid: Some(active_kid.to_string()),
+            keys,
+            ..Default::default()
+        };
+        config.keyring = Keyring::load(&config.jwt_keys, &config.jwt_secret).unwrap();
+        config
+    }
+
+    // Helper function para crear un refresh token persistido de prueba
+    fn create_test_refresh_token() -> RefreshToken {
+        let now = Utc::now().naive_utc();
+        RefreshToken {
+            id: 1,
+            jti: "jti-1".to_string(),
+            family_id: "family-1".to_string(),
+            user_id: 1,
+            token_hash: hash_token("token"),
+            expires_at: now,
+            used_at: None,
+            revoked_at: None,
+            created_at: now,
+        }
+    }
+
+    #[test]
+    fn test_claims_have_unique_jti() {
+        let first = Claims::new("Gym_Helper".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Refresh);
+        let second = Claims::new("Gym_Helper".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Refresh);
+        assert_ne!(first.jti, second.jti);
+        assert!(first.family_id.is_none());
+    }
+
+    #[test]
+    fn test_family_id_survives_round_trip() {
+        let claims = Claims::new("Gym_Helper".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Refresh)
+            .with_family("family-1".to_string());
+        let token = generate_token(&claims, &test_config().keyring);
+        let decoded = validate_token(token, web::Data::new(test_config())).unwrap();
+        assert_eq!(decoded.jti, claims.jti);
+        assert_eq!(decoded.family_id, Some("family-1".to_string()));
+    }
+
+    #[test]
+    fn test_hash_token_is_deterministic() {
+        assert_eq!(hash_token("token"), hash_token("token"));
+        assert_ne!(hash_token("token"), hash_token("other"));
+        assert_eq!(hash_token("token").len(), 64);
+    }
+
+    #[test]
+    fn test_refresh_token_reuse_detection() {
+        let mut stored = create_test_refresh_token();
+        assert!(!stored.is_reused());
+
+        stored.used_at = Some(Utc::now().naive_utc());
+        assert!(stored.is_reused());
+
+        stored.used_at = None;
+        stored.revoked_at = Some(Utc::now().naive_utc());
+        assert!(stored.is_reused());
+    }
+
+    #[test]
+    fn test_password_policy_min_length() {
+        let policy = PasswordPolicy::default();
+        assert!(policy.validate("profe", "corta").is_err());
+        assert!(policy.validate("profe", "una frase bastante larga").is_ok());
+    }
+
+    #[test]
+    fn test_password_policy_rejects_username_and_common_passwords() {
+        let policy = PasswordPolicy::default();
+        assert!(policy.validate("profe_juan", "PROFE_JUAN").is_err());
+        assert!(policy.validate("profe", "password123").is_err());
+        assert!(policy.validate("profe", "Contraseña").is_err());
+        assert!(policy.validate("profe", "").is_err());
+    }
+
+    #[test]
+    fn test_password_policy_can_be_relaxed() {
+        let policy = PasswordPolicy {
+            min_length: 4,
+            reject_common: false,
+            reject_username: false,
+        };
+        assert!(policy.validate("admin", "admin").is_ok());
+        assert!(policy.validate("profe", "1234").is_ok());
+    }
+
+    #[test]
+    fn test_needs_rehash_detects_old_params() {
+        // Hash generado con Argon2::default() (m=19456, t=2, p=1)
+        let old_hash = "$argon2id$v=19$m=19456,t=2,p=1$c29tZXNhbHQ$AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+        let current_hash = "$argon2id$v=19$m=65536,t=3,p=1$c29tZXNhbHQ$AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+        let argon2i_hash = "$argon2i$v=19$m=65536,t=3,p=1$c29tZXNhbHQ$AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+
+        assert!(needs_rehash(old_hash));
+        assert!(!needs_rehash(current_hash));
+        assert!(needs_rehash(argon2i_hash));
+        assert!(needs_rehash("not a hash"));
+    }
+
+    #[test]
+    fn test_dummy_hash_uses_current_params() {
+        // Si cambian los parámetros de argon2 el hash ficticio debe actualizarse para igualar tiempos
+        assert!(!needs_rehash(DUMMY_PASSWORD_HASH));
+    }
+
+    #[test]
+    fn test_login_throttle_exponential_backoff() {
+        let throttle = LoginThrottle::default();
+        assert_eq!(throttle.required_wait(0, 5).num_seconds(), 0);
+        assert_eq!(throttle.required_wait(1, 5).num_seconds(), 1);
+        assert_eq!(throttle.required_wait(2, 5).num_seconds(), 2);
+        assert_eq!(throttle.required_wait(4, 5).num_seconds(), 8);
+        assert_eq!(throttle.required_wait(5, 5).num_minutes(), 15);
+        assert_eq!(throttle.required_wait(19, 20).num_seconds(), 60);
+    }
+
+    #[test]
+    fn test_login_throttle_retry_after() {
+        let throttle = LoginThrottle::default();
+        let now = Utc::now().naive_utc();
+
+        let no_failures = FailureStats::default();
+        assert!(throttle.retry_after(&no_failures, 5, now).is_none());
+
+        let locked = FailureStats { failures: 5, last_failure: Some(now - chrono::Duration::minutes(1)) };
+        let seconds = throttle.retry_after(&locked, 5, now).unwrap();
+        assert!(seconds > 13 * 60 && seconds <= 14 * 60);
+
+        let expired = FailureStats { failures: 5, last_failure: Some(now - chrono::Duration::minutes(16)) };
+        assert!(throttle.retry_after(&expired, 5, now).is_none());
+    }
+
+    #[actix_web::test]
+    async fn test_change_password_requires_token() {
+        let app = init_app(crate::auth::routes).await;
+
+        let req = TestRequest::put()
+            .uri("/auth/password")
+            .set_json(serde_json::json!({"current_password": "old", "new_password": "una frase bastante larga"}))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_refresh_with_access_token_is_unauthorized() {
+        let app = init_app(crate::auth::routes).await;
+
+        let req = TestRequest::post()
+            .uri("/auth/refresh_token")
+            .insert_header(bearer("Admin", TokenType::Access))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_logout_without_refresh_token_is_unauthorized() {
+        let app = init_app(crate::auth::routes).await;
+
+        let req = TestRequest::post().uri("/auth/logout").to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = TestRequest::post()
+            .uri("/auth/logout")
+            .insert_header(bearer("Trainer", TokenType::Access))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_bootstrap_with_invalid_setup_token_is_forbidden() {
+        let app = init_app(crate::auth::routes).await;
+
+        let req = TestRequest::post()
+            .uri("/auth/bootstrap")
+            .set_json(serde_json::json!({"setup_token": "wrong", "username": "admin", "password": "secret"}))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_web::test]
+    async fn test_register_is_no_longer_public() {
+        let app = init_app(crate::auth::routes).await;
+
+        let req = TestRequest::post()
+            .uri("/auth/register")
+            .set_json(serde_json::json!({"username": "intruder", "password": "secret", "role": "Admin"}))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_trainer_cannot_revoke_sessions() {
+        let app = init_app(crate::auth::routes).await;
+
+        let req = TestRequest::delete().uri("/auth/sessions/1").to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = TestRequest::delete()
+            .uri("/auth/sessions/1")
+            .insert_header(bearer("Trainer", TokenType::Access))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_totp_rfc6238_vectors() {
+        // Vectores del RFC 6238 (SHA1, 8 dígitos) truncados a 6 dígitos
+        let secret = b"12345678901234567890";
+        assert_eq!(totp_code(secret, 59 / 30), 287082);
+        assert_eq!(totp_code(secret, 1111111109 / 30), 81804);
+        assert_eq!(totp_code(secret, 1234567890 / 30), 5924);
+    }
+
+    #[test]
+    fn test_verify_totp_accepts_clock_skew() {
+        let secret = base32::encode(base32::Alphabet::Rfc4648 { padding: false }, b"12345678901234567890");
+        assert_eq!(verify_totp(&secret, "287082", 59), Some(1));
+        // Un paso antes o después sigue siendo válido
+        assert_eq!(verify_totp(&secret, "287082", 89), Some(1));
+        assert_eq!(verify_totp(&secret, "287082", 149), None);
+        assert_eq!(verify_totp(&secret, "081804", 1111111109), Some(1111111109 / 30));
+        assert_eq!(verify_totp(&secret, "81804", 1111111109), None);
+        assert_eq!(verify_totp(&secret, "abcdef", 59), None);
+    }
+
+    #[test]
+    fn test_generated_secret_round_trips() {
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        let bytes = base32::decode(base32::Alphabet::Rfc4648 { padding: false }, &secret).unwrap();
+        let code = format!("{:06}", totp_code(&bytes, 1000));
+        assert_eq!(verify_totp(&secret, &code, 1000 * 30), Some(1000));
+    }
+
+    #[test]
+    fn test_otpauth_uri_format() {
+        let uri = otpauth_uri("Gym Helper", "admin@gym", "ABC");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Gym%20Helper:admin%40gym?secret=ABC&issuer=Gym%20Helper&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn test_recovery_codes_are_unique_and_normalized() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), 10);
+        let unique: std::collections::HashSet<_> = codes.iter().collect();
+        assert_eq!(unique.len(), codes.len());
+        assert!(codes.iter().all(|code| code.len() == 11 && code.chars().nth(5) == Some('-')));
+        assert_eq!(normalize_recovery_code(" AbCdE-12345 "), "abcde12345");
+    }
+
+    #[actix_web::test]
+    async fn test_mfa_endpoints_require_token() {
+        let app = init_app(crate::auth::routes).await;
+
+        for uri in ["/auth/mfa/enroll", "/auth/mfa/confirm", "/auth/mfa/login", "/auth/mfa/disable"] {
+            let req = TestRequest::post()
+                .uri(uri)
+                .set_json(serde_json::json!({"code": "123456"}))
+                .to_request();
+            let resp = call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED, "{}", uri);
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_mfa_login_rejects_access_token() {
+        let app = init_app(crate::auth::routes).await;
+
+        let req = TestRequest::post()
+            .uri("/auth/mfa/login")
+            .insert_header(bearer("Admin", TokenType::Access))
+            .set_json(serde_json::json!({"code": "123456"}))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn test_mfa_pending_token_is_rejected_by_middleware() {
+        // El token pendiente de segundo factor no da acceso a la API
+        let app = init_app(crate::auth::routes).await;
+
+        let req = TestRequest::put()
+            .uri("/auth/password")
+            .insert_header(bearer("Admin", TokenType::MfaPending))
+            .set_json(serde_json::json!({"current_password": "old", "new_password": "una frase bastante larga"}))
+            .to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn test_tokens_carry_kid_of_active_key() {
+        let claims = Claims::new("Gym_Helper".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Access);
+        let token = generate_token(&claims, &test_config().keyring);
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert_eq!(header.kid.as_deref(), Some("default"));
+        assert_eq!(header.alg, jsonwebtoken::Algorithm::HS512);
+    }
+
+    #[test]
+    fn test_tokens_without_kid_use_default_key() {
+        // Tokens emitidos antes del keyring
+        let claims = Claims::new("Gym_Helper".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Access);
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS512),
+            &claims,
+            &jsonwebtoken::EncodingKey::from_secret(test_config().jwt_secret.as_bytes()),
+        ).unwrap();
+        assert!(validate_token(token, web::Data::new(test_config())).is_ok());
+    }
+
+    #[test]
+    fn test_asymmetric_keys_sign_and_verify() {
+        for (kid, algorithm) in [("rsa-1", JwtAlgorithm::RS256), ("ed-1", JwtAlgorithm::EdDSA)] {
+            let config = config_with_keys(kid, vec![key_config(kid, algorithm, None)]);
+            let claims = Claims::new("Gym_Helper".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Access);
+            let token = generate_token(&claims, &config.keyring);
+            assert_eq!(jsonwebtoken::decode_header(&token).unwrap().kid.as_deref(), Some(kid));
+            assert_eq!(validate_token(token, web::Data::new(config)).unwrap().sub, "user");
+        }
+    }
+
+    #[test]
+    fn test_retired_key_accepted_during_grace_period() {
+        let claims = Claims::new("Gym_Helper".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Access);
+        let old = config_with_keys("old", vec![key_config("old", JwtAlgorithm::HS512, None)]);
+        let token = generate_token(&claims, &old.keyring);
+
+        let rotated = config_with_keys("new", vec![
+            key_config("new", JwtAlgorithm::EdDSA, None),
+            key_config("old", JwtAlgorithm::HS512, Some(1)),
+        ]);
+        assert!(validate_token(token.clone(), web::Data::new(rotated)).is_ok());
+
+        let expired = config_with_keys("new", vec![
+            key_config("new", JwtAlgorithm::EdDSA, None),
+            key_config("old", JwtAlgorithm::HS512, Some(25)),
+        ]);
+        assert!(validate_token(token, web::Data::new(expired)).is_err());
+    }
+
+    #[test]
+    fn test_unknown_kid_is_rejected() {
+        let claims = Claims::new("Gym_Helper".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Access);
+        let other = config_with_keys("other", vec![key_config("other", JwtAlgorithm::HS512, None)]);
+        let token = generate_token(&claims, &other.keyring);
+        assert!(validate_token(token, web::Data::new(test_config())).is_err());
+    }
+
+    #[test]
+    fn test_keyring_rejects_invalid_configs() {
+        let keys = |keys: Vec<JwtKeyConfig>, active: &str| JwtKeysConfig {
+            active_kid: Some(active.to_string()),
+            keys,
+            ..Default::default()
+        };
+
+        let retired = keys(vec![key_config("a", JwtAlgorithm::HS512, Some(1))], "a");
+        assert!(Keyring::load(&retired, "").is_err());
+
+        let duplicated = keys(vec![
+            key_config("a", JwtAlgorithm::HS512, None),
+            key_config("a", JwtAlgorithm::RS256, None),
+        ], "a");
+        assert!(Keyring::load(&duplicated, "").is_err());
+
+        let mut public_only = key_config("a", JwtAlgorithm::EdDSA, None);
+        public_only.private_key_path = None;
+        assert!(Keyring::load(&keys(vec![public_only], "a"), "").is_err());
+
+        let missing = keys(vec![key_config("a", JwtAlgorithm::HS512, None)], "b");
+        assert!(Keyring::load(&missing, "").is_err());
+    }
+
+    #[test]
+    fn test_jwks_publishes_only_public_keys() {
+        let config = config_with_keys("ed-1", vec![
+            key_config("ed-1", JwtAlgorithm::EdDSA, None),
+            key_config("rsa-1", JwtAlgorithm::RS256, Some(1)),
+            key_config("rsa-old", JwtAlgorithm::RS256, Some(48)),
+            key_config("hs-1", JwtAlgorithm::HS512, Some(1)),
+        ]);
+        let jwks = serde_json::to_value(config.keyring.jwks(Utc::now())).unwrap();
+        let keys = jwks["keys"].as_array().unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0]["kid"], "ed-1");
+        assert_eq!(keys[0]["kty"], "OKP");
+        assert_eq!(keys[0]["crv"], "Ed25519");
+        assert_eq!(keys[1]["kid"], "rsa-1");
+        assert_eq!(keys[1]["kty"], "RSA");
+        assert_eq!(keys[1]["e"], "AQAB");
+
+        // Otro servicio puede validar con la JWK publicada
+        let claims = Claims::new("Gym_Helper".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Access);
+        let token = generate_token(&claims, &config.keyring);
+        let jwk = config.keyring.jwks(Utc::now()).find("ed-1").cloned().unwrap();
+        let key = jsonwebtoken::DecodingKey::from_jwk(&jwk).unwrap();
+        let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::EdDSA);
+        validation.set_audience(&["gym_helper_api"]);
+        assert!(jsonwebtoken::decode::<Claims>(&token, &key, &validation).is_ok());
+    }
+
+    #[actix_web::test]
+    async fn test_jwks_endpoint_is_public() {
+        let app = init_app(crate::auth::routes).await;
+
+        let req = TestRequest::get().uri("/.well-known/jwks.json").to_request();
+        let resp = call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_token_lifetime_per_role() {
+        let mut policy = TokenPolicy::default();
+        policy.roles.insert("Trainer".to_string(), TokenLifetime { access_minutes: 480, refresh_minutes: 43200 });
+
+        assert_eq!(policy.lifetime("Trainer"), TokenLifetime { access_minutes: 480, refresh_minutes: 43200 });
+        assert_eq!(policy.lifetime("Admin"), TokenLifetime { access_minutes: 30, refresh_minutes: 1440 });
+    }
+
+    #[test]
+    fn test_grace_period_covers_longest_refresh_token() {
+        let mut policy = TokenPolicy::default();
+        let mut keys = JwtKeysConfig::default();
+        assert!(keys.check_grace_period(&policy).is_ok());
+
+        // Un refresh de 30 días para Trainer no entra en las 24 horas de gracia
+        policy.roles.insert("Trainer".to_string(), TokenLifetime { access_minutes: 480, refresh_minutes: 43200 });
+        assert_eq!(policy.max_refresh_minutes(), 43200);
+        assert!(keys.check_grace_period(&policy).is_err());
+
+        keys.grace_period_hours = 720;
+        assert!(keys.check_grace_period(&policy).is_ok());
+    }
+
+    #[test]
+    fn test_validate_token_checks_issuer_and_audience() {
+        let config = test_config();
+        let valid = Claims::new("Gym_Helper".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Access);
+        assert!(validate_token(generate_token(&valid, &config.keyring), web::Data::new(test_config())).is_ok());
+
+        let wrong_issuer = Claims::new("other".to_string(), "gym_helper_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Access);
+        assert!(validate_token(generate_token(&wrong_issuer, &config.keyring), web::Data::new(test_config())).is_err());
+
+        let wrong_audience = Claims::new("Gym_Helper".to_string(), "other_api".to_string(), "user".to_string(), 5, 1, "Admin".to_string(), TokenType::Access);
+        assert!(validate_token(generate_token(&wrong_audience, &config.keyring), web::Data::new(test_config())).is_err());
+    }
 }
@@ -0,0 +1,68 @@
+use std::time::Duration;
+use actix_http::Request;
+use actix_web::{body::MessageBody, dev::{Service, ServiceResponse}, test, web, App, Error};
+use sqlx::mysql::{MySqlPool, MySqlPoolOptions};
+use crate::auth::jwt::generate_token;
+use crate::auth::keyring::Keyring;
+use crate::auth::models::jwt_models::{Claims, TokenType};
+use crate::config::Config;
+
+// Configuración mínima para levantar la app en los tests
+pub fn test_config() -> Config {
+    let mut config = Config {
+        database_url: "mysql://root@127.0.0.1:1/gym_helper_test".to_string(),
+        api_bind: "127.0.0.1:0".to_string(),
+        jwt_secret: "test_secret".to_string(),
+        setup_token: Some("test_setup_token".to_string()),
+        password_policy: Default::default(),
+        login_throttle: Default::default(),
+        mfa: Default::default(),
+        phone: Default::default(),
+        debt: Default::default(),
+        scheduler: Default::default(),
+        notifications: Default::default(),
+        jwt_keys: Default::default(),
+        tokens: Default::default(),
+        keyring: Default::default(),
+    };
+    config.keyring = Keyring::load(&config.jwt_keys, &config.jwt_secret)
+        .expect("Invalid test keyring");
+    config
+}
+
+// Pool que nunca llega a conectarse: alcanza para que los extractores no fallen
+pub fn lazy_pool() -> MySqlPool {
+    MySqlPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(200))
+        .connect_lazy(&test_config().database_url)
+        .expect("Invalid test database url")
+}
+
+// App con las rutas indicadas, el pool perezoso y la configuración de test. Alcanza para los tests
+// de autorización y validación: no requieren base de datos porque el rechazo ocurre antes del handler
+pub async fn init_app<F>(configure: F) -> impl Service<Request, Response = ServiceResponse<impl MessageBody>, Error = Error>
+where
+    F: FnOnce(&mut web::ServiceConfig),
+{
+    test::init_service(
+        App::new()
+            .app_data(web::Data::new(lazy_pool()))
+            .app_data(web::Data::new(test_config()))
+            .configure(configure)
+    ).await
+}
+
+// Genera un header Authorization con un token firmado para el rol indicado
+pub fn bearer(role: &str, token_type: TokenType) -> (&'static str, String) {
+    let claims = Claims::new(
+        "Gym_Helper".to_string(),
+        "gym_helper_api".to_string(),
+        "test_user".to_string(),
+        5,
+        1,
+        role.to_string(),
+        token_type,
+    );
+    let token = generate_token(&claims, &test_config().keyring);
+    ("Authorization", format!("Bearer {}", token))
+}
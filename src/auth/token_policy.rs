@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TokenLifetime {
+    pub access_minutes: i64,
+    pub refresh_minutes: i64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TokenPolicy {
+    pub issuer: String,
+    pub audience: String,
+    pub access_minutes: i64,
+    pub refresh_minutes: i64,
+    // Duraciones por rol, ej. sesiones largas para el kiosco de recepción y cortas para Admin
+    pub roles: HashMap<String, TokenLifetime>,
+}
+
+impl Default for TokenPolicy {
+    fn default() -> Self {
+        Self {
+            issuer: "Gym_Helper".to_string(),
+            audience: "gym_helper_api".to_string(),
+            access_minutes: 30,
+            refresh_minutes: 1440,
+            roles: HashMap::new(),
+        }
+    }
+}
+
+impl TokenPolicy {
+    pub fn lifetime(&self, role: &str) -> TokenLifetime {
+        self.roles.get(role).copied().unwrap_or(TokenLifetime {
+            access_minutes: self.access_minutes,
+            refresh_minutes: self.refresh_minutes,
+        })
+    }
+
+    // Refresh token más largo entre el default y los roles
+    pub fn max_refresh_minutes(&self) -> i64 {
+        self.roles.values()
+            .map(|lifetime| lifetime.refresh_minutes)
+            .fold(self.refresh_minutes, i64::max)
+    }
+}
@@ -0,0 +1,293 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::mysql::MySqlRow;
+use sqlx::{self, MySqlConnection, MySqlPool, Row};
+use crate::payments::models::payments::{Payment, PaymentMethod};
+use super::models::cash_register::{CashMovement, CashSession, MovementKind};
+use super::models::responses::{MethodTotal, MovementTotal};
+
+// Datos de un movimiento a registrar en la sesión abierta
+pub struct MovementEntry<'a> {
+    pub session: &'a CashSession,
+    pub kind: MovementKind,
+    pub method: PaymentMethod,
+    pub amount: Decimal,
+    pub currency: &'a str,
+    pub payment_id: Option<i32>,
+    pub description: Option<&'a str>,
+    pub recorded_by: Option<i32>,
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// SESSION HANDLERS ////////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+pub async fn get_session_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<CashSession>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM cash_register_sessions WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| CashSession::from_row(&row)))
+}
+
+pub async fn get_open_session(
+    pool: &MySqlPool,
+) -> Result<Option<CashSession>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM cash_register_sessions WHERE closed_at IS NULL")
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| CashSession::from_row(&row)))
+}
+
+// Bloquea la sesión abierta hasta el fin de la transacción: el cierre (FOR UPDATE) espera a los
+// movimientos en curso (FOR SHARE) y ningún movimiento entra en una sesión ya cerrada
+async fn lock_open_session(
+    conn: &mut MySqlConnection,
+    exclusive: bool,
+) -> Result<Option<CashSession>, sqlx::Error> {
+    let query = format!(
+        "SELECT * FROM cash_register_sessions WHERE closed_at IS NULL {}",
+        if exclusive { "FOR UPDATE" } else { "FOR SHARE" });
+    let row = sqlx::query(&query)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(row.map(|row| CashSession::from_row(&row)))
+}
+
+// Una segunda apertura choca con el índice único de open_marker y se informa como conflicto
+pub async fn open_session_handler(
+    pool: &MySqlPool,
+    currency: &str,
+    opening_float: Decimal,
+    notes: Option<&str>,
+    opened_by: Option<i32>,
+) -> Result<CashSession, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO cash_register_sessions (currency, opening_float, notes, opened_by)
+        VALUES (?, ?, ?, ?)
+        "#)
+        .bind(currency)
+        .bind(opening_float)
+        .bind(notes)
+        .bind(opened_by)
+        .execute(pool)
+        .await?;
+
+    get_session_by_id(pool, result.last_insert_id() as i32).await?
+        .ok_or(sqlx::Error::RowNotFound)
+}
+
+// Efectivo que debería haber en la caja: fondo inicial más los movimientos en efectivo de la moneda de la sesión
+pub async fn get_expected_cash(
+    conn: &mut MySqlConnection,
+    session: &CashSession,
+) -> Result<Decimal, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT COALESCE(SUM(amount), 0) AS total
+        FROM cash_movements
+        WHERE session_id = ? AND method = 'cash' AND currency = ?
+        "#)
+        .bind(session.id)
+        .bind(&session.currency)
+        .fetch_one(conn)
+        .await?;
+
+    Ok(session.opening_float + row.get::<Decimal, _>("total"))
+}
+
+// Cierra la sesión abierta guardando lo esperado, lo contado y la diferencia. None si no había sesión abierta
+pub async fn close_session_handler(
+    pool: &MySqlPool,
+    counted_cash: Decimal,
+    notes: Option<&str>,
+    closed_by: Option<i32>,
+) -> Result<Option<CashSession>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let Some(session) = lock_open_session(&mut tx, true).await? else {
+        return Ok(None);
+    };
+    let expected = get_expected_cash(&mut tx, &session).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE cash_register_sessions
+        SET closed_at = NOW(), closed_by = ?, counted_cash = ?, expected_cash = ?, discrepancy = ?,
+            notes = COALESCE(?, notes)
+        WHERE id = ?
+        "#)
+        .bind(closed_by)
+        .bind(counted_cash)
+        .bind(expected)
+        .bind(counted_cash - expected)
+        .bind(notes)
+        .bind(session.id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    get_session_by_id(pool, session.id).await
+}
+
+// Sesiones abiertas en el día indicado, en orden de apertura
+pub async fn get_sessions_by_date(
+    pool: &MySqlPool,
+    date: NaiveDate,
+) -> Result<Vec<CashSession>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT * FROM cash_register_sessions WHERE DATE(opened_at) = ? ORDER BY opened_at, id")
+        .bind(date)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(CashSession::from_row).collect())
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// MOVEMENT HANDLERS ///////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+async fn insert_movement(
+    conn: &mut MySqlConnection,
+    entry: MovementEntry<'_>,
+) -> Result<i32, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO cash_movements (session_id, kind, method, amount, currency, payment_id, description, recorded_by)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(entry.session.id)
+        .bind(entry.kind.as_str())
+        .bind(entry.method.as_str())
+        .bind(entry.amount)
+        .bind(entry.currency)
+        .bind(entry.payment_id)
+        .bind(entry.description)
+        .bind(entry.recorded_by)
+        .execute(conn)
+        .await?;
+
+    Ok(result.last_insert_id() as i32)
+}
+
+// Registra un movimiento manual en la sesión abierta. None si la caja está cerrada
+pub async fn new_movement_handler(
+    pool: &MySqlPool,
+    kind: MovementKind,
+    method: PaymentMethod,
+    amount: Decimal,
+    description: &str,
+    recorded_by: Option<i32>,
+) -> Result<Option<CashMovement>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let Some(session) = lock_open_session(&mut tx, false).await? else {
+        return Ok(None);
+    };
+    let id = insert_movement(&mut tx, MovementEntry {
+        session: &session,
+        kind,
+        method,
+        amount,
+        currency: &session.currency,
+        payment_id: None,
+        description: Some(description),
+        recorded_by,
+    }).await?;
+
+    let row = sqlx::query("SELECT * FROM cash_movements WHERE id = ?")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(Some(CashMovement::from_row(&row)))
+}
+
+// Asienta un pago (o devolución) en la sesión abierta, dentro de la transacción que lo registra.
+// Devuelve false si la caja está cerrada: el pago queda registrado pero fuera de caja
+pub async fn record_payment_movement(
+    conn: &mut MySqlConnection,
+    payment: &Payment,
+) -> Result<bool, sqlx::Error> {
+    let Some(session) = lock_open_session(conn, false).await? else {
+        return Ok(false);
+    };
+    let kind = if payment.amount < Decimal::ZERO { MovementKind::Refund } else { MovementKind::Sale };
+    insert_movement(conn, MovementEntry {
+        session: &session,
+        kind,
+        method: payment.method,
+        amount: payment.amount,
+        currency: &payment.currency,
+        payment_id: Some(payment.id),
+        description: None,
+        recorded_by: payment.recorded_by,
+    }).await?;
+    Ok(true)
+}
+
+pub async fn get_session_movements(
+    pool: &MySqlPool,
+    session_id: i32,
+) -> Result<Vec<CashMovement>, sqlx::Error> {
+    let rows = sqlx::query("SELECT * FROM cash_movements WHERE session_id = ? ORDER BY created_at, id")
+        .bind(session_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(CashMovement::from_row).collect())
+}
+
+pub async fn get_session_totals(
+    pool: &MySqlPool,
+    session_id: i32,
+) -> Result<Vec<MovementTotal>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT kind, method, currency, SUM(amount) AS total, COUNT(*) AS count
+        FROM cash_movements
+        WHERE session_id = ?
+        GROUP BY kind, method, currency
+        ORDER BY kind, method, currency
+        "#)
+        .bind(session_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(|row: &MySqlRow| MovementTotal {
+        kind: MovementKind::from(row.get::<String, _>("kind")),
+        method: PaymentMethod::from(row.get::<String, _>("method")),
+        currency: row.get("currency"),
+        total: row.get("total"),
+        count: row.get("count"),
+    }).collect())
+}
+
+// Totales netos por medio de pago de todas las sesiones abiertas en el día
+pub async fn get_totals_by_method(
+    pool: &MySqlPool,
+    date: NaiveDate,
+) -> Result<Vec<MethodTotal>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT m.method, m.currency, SUM(m.amount) AS total
+        FROM cash_movements m
+        JOIN cash_register_sessions s ON s.id = m.session_id
+        WHERE DATE(s.opened_at) = ?
+        GROUP BY m.method, m.currency
+        ORDER BY m.method, m.currency
+        "#)
+        .bind(date)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(|row: &MySqlRow| MethodTotal {
+        method: PaymentMethod::from(row.get::<String, _>("method")),
+        currency: row.get("currency"),
+        total: row.get("total"),
+    }).collect())
+}
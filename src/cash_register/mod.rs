@@ -0,0 +1,20 @@
+pub mod models;
+pub mod handlers;
+pub mod services;
+
+use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/cash_register")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
+            .service(services::open_session)
+            .service(services::get_current_session)
+            .service(services::new_movement)
+            .service(services::close_session)
+            .service(services::get_session)
+            .service(services::daily_report)
+    );
+}
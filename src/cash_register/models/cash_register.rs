@@ -0,0 +1,115 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, mysql::MySqlRow};
+use utoipa::ToSchema;
+use crate::payments::models::payments::PaymentMethod;
+
+// Sesión de caja; `expected_cash`, `counted_cash` y `discrepancy` se completan al cerrarla
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CashSession {
+    pub id: i32,
+    pub currency: String,
+    #[schema(value_type = String, example = "10000.00")]
+    pub opening_float: Decimal,
+    pub opened_by: Option<i32>,
+    pub opened_at: NaiveDateTime,
+    pub closed_by: Option<i32>,
+    pub closed_at: Option<NaiveDateTime>,
+    #[schema(value_type = Option<String>, example = "45980.00")]
+    pub counted_cash: Option<Decimal>,
+    #[schema(value_type = Option<String>, example = "46000.00")]
+    pub expected_cash: Option<Decimal>,
+    // Contado menos esperado: negativo si falta dinero
+    #[schema(value_type = Option<String>, example = "-20.00")]
+    pub discrepancy: Option<Decimal>,
+    pub notes: Option<String>,
+}
+
+impl CashSession {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            currency: row.get("currency"),
+            opening_float: row.get("opening_float"),
+            opened_by: row.get("opened_by"),
+            opened_at: row.get("opened_at"),
+            closed_by: row.get("closed_by"),
+            closed_at: row.get("closed_at"),
+            counted_cash: row.get("counted_cash"),
+            expected_cash: row.get("expected_cash"),
+            discrepancy: row.get("discrepancy"),
+            notes: row.get("notes"),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.closed_at.is_none()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementKind {
+    // Pago de un cargo (venta de planes u otros conceptos)
+    Sale,
+    Refund,
+    // Ingreso que no corresponde a un cargo
+    Income,
+    // Retiro de caja chica
+    Withdrawal,
+}
+
+impl MovementKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MovementKind::Sale => "sale",
+            MovementKind::Refund => "refund",
+            MovementKind::Income => "income",
+            MovementKind::Withdrawal => "withdrawal",
+        }
+    }
+}
+
+impl From<String> for MovementKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "refund" => MovementKind::Refund,
+            "income" => MovementKind::Income,
+            "withdrawal" => MovementKind::Withdrawal,
+            _ => MovementKind::Sale,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CashMovement {
+    pub id: i32,
+    pub session_id: i32,
+    pub kind: MovementKind,
+    pub method: PaymentMethod,
+    #[schema(value_type = String, example = "12999.99")]
+    pub amount: Decimal,
+    pub currency: String,
+    pub payment_id: Option<i32>,
+    pub description: Option<String>,
+    pub recorded_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+impl CashMovement {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            session_id: row.get("session_id"),
+            kind: MovementKind::from(row.get::<String, _>("kind")),
+            method: PaymentMethod::from(row.get::<String, _>("method")),
+            amount: row.get("amount"),
+            currency: row.get("currency"),
+            payment_id: row.get("payment_id"),
+            description: row.get("description"),
+            recorded_by: row.get("recorded_by"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod cash_register;
+pub mod requests;
+pub mod responses;
@@ -0,0 +1,65 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+use crate::money::{validate_amount, validate_cash, validate_currency};
+use crate::payments::models::payments::PaymentMethod;
+use crate::validation::validate_not_blank;
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"opening_float": "10000.00"}))]
+pub struct OpenSessionRequest {
+    #[validate(custom(function = "validate_cash"))]
+    #[schema(value_type = String, example = "10000.00")]
+    pub opening_float: Decimal,
+    // Moneda del efectivo de la caja; por defecto ARS
+    #[validate(custom(function = "validate_currency"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[validate(length(max = 255, message = "Notes must have at most 255 characters"))]
+    #[schema(max_length = 255)]
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"counted_cash": "45980.00", "notes": "Faltan $20"}))]
+pub struct CloseSessionRequest {
+    #[validate(custom(function = "validate_cash"))]
+    #[schema(value_type = String, example = "45980.00")]
+    pub counted_cash: Decimal,
+    #[validate(length(max = 255, message = "Notes must have at most 255 characters"))]
+    #[schema(max_length = 255)]
+    pub notes: Option<String>,
+}
+
+// Movimientos manuales; los cobros y devoluciones se registran solos al cargar el pago
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ManualMovementKind {
+    Income,
+    Withdrawal,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"kind": "withdrawal", "amount": "3500.00", "description": "Artículos de limpieza"}))]
+pub struct NewMovementRequest {
+    pub kind: ManualMovementKind,
+    // Positivo; los retiros se guardan en negativo
+    #[validate(custom(function = "validate_amount"))]
+    #[schema(value_type = String, example = "3500.00")]
+    pub amount: Decimal,
+    // Por defecto efectivo; los retiros de caja chica siempre son en efectivo
+    pub method: Option<PaymentMethod>,
+    #[validate(
+        length(min = 1, max = 255, message = "Description must have between 1 and 255 characters"),
+        custom(function = "validate_not_blank"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub description: String,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct DailyReportParams {
+    // Por defecto, hoy (UTC)
+    pub date: Option<NaiveDate>,
+}
@@ -0,0 +1,46 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use utoipa::ToSchema;
+use crate::payments::models::payments::PaymentMethod;
+use super::cash_register::{CashMovement, CashSession, MovementKind};
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct MovementTotal {
+    pub kind: MovementKind,
+    pub method: PaymentMethod,
+    pub currency: String,
+    #[schema(value_type = String, example = "38999.97")]
+    pub total: Decimal,
+    pub count: i64,
+}
+
+// Sesión con sus totales; `expected_cash` se calcula en vivo mientras está abierta
+#[derive(Serialize, Debug, ToSchema)]
+pub struct SessionSummary {
+    pub session: CashSession,
+    #[schema(value_type = String, example = "46000.00")]
+    pub expected_cash: Decimal,
+    pub totals: Vec<MovementTotal>,
+    pub movements: Vec<CashMovement>,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct MethodTotal {
+    pub method: PaymentMethod,
+    pub currency: String,
+    #[schema(value_type = String, example = "38999.97")]
+    pub total: Decimal,
+}
+
+// Cierre del día: sesiones abiertas ese día, totales netos por medio de pago y diferencias de caja
+#[derive(Serialize, Debug, ToSchema)]
+pub struct DailyClosingReport {
+    pub date: NaiveDate,
+    pub sessions: Vec<SessionSummary>,
+    pub totals_by_method: Vec<MethodTotal>,
+    #[schema(value_type = String, example = "-20.00")]
+    pub total_discrepancy: Decimal,
+    // Sesiones del día que todavía no se cerraron
+    pub open_sessions: usize,
+}
@@ -0,0 +1,248 @@
+use actix_web::{get, post, web, HttpResponse};
+use actix_web_grants::protect;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use sqlx::MySqlPool;
+use validator::Validate;
+use crate::auth::models::jwt_models::Claims;
+use crate::errors::{access_denied, AppError};
+use crate::money::DEFAULT_CURRENCY;
+use crate::payments::models::payments::PaymentMethod;
+use super::handlers::{
+    get_session_by_id, get_open_session, open_session_handler, get_expected_cash, close_session_handler,
+    get_sessions_by_date, new_movement_handler, get_session_movements, get_session_totals, get_totals_by_method};
+use super::models::cash_register::{CashSession, MovementKind};
+use super::models::requests::{
+    OpenSessionRequest, CloseSessionRequest, NewMovementRequest, ManualMovementKind, DailyReportParams};
+use super::models::responses::{SessionSummary, DailyClosingReport};
+
+fn register_closed() -> AppError {
+    AppError::Conflict("Cash register is closed".to_string())
+}
+
+// Los retiros salen del efectivo de la caja y se guardan en negativo
+fn movement_values(req: &NewMovementRequest) -> Result<(MovementKind, PaymentMethod, Decimal), AppError> {
+    match req.kind {
+        ManualMovementKind::Income =>
+            Ok((MovementKind::Income, req.method.unwrap_or(PaymentMethod::Cash), req.amount)),
+        ManualMovementKind::Withdrawal => match req.method {
+            None | Some(PaymentMethod::Cash) => Ok((MovementKind::Withdrawal, PaymentMethod::Cash, -req.amount)),
+            Some(_) => Err(AppError::Validation {
+                message: "Withdrawals must be in cash".to_string(),
+                details: Some(serde_json::json!({ "field": "method" })),
+            }),
+        },
+    }
+}
+
+fn user_id(claims: Option<web::ReqData<Claims>>) -> Option<i32> {
+    claims.map(|claims| claims.user_id as i32)
+}
+
+async fn session_summary(pool: &MySqlPool, session: CashSession) -> Result<SessionSummary, AppError> {
+    let expected_cash = match session.expected_cash {
+        Some(expected) => expected,
+        None => get_expected_cash(&mut *pool.acquire().await?, &session).await?,
+    };
+    let totals = get_session_totals(pool, session.id).await?;
+    let movements = get_session_movements(pool, session.id).await?;
+    Ok(SessionSummary { session, expected_cash, totals, movements })
+}
+
+#[post("/open")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn open_session(
+    pool: web::Data<MySqlPool>,
+    claims: Option<web::ReqData<Claims>>,
+    req: web::Json<OpenSessionRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+
+    let currency = req.currency.as_deref().unwrap_or(DEFAULT_CURRENCY);
+    let session = open_session_handler(&pool, currency, req.opening_float, req.notes.as_deref(), user_id(claims))
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() =>
+                AppError::Conflict("Cash register is already open".to_string()),
+            e => e.into(),
+        })?;
+
+    tracing::info!("Cash register session {} opened with {} {}", session.id, session.opening_float, session.currency);
+    Ok(HttpResponse::Created().json(session))
+}
+
+#[get("/current")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn get_current_session(
+    pool: web::Data<MySqlPool>,
+) -> Result<HttpResponse, AppError> {
+    let session = get_open_session(&pool).await?.ok_or_else(register_closed)?;
+    Ok(HttpResponse::Ok().json(session_summary(&pool, session).await?))
+}
+
+#[post("/movements")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn new_movement(
+    pool: web::Data<MySqlPool>,
+    claims: Option<web::ReqData<Claims>>,
+    req: web::Json<NewMovementRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    let (kind, method, amount) = movement_values(&req)?;
+
+    let movement = new_movement_handler(&pool, kind, method, amount, req.description.trim(), user_id(claims)).await?
+        .ok_or_else(register_closed)?;
+    tracing::info!("Cash movement {} ({}) recorded in session {}", movement.id, kind.as_str(), movement.session_id);
+    Ok(HttpResponse::Created().json(movement))
+}
+
+#[post("/close")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn close_session(
+    pool: web::Data<MySqlPool>,
+    claims: Option<web::ReqData<Claims>>,
+    req: web::Json<CloseSessionRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+
+    let session = close_session_handler(&pool, req.counted_cash, req.notes.as_deref(), user_id(claims)).await?
+        .ok_or_else(register_closed)?;
+    tracing::info!("Cash register session {} closed with a discrepancy of {}",
+        session.id, session.discrepancy.unwrap_or_default());
+    Ok(HttpResponse::Ok().json(session_summary(&pool, session).await?))
+}
+
+#[get("/sessions/{id}")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn get_session(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let session = get_session_by_id(&pool, id.into_inner()).await?
+        .ok_or_else(|| AppError::NotFound("Cash register session not found".to_string()))?;
+    Ok(HttpResponse::Ok().json(session_summary(&pool, session).await?))
+}
+
+// Reporte de cierre diario: totales por medio de pago y diferencias de todas las sesiones del día
+#[get("/report")]
+#[protect("Admin", error = "access_denied")]
+pub async fn daily_report(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<DailyReportParams>,
+) -> Result<HttpResponse, AppError> {
+    let date = query.date.unwrap_or_else(|| Utc::now().date_naive());
+
+    let mut sessions = Vec::new();
+    for session in get_sessions_by_date(&pool, date).await? {
+        sessions.push(session_summary(&pool, session).await?);
+    }
+    let total_discrepancy = sessions.iter()
+        .filter_map(|summary| summary.session.discrepancy)
+        .sum();
+    let open_sessions = sessions.iter().filter(|summary| summary.session.is_open()).count();
+    let totals_by_method = get_totals_by_method(&pool, date).await?;
+
+    Ok(HttpResponse::Ok().json(DailyClosingReport { date, sessions, totals_by_method, total_discrepancy, open_sessions }))
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use validator::Validate;
+    use crate::cash_register::models::cash_register::MovementKind;
+    use crate::cash_register::models::requests::{CloseSessionRequest, NewMovementRequest, OpenSessionRequest};
+    use crate::payments::models::payments::PaymentMethod;
+
+    fn movement(json: &str) -> NewMovementRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_movement_kind_serialization() {
+        assert_eq!(serde_json::to_string(&MovementKind::Withdrawal).unwrap(), r#""withdrawal""#);
+        for kind in [MovementKind::Sale, MovementKind::Refund, MovementKind::Income, MovementKind::Withdrawal] {
+            assert_eq!(MovementKind::from(kind.as_str().to_string()), kind);
+        }
+        // Los cobros y devoluciones no se cargan a mano
+        assert!(serde_json::from_str::<NewMovementRequest>(
+            r#"{"kind":"sale","amount":"10","description":"x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_withdrawals_are_negative_cash() {
+        let req = movement(r#"{"kind":"withdrawal","amount":"3500.00","description":"Limpieza"}"#);
+        let (kind, method, amount) = super::movement_values(&req).unwrap();
+        assert_eq!((kind, method, amount), (MovementKind::Withdrawal, PaymentMethod::Cash, Decimal::new(-350000, 2)));
+
+        let req = movement(r#"{"kind":"withdrawal","amount":"3500.00","method":"transfer","description":"Limpieza"}"#);
+        assert!(super::movement_values(&req).is_err());
+
+        let req = movement(r#"{"kind":"income","amount":"500","method":"transfer","description":"Venta de agua"}"#);
+        let (kind, method, amount) = super::movement_values(&req).unwrap();
+        assert_eq!((kind, method, amount), (MovementKind::Income, PaymentMethod::Transfer, Decimal::new(500, 0)));
+    }
+
+    #[test]
+    fn test_session_request_validation() {
+        let open: OpenSessionRequest = serde_json::from_str(r#"{"opening_float":"0"}"#).unwrap();
+        assert!(open.validate().is_ok());
+        let open: OpenSessionRequest = serde_json::from_str(r#"{"opening_float":"-1"}"#).unwrap();
+        assert!(open.validate().unwrap_err().field_errors().contains_key("opening_float"));
+        let open: OpenSessionRequest = serde_json::from_str(r#"{"opening_float":"10","currency":"ars"}"#).unwrap();
+        assert!(open.validate().unwrap_err().field_errors().contains_key("currency"));
+
+        let close: CloseSessionRequest = serde_json::from_str(r#"{"counted_cash":"45980.001"}"#).unwrap();
+        assert!(close.validate().is_err());
+
+        assert!(movement(r#"{"kind":"income","amount":"0","description":"x"}"#).validate().is_err());
+        assert!(movement(r#"{"kind":"income","amount":"10","description":"  "}"#).validate().is_err());
+    }
+
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_missing_token_is_unauthorized() {
+            let app = init_app(crate::cash_register::routes).await;
+
+            let req = test::TestRequest::get().uri("/cash_register/current").to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_see_daily_report() {
+            let app = init_app(crate::cash_register::routes).await;
+
+            let req = test::TestRequest::get()
+                .uri("/cash_register/report")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_invalid_requests_are_unprocessable() {
+            let app = init_app(crate::cash_register::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/cash_register/open")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({"opening_float": "-100"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+            let req = test::TestRequest::post()
+                .uri("/cash_register/movements")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({"kind": "withdrawal", "amount": "100", "method": "debit", "description": "x"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+}
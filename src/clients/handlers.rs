@@ -1,25 +1,33 @@
 use sqlx::mysql::MySqlQueryResult;
-use sqlx::{self, MySqlPool};
+use sqlx::{self, MySqlPool, Row};
 use super::models::clients::Client;
 use super::models::requests::{ClientQueryParams, CreateClientRequest};
+use super::search::search_text;
 use sqlx::Arguments;
 use sqlx::mysql::MySqlArguments;
 use crate::add_filter;
+use crate::pagination::{fetch_page, PageRequest, PageResult};
 
 
 pub async fn create_client_in_db(
     pool: &MySqlPool,
     req: CreateClientRequest,
 ) -> Result<MySqlQueryResult, sqlx::Error> {
+    let search_text = search_text(&req.name, &req.last_name, &req.phone, req.document_number.as_deref());
     let result = sqlx::query(
         r#"
-        INSERT INTO clients (name, last_name, age, phone, active) VALUES (?, ?, ?, ?, ?)
+        INSERT INTO clients (name, last_name, age, phone, document_number, email, language, search_text, active)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#
     )
     .bind(req.name)
     .bind(req.last_name)
     .bind(req.age)
     .bind(req.phone)
+    .bind(req.document_number)
+    .bind(req.email)
+    .bind(req.language.unwrap_or_default().as_str())
+    .bind(search_text)
     .bind(true)
     .execute(pool)
     .await;
@@ -27,6 +35,25 @@ pub async fn create_client_in_db(
     result
 }
 
+// Completa `search_text` de los clientes anteriores a la búsqueda (la migración la deja vacía) con el
+// mismo normalizador de las altas y ediciones. Devuelve los clientes actualizados
+pub async fn backfill_search_text(
+    pool: &MySqlPool,
+) -> Result<u64, sqlx::Error> {
+    let rows = sqlx::query("SELECT * FROM clients WHERE search_text = ''")
+        .fetch_all(pool)
+        .await?;
+
+    for client in rows.iter().map(Client::from_row) {
+        sqlx::query("UPDATE clients SET search_text = ? WHERE id = ?")
+            .bind(search_text(&client.name, &client.last_name, &client.phone, client.document_number.as_deref()))
+            .bind(client.id)
+            .execute(pool)
+            .await?;
+    }
+    Ok(rows.len() as u64)
+}
+
 pub async fn obtain_client_by_id(
     pool: &MySqlPool,
     id: i32,
@@ -44,28 +71,20 @@ pub async fn obtain_client_by_id(
 
 pub async fn obtain_clients(
     pool: &MySqlPool,
-) -> Result<Vec<Client>, sqlx::Error> {
-    let rows = sqlx::query(
-        r#"
-        SELECT * FROM clients
-        "#)
-        .fetch_all(pool)
-        .await?;
-
-    Ok(rows.iter().map(Client::from_row).collect())
+    page: &PageRequest,
+) -> Result<PageResult<Client>, sqlx::Error> {
+    fetch_page(pool, "clients", || (String::new(), MySqlArguments::default()), page, Client::from_row).await
 }
 
-pub async fn filter_clients(
-    pool: &MySqlPool,
-    params: ClientQueryParams,
-) -> Result <Vec<Client>, sqlx::Error> {
-    let mut query = String::from("SELECT * FROM clients WHERE 1=1");
+fn client_filters(params: &ClientQueryParams) -> (String, MySqlArguments) {
+    let mut query = String::new();
     let mut args = MySqlArguments::default();
 
     add_filter!(query, args, &params.name, " AND name = ?");
     add_filter!(query, args, &params.last_name, " AND last_name = ?");
     add_filter!(query, args, &params.age, " AND age = ?");
     add_filter!(query, args, &params.phone, " AND phone = ?");
+    add_filter!(query, args, &params.document_number, " AND document_number = ?");
     add_filter!(query, args, &params.active, " AND active = ?");
     add_filter!(query, args, &params.created_at, " AND created_at = ?");
     add_filter!(query, args, &params.updated_at, " AND updated_at = ?");
@@ -77,10 +96,51 @@ pub async fn filter_clients(
     add_filter!(query, args, &params.deleted_from, " AND deleted_at >= ?");
     add_filter!(query, args, &params.deleted_to, " AND deleted_at <= ?");
 
+    (query, args)
+}
+
+pub async fn filter_clients(
+    pool: &MySqlPool,
+    params: ClientQueryParams,
+    page: &PageRequest,
+) -> Result<PageResult<Client>, sqlx::Error> {
+    fetch_page(pool, "clients", || client_filters(&params), page, Client::from_row).await
+}
+
+// Cada término debe aparecer en `search_text`; puntúa más la palabra completa (3) y el inicio de palabra (2)
+// que una coincidencia en el medio (1, útil para teléfono y documento)
+pub async fn search_clients(
+    pool: &MySqlPool,
+    terms: &[String],
+    limit: u32,
+) -> Result<Vec<(Client, i64)>, sqlx::Error> {
+    let mut score = Vec::new();
+    let mut conditions = String::new();
+    let mut args = MySqlArguments::default();
+    for term in terms {
+        score.push("(CASE WHEN search_text LIKE ? THEN 3 WHEN search_text LIKE ? THEN 2 ELSE 1 END)");
+        let _ = args.add(format!("% {} %", term));
+        let _ = args.add(format!("% {}%", term));
+    }
+    for term in terms {
+        conditions.push_str(" AND search_text LIKE ?");
+        let _ = args.add(format!("%{}%", term));
+    }
+    let _ = args.add(limit);
+
+    let query = format!(
+        r#"
+        SELECT *, CAST({} AS SIGNED) AS score FROM clients
+        WHERE deleted_at IS NULL{}
+        ORDER BY score DESC, last_name, name, id
+        LIMIT ?
+        "#,
+        score.join(" + "), conditions);
     let rows = sqlx::query_with(&query, args)
         .fetch_all(pool)
         .await?;
-    Ok(rows.iter().map(Client::from_row).collect())
+
+    Ok(rows.iter().map(|row| (Client::from_row(row), row.get::<i64, _>("score"))).collect())
 }
 
 pub async fn delete_client(
@@ -104,16 +164,22 @@ pub async fn update_client(
     id: i32,
     req: CreateClientRequest
 ) -> Result<MySqlQueryResult, sqlx::Error> {
+    let search_text = search_text(&req.name, &req.last_name, &req.phone, req.document_number.as_deref());
     let result = sqlx::query(
         r#"
         UPDATE clients
-        SET name = ?, last_name = ?, age = ?, phone = ?, active = ?, deleted_at = NULL
+        SET name = ?, last_name = ?, age = ?, phone = ?, document_number = ?, email = ?, language = ?,
+            search_text = ?, active = ?, deleted_at = NULL
         WHERE id = ?
         "#)
         .bind(req.name)
         .bind(req.last_name)
         .bind(req.age)
         .bind(req.phone)
+        .bind(req.document_number)
+        .bind(req.email)
+        .bind(req.language.unwrap_or_default().as_str())
+        .bind(search_text)
         .bind(true)
         .bind(id)
         .execute(pool)
@@ -1,16 +1,24 @@
 pub mod models;
 pub mod handlers;
 pub mod services;
+pub mod search;
 
 use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
 
 pub fn routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/clients")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
             .service(services::create_client)
             .service(services::get_clients)
             .service(services::get_clients_by_query_params)
+            .service(services::search_clients_by_text)
+            // /debtors antes que /{id}
+            .service(services::get_debtors_report)
             .service(services::get_client_by_id)
+            .service(services::get_client_balance)
             .service(services::delete_client_by_id)
             .service(services::update_client_by_admin)
             .service(services::alta_client)
@@ -3,6 +3,32 @@ use sqlx::{Row, mysql::MySqlRow};
 use chrono::NaiveDateTime;
 use utoipa::ToSchema;
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    #[default]
+    Es,
+    En,
+}
+
+impl Language {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::Es => "es",
+            Language::En => "en",
+        }
+    }
+}
+
+impl From<String> for Language {
+    fn from(language: String) -> Self {
+        match language.as_str() {
+            "en" => Language::En,
+            _ => Language::Es,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct Client {
     pub id: i32,
@@ -10,6 +36,11 @@ pub struct Client {
     pub last_name: String,
     pub age: i32,
     pub phone: String,
+    pub document_number: Option<String>,
+    // Para avisos por email (vencimientos, renovaciones)
+    pub email: Option<String>,
+    // Idioma de los avisos
+    pub language: Language,
     pub active: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -24,6 +55,9 @@ impl Client {
             last_name: row.get("last_name"),
             age: row.get("age"),
             phone: row.get("phone"),
+            document_number: row.get("document_number"),
+            email: row.get("email"),
+            language: Language::from(row.get::<String, _>("language")),
             active: row.get::<i8, _>("active") != 0,
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
@@ -1,2 +1,3 @@
 pub mod clients;
-pub mod requests;
\ No newline at end of file
+pub mod requests;
+pub mod responses;
\ No newline at end of file
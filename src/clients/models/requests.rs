@@ -1,14 +1,64 @@
 use serde::{Deserialize, Serialize};
 use chrono::NaiveDateTime;
 use utoipa::ToSchema;
+use validator::Validate;
+use crate::errors::AppError;
+use super::clients::Language;
+use crate::validation::{
+    normalize_document, normalize_phone, validate_document, validate_not_blank, validate_phone, PhoneConfig};
 
-#[derive(Serialize, Deserialize, Debug, ToSchema)]
-#[schema(example = json!({"name": "Juan", "last_name": "Pérez", "age": 25, "phone": "123456789"}))]
+#[derive(Serialize, Deserialize, Debug, ToSchema, Validate)]
+#[schema(example = json!({"name": "Juan", "last_name": "Pérez", "age": 25, "phone": "+54 9 11 1234-5678"}))]
 pub struct CreateClientRequest {
+    #[validate(
+        length(min = 1, max = 255, message = "Name must have between 1 and 255 characters"),
+        custom(function = "validate_not_blank"))]
+    #[schema(min_length = 1, max_length = 255)]
     pub name: String,
+    #[validate(
+        length(min = 1, max = 255, message = "Last name must have between 1 and 255 characters"),
+        custom(function = "validate_not_blank"))]
+    #[schema(min_length = 1, max_length = 255)]
     pub last_name: String,
+    #[validate(range(min = 1, max = 120, message = "Age must be between 1 and 120"))]
+    #[schema(minimum = 1, maximum = 120)]
     pub age: i32,
+    // Se guarda normalizado a E.164; sin prefijo internacional se asume `phone.default_country_code`
+    #[validate(custom(function = "validate_phone"))]
+    #[schema(max_length = 20, pattern = r"^\+?[0-9 ().-]{8,20}$")]
     pub phone: String,
+    // DNI/pasaporte; se guarda sin puntos ni espacios
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(custom(function = "validate_document"))]
+    #[schema(min_length = 6, max_length = 20, pattern = r"^[A-Za-z0-9 .-]+$")]
+    pub document_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(
+        email(message = "Email must be a valid address"),
+        length(max = 255, message = "Email must have at most 255 characters"))]
+    #[schema(max_length = 255, format = "email")]
+    pub email: Option<String>,
+    // Idioma de los avisos; por defecto español
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+}
+
+impl CreateClientRequest {
+    // Se llama después de `validate`: recorta los nombres y deja el teléfono en E.164
+    pub fn normalize(mut self, phone_config: &PhoneConfig) -> Result<Self, AppError> {
+        self.name = self.name.trim().to_string();
+        self.last_name = self.last_name.trim().to_string();
+        self.phone = normalize_phone(&self.phone, &phone_config.default_country_code)
+            .ok_or_else(|| AppError::Validation {
+                message: "Invalid request".to_string(),
+                details: Some(serde_json::json!({
+                    "fields": { "phone": ["Phone must be a valid number (E.164)"] }
+                })),
+            })?;
+        self.document_number = self.document_number.as_deref().map(normalize_document);
+        self.email = self.email.map(|email| email.trim().to_lowercase());
+        Ok(self)
+    }
 }
 
 #[derive(Deserialize, Debug, ToSchema)]
@@ -17,6 +67,7 @@ pub struct ClientQueryParams {
     pub last_name: Option<String>,
     pub age: Option<i32>,
     pub phone: Option<String>,
+    pub document_number: Option<String>,
     pub active: Option<bool>,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
@@ -27,4 +78,11 @@ pub struct ClientQueryParams {
     pub updated_to: Option<NaiveDateTime>,
     pub deleted_from: Option<NaiveDateTime>,
     pub deleted_to: Option<NaiveDateTime>,
-}
\ No newline at end of file
+}
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct ClientSearchParams {
+    // Texto libre: nombre, apellido, teléfono o documento (parcial, sin distinguir mayúsculas ni acentos)
+    pub q: String,
+    // Entre 1 y 100 (por defecto 20)
+    pub limit: Option<u32>,
+}
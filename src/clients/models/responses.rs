@@ -0,0 +1,11 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use super::clients::Client;
+
+// Resultado de /clients/search: el cliente más su puntaje de relevancia
+#[derive(Serialize, ToSchema)]
+pub struct ClientSearchResult {
+    #[serde(flatten)]
+    pub client: Client,
+    pub score: i64,
+}
@@ -0,0 +1,35 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+pub const DEFAULT_SEARCH_LIMIT: u32 = 20;
+pub const MAX_SEARCH_LIMIT: u32 = 100;
+// Evita consultas con decenas de LIKE por un texto pegado por error
+pub const MAX_SEARCH_TERMS: usize = 5;
+
+// Minúsculas, sin acentos y solo letras/dígitos separados por un espacio: "José García-López" -> "jose garcia lopez"
+pub fn normalize(text: &str) -> String {
+    text.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Valor de `clients.search_text`. Los espacios en los extremos permiten buscar inicio de palabra con LIKE '% term%'
+pub fn search_text(name: &str, last_name: &str, phone: &str, document_number: Option<&str>) -> String {
+    let phone: String = phone.chars().filter(char::is_ascii_digit).collect();
+    let text = normalize(&format!("{} {} {} {}", name, last_name, phone, document_number.unwrap_or_default()));
+    format!(" {} ", text)
+}
+
+// Términos de búsqueda normalizados; vacío si `q` no tiene letras ni dígitos
+pub fn search_terms(q: &str) -> Vec<String> {
+    normalize(q)
+        .split(' ')
+        .filter(|term| !term.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(str::to_string)
+        .collect()
+}
@@ -1,134 +1,208 @@
-use actix_web::{delete, get, patch, post, put, web, HttpResponse};
+use actix_web::{delete, get, patch, post, put, web, HttpRequest, HttpResponse};
+use actix_web_grants::protect;
+use rust_decimal::Decimal;
 use sqlx::MySqlPool;
+use validator::Validate;
+use crate::config::Config;
+use crate::errors::{access_denied, AppError};
+use crate::validation::normalize_phone;
+use crate::pagination::PageParams;
+use super::models::clients::Client;
 use crate::clients::models::requests::{
-    CreateClientRequest, ClientQueryParams};
-use crate::clients::models::clients::Client;
+    CreateClientRequest, ClientQueryParams, ClientSearchParams};
+use crate::clients::models::responses::ClientSearchResult;
+use super::search::{search_terms, DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT};
 use super::handlers::{
     obtain_client_by_id, create_client_in_db,
-    obtain_clients, filter_clients, delete_client,
+    obtain_clients, filter_clients, search_clients, delete_client,
     update_client, activate_client};
 use crate::subscription::handlers::{get_all_client_subscriptions, delete_subscription_handler};
+use crate::payments::handlers::{get_client_balances, get_client_debts, get_debtors, get_statement_entries};
+use crate::payments::models::requests::DebtorQueryParams;
+use crate::payments::models::responses::AccountStatement;
 
 #[post("/")]
+#[protect(any("Admin", "Trainer", "clients:write"), error = "access_denied")]
 pub async fn create_client(
     pool: web::Data<MySqlPool>,
-    req: web::Json<CreateClientRequest>
-) -> HttpResponse {
-    match create_client_in_db(&pool, req.into_inner()).await {
-        Ok(_) => {
-            tracing::info!("Client created successfully");
-            HttpResponse::Created().body("Client created successfully")
-        },
-        Err(e) => {
-            tracing::error!("Error creating client: {}", e);
-            HttpResponse::InternalServerError().body("Error creating client")
-        }
-    }
+    req: web::Json<CreateClientRequest>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    create_client_in_db(&pool, req.into_inner().normalize(&data.phone)?).await?;
+    tracing::info!("Client created successfully");
+    Ok(HttpResponse::Created().body("Client created successfully"))
 }
 
 #[get("/{id}")]
+#[protect(any("Admin", "Trainer", "clients:read"), error = "access_denied")]
 pub async fn get_client_by_id(
     pool: web::Data<MySqlPool>,
     id: web::Path<i32>,
-) -> HttpResponse {
-    match obtain_client_by_id(&pool, id.into_inner()).await {
-        Ok(Some(client)) => HttpResponse::Ok().json(client),
-        Ok(None) => HttpResponse::NotFound().body("Client not found"),
-        Err(e) => {
-            tracing::error!("Error fetching client: {}", e);
-            HttpResponse::InternalServerError().body("Error fetching client")
-        }
+) -> Result<HttpResponse, AppError> {
+    match obtain_client_by_id(&pool, id.into_inner()).await? {
+        Some(client) => Ok(HttpResponse::Ok().json(client)),
+        None => Err(AppError::NotFound("Client not found".to_string())),
     }
 }
 
 #[get("/")]
+#[protect(any("Admin", "Trainer", "clients:read"), error = "access_denied")]
 pub async fn get_clients(
     pool: web::Data<MySqlPool>,
-) -> HttpResponse {
-    match obtain_clients(&pool).await {
-        Ok(clients) => HttpResponse::Ok().json(clients),
-        Err(e) => {
-            tracing::error!("Error fetching clients: {}", e);
-            HttpResponse::InternalServerError().body("Error fetching clients")
-        }
-    }
+    http_req: HttpRequest,
+    page: web::Query<PageParams>,
+) -> Result<HttpResponse, AppError> {
+    let page = page.resolve::<Client>()?;
+    let clients = obtain_clients(&pool, &page).await?;
+    Ok(HttpResponse::Ok().json(clients.into_page(&page, http_req.path(), http_req.query_string())))
 }
 
 #[get("/filter")]
+#[protect(any("Admin", "Trainer", "clients:read"), error = "access_denied")]
 pub async fn get_clients_by_query_params(
     pool: web::Data<MySqlPool>,
+    http_req: HttpRequest,
     query: web::Query<ClientQueryParams>,
-) -> HttpResponse {
-    match filter_clients(&pool, query.into_inner()).await {
-        Ok(clients) => HttpResponse::Ok().json(clients),
-        Err(e) => {
-            tracing::error!("Error fetching clients: {}", e);
-            HttpResponse::InternalServerError().body("Error fetching clients")
-        }
+    page: web::Query<PageParams>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let page = page.resolve::<Client>()?;
+    let mut params = query.into_inner();
+    // Los teléfonos se guardan en E.164: el filtro acepta el mismo formato libre que el alta
+    if let Some(phone) = params.phone.as_deref()
+        .and_then(|phone| normalize_phone(phone, &data.phone.default_country_code)) {
+        params.phone = Some(phone);
     }
+    let clients = filter_clients(&pool, params, &page).await?;
+    Ok(HttpResponse::Ok().json(clients.into_page(&page, http_req.path(), http_req.query_string())))
+}
+
+// Búsqueda para recepción: "jose garcia" encuentra a "José García-López"
+#[get("/search")]
+#[protect(any("Admin", "Trainer", "clients:read"), error = "access_denied")]
+pub async fn search_clients_by_text(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<ClientSearchParams>,
+) -> Result<HttpResponse, AppError> {
+    let terms = search_terms(&query.q);
+    if terms.is_empty() {
+        return Err(AppError::Validation {
+            message: "Search text must contain letters or digits".to_string(),
+            details: Some(serde_json::json!({ "field": "q" })),
+        })
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    if !(1..=MAX_SEARCH_LIMIT).contains(&limit) {
+        return Err(AppError::Validation {
+            message: format!("Limit must be between 1 and {}", MAX_SEARCH_LIMIT),
+            details: Some(serde_json::json!({ "field": "limit" })),
+        })
+    }
+
+    let results = search_clients(&pool, &terms, limit).await?;
+    Ok(HttpResponse::Ok().json(results.into_iter()
+        .map(|(client, score)| ClientSearchResult { client, score })
+        .collect::<Vec<_>>()))
+}
+
+// Estado de cuenta del cliente: saldo por moneda, deuda vencida y movimientos con saldo acumulado
+#[get("/{id}/balance")]
+#[protect(any("Admin", "Trainer", "payments:read"), error = "access_denied")]
+pub async fn get_client_balance(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let client_id = id.into_inner();
+    if obtain_client_by_id(&pool, client_id).await?.is_none() {
+        return Err(AppError::NotFound("Client not found".to_string()))
+    }
+    let balances = get_client_balances(&pool, client_id).await?;
+    let debts = get_client_debts(&pool, client_id).await?;
+    let entries = get_statement_entries(&pool, client_id).await?;
+    Ok(HttpResponse::Ok().json(AccountStatement { client_id, balances, debts, entries }))
+}
+
+// Reporte de deudores: clientes con saldo pendiente y antigüedad de la deuda
+#[get("/debtors")]
+#[protect(any("Admin", "Trainer", "payments:read"), error = "access_denied")]
+pub async fn get_debtors_report(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<DebtorQueryParams>,
+) -> Result<HttpResponse, AppError> {
+    if query.min_days.is_some_and(|days| days < 0) {
+        return Err(AppError::Validation {
+            message: "min_days must be zero or positive".to_string(),
+            details: Some(serde_json::json!({ "field": "min_days" })),
+        })
+    }
+    if query.min_amount.is_some_and(|amount| amount < Decimal::ZERO) {
+        return Err(AppError::Validation {
+            message: "min_amount must be zero or positive".to_string(),
+            details: Some(serde_json::json!({ "field": "min_amount" })),
+        })
+    }
+    let debtors = get_debtors(&pool, &query).await?;
+    Ok(HttpResponse::Ok().json(debtors))
 }
 
 #[delete("/{id}")]
+#[protect("Admin", error = "access_denied")]
 pub async fn delete_client_by_id(
     pool: web::Data<MySqlPool>,
     id: web::Path<i32>,
-) -> HttpResponse {
-    // TODO: Ver por que en caso de no encontrar el cliente devuelve un 200 Ok
+) -> Result<HttpResponse, AppError> {
     let client_id = id.into_inner();
-    match delete_client(&pool, client_id).await {
-        Ok(_) => {
-            let client_subscriptions = get_all_client_subscriptions(&pool, client_id).await.unwrap();
-            if !client_subscriptions.is_empty() {
-                for subscription in client_subscriptions {
-                    tracing::info!("Deleting subscription with id: {}", subscription.id);
-                    delete_subscription_handler(&pool, subscription.id).await.unwrap();
-                }
-            }
-            tracing::info!("Client deleted successfully");
-            HttpResponse::Ok().body("Client deleted successfully")},
-        Err(e) => {
-            tracing::error!("Error deleting client: {}", e);
-            HttpResponse::InternalServerError().body("Error deleting client")
-        }
+    if delete_client(&pool, client_id).await?.rows_affected() == 0 {
+        return Err(AppError::NotFound("Client not found".to_string()))
     }
+
+    for subscription in get_all_client_subscriptions(&pool, client_id).await? {
+        tracing::info!("Deleting subscription with id: {}", subscription.id);
+        delete_subscription_handler(&pool, subscription.id).await?;
+    }
+    tracing::info!("Client deleted successfully");
+    Ok(HttpResponse::Ok().body("Client deleted successfully"))
 }
 
 // Endpoint para admins
 #[put("/{id}")]
+#[protect("Admin", error = "access_denied")]
 pub async fn update_client_by_admin(
     pool: web::Data<MySqlPool>,
     id: web::Path<i32>,
     req: web::Json<CreateClientRequest>,
-) -> HttpResponse {
-    match update_client(&pool, id.into_inner(), req.into_inner()).await {
-        Ok(_) => HttpResponse::Ok().body("Client updated successfully"),
-        Err(e) => {
-            tracing::error!("Error updating client: {}", e);
-            HttpResponse::NotModified().body("Error updating client")
-        }
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    let req = req.into_inner().normalize(&data.phone)?;
+    if update_client(&pool, id.into_inner(), req).await?.rows_affected() == 0 {
+        return Err(AppError::NotFound("Client not found".to_string()))
     }
+    Ok(HttpResponse::Ok().body("Client updated successfully"))
 }
 
 #[patch("/{id}")]
+#[protect("Admin", error = "access_denied")]
 pub async fn alta_client(
     pool: web::Data<MySqlPool>,
     id: web::Path<i32>
-) -> HttpResponse {
-    match activate_client(&pool, id.into_inner()).await {
-        Ok(_) => HttpResponse::Ok().body("Client activated successfully"),
-        Err(e) => {
-            tracing::error!("Error activating client: {}", e);
-            HttpResponse::InternalServerError().body("Error activating client")
-        }
+) -> Result<HttpResponse, AppError> {
+    if activate_client(&pool, id.into_inner()).await?.rows_affected() == 0 {
+        return Err(AppError::NotFound("Client not found".to_string()))
     }
+    Ok(HttpResponse::Ok().body("Client activated successfully"))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
     use chrono::Utc;
+    use actix_web::{http::StatusCode, ResponseError};
+    use validator::Validate;
+    use crate::errors::AppError;
+    use crate::validation::PhoneConfig;
     use crate::clients::models::{
-        clients::Client,
+        clients::{Client, Language},
         requests::{CreateClientRequest, ClientQueryParams}
     };
 
@@ -141,6 +215,9 @@ mod tests {
             last_name: "Pérez".to_string(),
             age: 25,
             phone: "123456789".to_string(),
+            document_number: None,
+            email: None,
+            language: Language::Es,
             active: true,
             created_at: now,
             updated_at: now,
@@ -155,6 +232,9 @@ mod tests {
             last_name: "Pérez".to_string(),
             age: 25,
             phone: "123456789".to_string(),
+            document_number: None,
+            email: None,
+            language: None,
         }
     }
 
@@ -165,6 +245,7 @@ mod tests {
             last_name: Some("Pérez".to_string()),
             age: Some(25),
             phone: Some("123456789".to_string()),
+            document_number: None,
             active: Some(true),
             created_at: None,
             updated_at: None,
@@ -205,7 +286,7 @@ mod tests {
         assert_eq!(client.last_name, "Pérez");
         assert_eq!(client.age, 25);
         assert_eq!(client.phone, "123456789");
-        assert_eq!(client.active, true);
+        assert!(client.active);
         assert!(client.deleted_at.is_none());
     }
 
@@ -228,13 +309,52 @@ mod tests {
             last_name: "".to_string(), // Apellido vacío
             age: -1, // Edad inválida
             phone: "".to_string(), // Teléfono vacío
+            document_number: None,
+            email: None,
+            language: None,
         };
 
-        // Verificamos que los valores son los esperados para el test
-        assert_eq!(request.name, "");
-        assert_eq!(request.last_name, "");
-        assert_eq!(request.age, -1);
-        assert_eq!(request.phone, "");
+        let errors = request.validate().unwrap_err();
+        let fields = errors.field_errors();
+        for field in ["name", "last_name", "age", "phone"] {
+            assert!(fields.contains_key(field), "missing error for {}", field);
+        }
+    }
+
+    #[test]
+    fn test_validation_errors_are_unprocessable_per_field() {
+        let mut request = create_test_create_client_request();
+        request.age = 200;
+        let error = AppError::from(request.validate().unwrap_err());
+
+        assert_eq!(error.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        let details = error.to_response_body().details.unwrap();
+        assert_eq!(details["fields"]["age"][0], "Age must be between 1 and 120");
+        assert!(details["fields"].get("name").is_none());
+    }
+
+    #[test]
+    fn test_normalize_trims_names_and_formats_phone() {
+        let mut request = create_test_create_client_request();
+        request.name = "  Juan ".to_string();
+        request.phone = "011 15-1234 5678".to_string();
+
+        let request = request.normalize(&PhoneConfig::default()).unwrap();
+        assert_eq!(request.name, "Juan");
+        assert_eq!(request.phone, "+54111512345678");
+    }
+
+    #[test]
+    fn test_client_email_and_language() {
+        let mut request = create_test_create_client_request();
+        request.email = Some("no-es-un-email".to_string());
+        assert!(request.validate().unwrap_err().field_errors().contains_key("email"));
+
+        let json = r#"{"name":"Juan","last_name":"Pérez","age":25,"phone":"1112345678","email":" Juan@Mail.com ","language":"en"}"#;
+        let request: CreateClientRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.language, Some(Language::En));
+        let request = request.normalize(&PhoneConfig::default()).unwrap();
+        assert_eq!(request.email.as_deref(), Some("juan@mail.com"));
     }
 
     #[test]
@@ -244,6 +364,9 @@ mod tests {
             last_name: "García-López".to_string(),
             age: 30,
             phone: "+54-11-1234-5678".to_string(),
+            document_number: None,
+            email: None,
+            language: None,
         };
 
         assert!(request.name.contains("José"));
@@ -270,69 +393,56 @@ mod tests {
     // Tests para validación de entrada
     mod validation_tests {
         use super::*;
+        use crate::validation::normalize_phone;
+
+        fn request_with(name: &str, age: i32, phone: &str) -> CreateClientRequest {
+            CreateClientRequest {
+                name: name.to_string(),
+                last_name: "User".to_string(),
+                age,
+                phone: phone.to_string(),
+                document_number: None,
+                email: None,
+                language: None,
+            }
+        }
 
         #[test]
         fn test_age_validation() {
-            let valid_ages = vec![18, 25, 35, 65, 80];
-            let invalid_ages = vec![-1, 0, 150, 200];
-
-            for age in valid_ages {
-                let request = CreateClientRequest {
-                    name: "Test".to_string(),
-                    last_name: "User".to_string(),
-                    age,
-                    phone: "123456789".to_string(),
-                };
-                assert!(age > 0 && age < 120); // Lógica de validación esperada
+            for age in [1, 18, 25, 65, 120] {
+                assert!(request_with("Test", age, "123456789").validate().is_ok(), "age {}", age);
             }
-
-            for age in invalid_ages {
-                let request = CreateClientRequest {
-                    name: "Test".to_string(),
-                    last_name: "User".to_string(),
-                    age,
-                    phone: "123456789".to_string(),
-                };
-                assert!(age <= 0 || age >= 120); // Lógica de validación esperada
+            for age in [-1, 0, 121, 200] {
+                assert!(request_with("Test", age, "123456789").validate().is_err(), "age {}", age);
             }
         }
 
         #[test]
         fn test_phone_format_validation() {
-            let valid_phones = vec![
-                "123456789".to_string(),
-                "+54-11-1234-5678".to_string(),
-                "011-1234-5678".to_string(),
-            ];
-
-            for phone in valid_phones {
-                let request = CreateClientRequest {
-                    name: "Test".to_string(),
-                    last_name: "User".to_string(),
-                    age: 25,
-                    phone: phone.clone(),
-                };
-                assert!(!phone.is_empty());
+            for phone in ["123456789", "+54-11-1234-5678", "011-1234-5678", "(011) 4555.1234", "0054 11 1234 5678"] {
+                assert!(request_with("Test", 25, phone).validate().is_ok(), "phone {}", phone);
+            }
+            for phone in ["", "12345", "abc-def-ghij", "+54 11 1234 5678 9999", "++5411"] {
+                assert!(request_with("Test", 25, phone).validate().is_err(), "phone {}", phone);
             }
         }
 
         #[test]
-        fn test_name_validation() {
-            let valid_names = vec![
-                "Juan".to_string(),
-                "María José".to_string(),
-                "José-Luis".to_string(),
-            ];
+        fn test_phone_normalization_to_e164() {
+            assert_eq!(normalize_phone("+54-11-1234-5678", "54").as_deref(), Some("+541112345678"));
+            assert_eq!(normalize_phone("0054 11 1234 5678", "54").as_deref(), Some("+541112345678"));
+            assert_eq!(normalize_phone("011-1234-5678", "54").as_deref(), Some("+541112345678"));
+            assert_eq!(normalize_phone("(11) 1234-5678", "1").as_deref(), Some("+11112345678"));
+            assert_eq!(normalize_phone("+0 11 1234 5678", "54"), None);
+        }
 
-            for name in valid_names {
-                let request = CreateClientRequest {
-                    name: name.clone(),
-                    last_name: "Test".to_string(),
-                    age: 25,
-                    phone: "123456789".to_string(),
-                };
-                assert!(!name.is_empty());
-                assert!(name.len() >= 2);
+        #[test]
+        fn test_name_validation() {
+            for name in ["Juan", "María José", "José-Luis"] {
+                assert!(request_with(name, 25, "123456789").validate().is_ok());
+            }
+            for name in ["", "   ", &"x".repeat(256)] {
+                assert!(request_with(name, 25, "123456789").validate().is_err());
             }
         }
     }
@@ -371,6 +481,223 @@ mod tests {
         }
     }
 
+    // Tests de la normalización usada por /clients/search
+    mod search_tests {
+        use super::*;
+        use crate::clients::search::{normalize, search_terms, search_text, MAX_SEARCH_TERMS};
+
+        #[test]
+        fn test_normalize_removes_accents_case_and_punctuation() {
+            assert_eq!(normalize("José García-López"), "jose garcia lopez");
+            assert_eq!(normalize("  MUÑOZ,  Ñandú "), "munoz nandu");
+            assert_eq!(normalize("Gonçalves Müller"), "goncalves muller");
+        }
+
+        #[test]
+        fn test_search_text_includes_phone_and_document() {
+            assert_eq!(
+                search_text("Juan", "Pérez", "+5491112345678", Some("30123456")),
+                " juan perez 5491112345678 30123456 ");
+            // Clientes anteriores a la validación de teléfonos: cuentan solo los dígitos
+            assert_eq!(search_text("Juan", "Pérez", "011 1234-5678", None), " juan perez 01112345678 ");
+        }
+
+        #[test]
+        fn test_search_terms() {
+            assert_eq!(search_terms("Jose  Garcia"), vec!["jose", "garcia"]);
+            assert_eq!(search_terms("11-1234"), vec!["11", "1234"]);
+            assert!(search_terms(" %_ ").is_empty());
+            assert_eq!(search_terms("a b c d e f g h").len(), MAX_SEARCH_TERMS);
+        }
+
+        #[test]
+        fn test_document_number_validation_and_normalization() {
+            let mut request = create_test_create_client_request();
+            request.document_number = Some("30.123.456".to_string());
+            assert!(request.validate().is_ok());
+            let request = request.normalize(&PhoneConfig::default()).unwrap();
+            assert_eq!(request.document_number.as_deref(), Some("30123456"));
+
+            for document in ["123", "30/123/456", &"1".repeat(21)] {
+                let mut request = create_test_create_client_request();
+                request.document_number = Some(document.to_string());
+                assert!(request.validate().is_err(), "{}", document);
+            }
+        }
+    }
+
+    // Tests de paginación: los parámetros se validan antes de consultar la base
+    mod pagination_tests {
+        use crate::clients::models::clients::Client;
+        use crate::pagination::{test_cursor, PageParams, PageResult, SortOrder, DEFAULT_LIMIT};
+
+        #[test]
+        fn test_page_defaults() {
+            let page = PageParams::default().resolve::<Client>().unwrap();
+            assert_eq!(page.limit, DEFAULT_LIMIT);
+            assert_eq!(page.offset, 0);
+            assert_eq!(page.sort_by, "id");
+            assert_eq!(page.order, SortOrder::Asc);
+        }
+
+        #[test]
+        fn test_page_rejects_invalid_params() {
+            let params = PageParams { sort_by: Some("phone; DROP TABLE clients".to_string()), ..Default::default() };
+            assert!(params.resolve::<Client>().is_err());
+
+            for limit in [0, 201] {
+                let params = PageParams { limit: Some(limit), ..Default::default() };
+                assert!(params.resolve::<Client>().is_err());
+            }
+
+            let params = PageParams { cursor: Some("not-a-cursor".to_string()), ..Default::default() };
+            assert!(params.resolve::<Client>().is_err());
+        }
+
+        #[test]
+        fn test_cursor_must_match_sort() {
+            let cursor = test_cursor("name", SortOrder::Asc, "Juan", 10);
+            let params = PageParams {
+                cursor: Some(cursor.clone()),
+                sort_by: Some("name".to_string()),
+                offset: Some(100),
+                ..Default::default()
+            };
+            // Con cursor se ignora el offset
+            assert_eq!(params.resolve::<Client>().unwrap().offset, 0);
+
+            let params = PageParams { cursor: Some(cursor), sort_by: Some("age".to_string()), ..Default::default() };
+            assert!(params.resolve::<Client>().is_err());
+        }
+
+        #[test]
+        fn test_next_link_replaces_offset_with_cursor() {
+            let params = PageParams { limit: Some(10), offset: Some(20), ..Default::default() };
+            let page = params.resolve::<Client>().unwrap();
+            let result: PageResult<Client> = PageResult {
+                data: Vec::new(),
+                total: 42,
+                next_cursor: Some("abc".to_string()),
+            };
+
+            let page = result.into_page(&page, "/clients/filter", "active=true&limit=10&offset=20");
+            assert_eq!(page.total, 42);
+            assert_eq!(page.links.self_link, "/clients/filter?active=true&limit=10&offset=20");
+            assert_eq!(page.links.next.as_deref(), Some("/clients/filter?active=true&limit=10&cursor=abc"));
+        }
+    }
+
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_missing_token_is_unauthorized() {
+            let app = init_app(crate::clients::routes).await;
+
+            let req = test::TestRequest::get().uri("/clients/").to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn test_debt_endpoints_require_payments_read() {
+            let app = init_app(crate::clients::routes).await;
+
+            for uri in ["/clients/1/balance", "/clients/debtors"] {
+                let req = test::TestRequest::get()
+                    .uri(uri)
+                    .insert_header(bearer("Guest", TokenType::Access))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+            }
+
+            let req = test::TestRequest::get()
+                .uri("/clients/debtors?min_days=-1")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+
+        #[actix_web::test]
+        async fn test_search_without_terms_is_unprocessable() {
+            let app = init_app(crate::clients::routes).await;
+
+            let req = test::TestRequest::get()
+                .uri("/clients/search?q=%25%25")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+
+        // La paginación se valida antes de consultar la base
+        #[actix_web::test]
+        async fn test_unknown_sort_is_unprocessable() {
+            let app = init_app(crate::clients::routes).await;
+
+            let req = test::TestRequest::get()
+                .uri("/clients/?sort_by=phone")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+
+        #[actix_web::test]
+        async fn test_refresh_token_is_unauthorized() {
+            let app = init_app(crate::clients::routes).await;
+
+            let req = test::TestRequest::get()
+                .uri("/clients/")
+                .insert_header(bearer("Admin", TokenType::Refresh))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_update_client() {
+            let app = init_app(crate::clients::routes).await;
+
+            let req = test::TestRequest::put()
+                .uri("/clients/1")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(super::create_test_create_client_request())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_delete_client() {
+            let app = init_app(crate::clients::routes).await;
+
+            let req = test::TestRequest::delete()
+                .uri("/clients/1")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_admin_passes_authorization() {
+            let app = init_app(crate::clients::routes).await;
+
+            let req = test::TestRequest::patch()
+                .uri("/clients/1")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_ne!(resp.status(), StatusCode::UNAUTHORIZED);
+            assert_ne!(resp.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
     // Tests de integración más avanzados (requieren configuración de DB de prueba)
     #[cfg(feature = "integration-tests")]
     mod integration_tests {
@@ -0,0 +1,249 @@
+use chrono::{Duration, NaiveDate, Utc};
+use sqlx::mysql::MySqlArguments;
+use sqlx::{self, Arguments, MySqlConnection, MySqlPool, Row};
+use crate::add_filter;
+use crate::subscription::handlers::refresh_balance;
+use super::models::closures::Closure;
+use super::models::requests::{ClosureQueryParams, NewClosureRequest};
+
+pub async fn get_closure_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<Closure>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM closures WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| Closure::from_row(&row)))
+}
+
+// Con `discipline_id` lista los cierres que la afectan, incluidos los de todo el gimnasio
+pub async fn get_closures(
+    pool: &MySqlPool,
+    params: &ClosureQueryParams,
+) -> Result<Vec<Closure>, sqlx::Error> {
+    let mut query = String::from("SELECT * FROM closures WHERE 1=1");
+    let mut args = MySqlArguments::default();
+
+    add_filter!(query, args, &params.discipline_id, " AND (discipline_id IS NULL OR discipline_id = ?)");
+    add_filter!(query, args, &params.from, " AND (recurring = true OR ends_on >= ?)");
+    query.push_str(" ORDER BY starts_on, id");
+
+    let rows = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(Closure::from_row).collect())
+}
+
+// Cierre que afecta a la disciplina en `date`, si lo hay
+pub async fn get_closure_on(
+    pool: &MySqlPool,
+    date: NaiveDate,
+    discipline_id: i32,
+) -> Result<Option<Closure>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM closures
+        WHERE (discipline_id IS NULL OR discipline_id = ?)
+          AND (recurring = true OR (starts_on <= ? AND ends_on >= ?))
+        ORDER BY starts_on, id
+        "#)
+        .bind(discipline_id)
+        .bind(date)
+        .bind(date)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter()
+        .map(Closure::from_row)
+        .find(|closure| closure.applies_to(discipline_id) && closure.is_closed_on(date)))
+}
+
+pub async fn count_extended_subscriptions(
+    pool: &MySqlPool,
+    closure_id: i32,
+) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT COUNT(DISTINCT subscription_id) AS total FROM closure_extensions WHERE closure_id = ? AND applied_at IS NOT NULL")
+        .bind(closure_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(row.get("total"))
+}
+
+pub async fn create_closure_handler(
+    pool: &MySqlPool,
+    req: &NewClosureRequest,
+    created_by: Option<i32>,
+) -> Result<Closure, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO closures (name, starts_on, ends_on, recurring, discipline_id, created_by)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(req.name.trim())
+        .bind(req.starts_on)
+        .bind(req.ends_on)
+        .bind(req.recurring)
+        .bind(req.discipline_id)
+        .bind(created_by)
+        .execute(pool)
+        .await?;
+
+    get_closure_by_id(pool, result.last_insert_id() as i32).await?
+        .ok_or(sqlx::Error::RowNotFound)
+}
+
+// Extiende por los días del cierre los períodos de suscripciones activas que estaban vigentes en la ocurrencia.
+// Cada período se extiende una sola vez por ocurrencia (clave única de closure_extensions), así que volver
+// a aplicarla solo alcanza a las compras nuevas. Devuelve las suscripciones extendidas
+pub async fn apply_closure_occurrence(
+    pool: &MySqlPool,
+    closure: &Closure,
+    starts_on: NaiveDate,
+    ends_on: NaiveDate,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let mut query = String::from(
+        r#"
+        INSERT INTO closure_extensions (closure_id, occurs_on, period_id, subscription_id, days)
+        SELECT ?, ?, p.id, p.subscription_id, ?
+        FROM subscription_periods p
+        JOIN subscriptions s ON s.id = p.subscription_id
+        WHERE s.active = true AND p.starts_at < ? AND p.expires_at > ?
+        "#);
+    let mut args = MySqlArguments::default();
+    let _ = args.add(closure.id);
+    let _ = args.add(starts_on);
+    let _ = args.add(closure.days());
+    let _ = args.add(ends_on + Duration::days(1));
+    let _ = args.add(starts_on);
+    add_filter!(query, args, &closure.discipline_id, " AND s.discipline_id = ?");
+    query.push_str(" ON DUPLICATE KEY UPDATE id = id");
+    sqlx::query_with(&query, args)
+        .execute(&mut *tx)
+        .await?;
+
+    let subscription_ids: Vec<i32> = sqlx::query(
+        r#"
+        SELECT DISTINCT subscription_id FROM closure_extensions
+        WHERE closure_id = ? AND occurs_on = ? AND applied_at IS NULL
+        "#)
+        .bind(closure.id)
+        .bind(starts_on)
+        .fetch_all(&mut *tx)
+        .await?
+        .iter()
+        .map(|row| row.get("subscription_id"))
+        .collect();
+
+    sqlx::query(
+        r#"
+        UPDATE subscription_periods p
+        JOIN closure_extensions x ON x.period_id = p.id
+        SET p.expires_at = p.expires_at + INTERVAL x.days DAY, x.applied_at = NOW()
+        WHERE x.closure_id = ? AND x.occurs_on = ? AND x.applied_at IS NULL
+        "#)
+        .bind(closure.id)
+        .bind(starts_on)
+        .execute(&mut *tx)
+        .await?;
+
+    for subscription_id in &subscription_ids {
+        refresh_balance(&mut tx, *subscription_id).await?;
+    }
+    tx.commit().await?;
+    Ok(subscription_ids.len() as u64)
+}
+
+// Ocurrencias (inicio, fin) de cierres que ya extendieron la suscripción
+pub async fn get_applied_occurrences(
+    conn: &mut MySqlConnection,
+    subscription_id: i32,
+) -> Result<Vec<(NaiveDate, NaiveDate)>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT DISTINCT closure_id, occurs_on, days FROM closure_extensions
+        WHERE subscription_id = ? AND applied_at IS NOT NULL
+        "#)
+        .bind(subscription_id)
+        .fetch_all(conn)
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let occurs_on: NaiveDate = row.get("occurs_on");
+            (occurs_on, occurs_on + Duration::days(row.get::<i32, _>("days") as i64 - 1))
+        })
+        .collect())
+}
+
+// Aplica la ocurrencia en curso o próxima de cada cierre; la usa la tarea programada para alcanzar
+// las compras hechas después de cargar el cierre y las repeticiones anuales
+pub async fn apply_pending_closures(
+    pool: &MySqlPool,
+) -> Result<u64, sqlx::Error> {
+    let today = Utc::now().date_naive();
+    let closures = get_closures(pool, &ClosureQueryParams { discipline_id: None, from: Some(today) }).await?;
+
+    let mut extended = 0;
+    for closure in closures {
+        if let Some((starts_on, ends_on)) = closure.next_occurrence(today) {
+            extended += apply_closure_occurrence(pool, &closure, starts_on, ends_on).await?;
+        }
+    }
+    Ok(extended)
+}
+
+// Borra el cierre descontando los días que había agregado. None si no existía; si no, las suscripciones revertidas
+pub async fn delete_closure_handler(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<u64>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    if sqlx::query("SELECT id FROM closures WHERE id = ? FOR UPDATE")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .is_none() {
+        return Ok(None);
+    }
+
+    let subscription_ids: Vec<i32> = sqlx::query(
+        "SELECT DISTINCT subscription_id FROM closure_extensions WHERE closure_id = ? AND applied_at IS NOT NULL")
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await?
+        .iter()
+        .map(|row| row.get("subscription_id"))
+        .collect();
+
+    // Un período puede tener varias ocurrencias del mismo cierre recurrente: se descuentan juntas
+    sqlx::query(
+        r#"
+        UPDATE subscription_periods p
+        JOIN (
+            SELECT period_id, SUM(days) AS days FROM closure_extensions
+            WHERE closure_id = ? AND applied_at IS NOT NULL
+            GROUP BY period_id
+        ) x ON x.period_id = p.id
+        SET p.expires_at = p.expires_at - INTERVAL x.days DAY
+        "#)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM closures WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    for subscription_id in &subscription_ids {
+        refresh_balance(&mut tx, *subscription_id).await?;
+    }
+    tx.commit().await?;
+    Ok(Some(subscription_ids.len() as u64))
+}
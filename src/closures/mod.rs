@@ -0,0 +1,18 @@
+pub mod models;
+pub mod handlers;
+pub mod services;
+
+use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/closures")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
+            .service(services::list_closures)
+            .service(services::get_closure)
+            .service(services::new_closure)
+            .service(services::delete_closure)
+    );
+}
@@ -0,0 +1,64 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, mysql::MySqlRow};
+use utoipa::ToSchema;
+
+// Cierre del gimnasio entre `starts_on` y `ends_on` inclusive; sin `discipline_id` aplica a todas.
+// Los recurrentes se repiten cada año en las mismas fechas a partir de `starts_on`
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct Closure {
+    pub id: i32,
+    pub name: String,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub recurring: bool,
+    pub discipline_id: Option<i32>,
+    pub created_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+// Misma fecha `years` años después; el 29 de febrero pasa al 28 en los años no bisiestos
+fn shift_years(date: NaiveDate, years: i32) -> NaiveDate {
+    let year = date.year() + years;
+    NaiveDate::from_ymd_opt(year, date.month(), date.day())
+        .or_else(|| NaiveDate::from_ymd_opt(year, date.month(), 28))
+        .unwrap_or(date)
+}
+
+impl Closure {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            name: row.get("name"),
+            starts_on: row.get("starts_on"),
+            ends_on: row.get("ends_on"),
+            recurring: row.get::<i8, _>("recurring") != 0,
+            discipline_id: row.get("discipline_id"),
+            created_by: row.get("created_by"),
+            created_at: row.get("created_at"),
+        }
+    }
+
+    pub fn days(&self) -> i32 {
+        (self.ends_on - self.starts_on).num_days() as i32 + 1
+    }
+
+    // Primera ocurrencia (inicio, fin) que no terminó antes de `date`. None si ya no vuelve a ocurrir
+    pub fn next_occurrence(&self, date: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        if !self.recurring {
+            return (self.ends_on >= date).then_some((self.starts_on, self.ends_on));
+        }
+        let first_year = (date.year() - self.starts_on.year() - 1).max(0);
+        (first_year..=first_year + 2)
+            .map(|years| (shift_years(self.starts_on, years), shift_years(self.ends_on, years)))
+            .find(|(_, ends_on)| *ends_on >= date)
+    }
+
+    pub fn is_closed_on(&self, date: NaiveDate) -> bool {
+        self.next_occurrence(date).is_some_and(|(starts_on, _)| starts_on <= date)
+    }
+
+    pub fn applies_to(&self, discipline_id: i32) -> bool {
+        self.discipline_id.is_none_or(|id| id == discipline_id)
+    }
+}
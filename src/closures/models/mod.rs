@@ -0,0 +1,3 @@
+pub mod closures;
+pub mod requests;
+pub mod responses;
@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+use crate::errors::AppError;
+use crate::validation::validate_not_blank;
+
+// Largo máximo de un cierre, así un recurrente no se superpone con su repetición del año siguiente
+pub const MAX_CLOSURE_DAYS: i64 = 90;
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"name": "Navidad", "starts_on": "2026-12-24", "ends_on": "2026-12-25", "recurring": true}))]
+pub struct NewClosureRequest {
+    #[validate(
+        length(min = 1, max = 100, message = "Name must have between 1 and 100 characters"),
+        custom(function = "validate_not_blank"))]
+    #[schema(min_length = 1, max_length = 100)]
+    pub name: String,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    #[serde(default)]
+    pub recurring: bool,
+    // Sin disciplina cierra todo el gimnasio
+    #[validate(range(min = 1, message = "Invalid discipline"))]
+    #[schema(minimum = 1)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discipline_id: Option<i32>,
+}
+
+impl NewClosureRequest {
+    pub fn validate_dates(&self) -> Result<(), AppError> {
+        let message = if self.ends_on < self.starts_on {
+            "ends_on must not be before starts_on".to_string()
+        } else if (self.ends_on - self.starts_on).num_days() >= MAX_CLOSURE_DAYS {
+            format!("A closure can last at most {} days", MAX_CLOSURE_DAYS)
+        } else {
+            return Ok(());
+        };
+        Err(AppError::Validation { message, details: Some(serde_json::json!({ "field": "ends_on" })) })
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct ClosureQueryParams {
+    pub discipline_id: Option<i32>,
+    // Solo los cierres que ocurren (o se repiten) desde esta fecha
+    pub from: Option<NaiveDate>,
+}
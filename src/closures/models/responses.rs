@@ -0,0 +1,11 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+use super::closures::Closure;
+
+// Cierre con las suscripciones a las que ya les extendió el vencimiento
+#[derive(Serialize, ToSchema)]
+pub struct ClosureSummary {
+    #[serde(flatten)]
+    pub closure: Closure,
+    pub extended_subscriptions: i64,
+}
@@ -0,0 +1,210 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use actix_web_grants::protect;
+use chrono::Utc;
+use sqlx::MySqlPool;
+use validator::Validate;
+use crate::auth::models::jwt_models::Claims;
+use crate::errors::{access_denied, AppError};
+use crate::membership::handlers::get_discipline_by_id;
+use super::handlers::{
+    get_closure_by_id, get_closures, count_extended_subscriptions, create_closure_handler,
+    apply_closure_occurrence, delete_closure_handler};
+use super::models::requests::{ClosureQueryParams, NewClosureRequest};
+use super::models::responses::ClosureSummary;
+
+#[get("/")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn list_closures(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<ClosureQueryParams>,
+) -> Result<HttpResponse, AppError> {
+    let closures = get_closures(&pool, &query).await?;
+    Ok(HttpResponse::Ok().json(closures))
+}
+
+#[get("/{id}")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn get_closure(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let closure = get_closure_by_id(&pool, id.into_inner()).await?
+        .ok_or_else(|| AppError::NotFound("Closure not found".to_string()))?;
+    let extended_subscriptions = count_extended_subscriptions(&pool, closure.id).await?;
+    Ok(HttpResponse::Ok().json(ClosureSummary { closure, extended_subscriptions }))
+}
+
+// Carga el cierre y extiende en el momento las suscripciones afectadas por su ocurrencia en curso o próxima
+// (un cierre puntual puede cargarse después de ocurrido); las siguientes las aplica la tarea apply_closures
+#[post("/")]
+#[protect("Admin", error = "access_denied")]
+pub async fn new_closure(
+    pool: web::Data<MySqlPool>,
+    claims: Option<web::ReqData<Claims>>,
+    req: web::Json<NewClosureRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    req.validate_dates()?;
+    if let Some(discipline_id) = req.discipline_id {
+        match get_discipline_by_id(&pool, discipline_id).await? {
+            Some(discipline) if discipline.deleted_at.is_none() => {},
+            _ => return Err(AppError::Validation {
+                message: "Invalid discipline".to_string(),
+                details: Some(serde_json::json!({ "field": "discipline_id" })),
+            }),
+        }
+    }
+
+    let closure = create_closure_handler(&pool, &req, claims.map(|claims| claims.user_id as i32)).await?;
+    let from = if closure.recurring { Utc::now().date_naive() } else { closure.starts_on };
+    let extended_subscriptions = match closure.next_occurrence(from) {
+        Some((starts_on, ends_on)) => apply_closure_occurrence(&pool, &closure, starts_on, ends_on).await?,
+        None => 0,
+    };
+    tracing::info!("Closure {} created, {} subscriptions extended", closure.id, extended_subscriptions);
+    Ok(HttpResponse::Created().json(ClosureSummary { closure, extended_subscriptions: extended_subscriptions as i64 }))
+}
+
+// Borra el cierre y revierte las extensiones que había aplicado
+#[delete("/{id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn delete_closure(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let closure_id = id.into_inner();
+    let reverted = delete_closure_handler(&pool, closure_id).await?
+        .ok_or_else(|| AppError::NotFound("Closure not found".to_string()))?;
+    tracing::info!("Closure {} deleted, {} subscriptions reverted", closure_id, reverted);
+    Ok(HttpResponse::Ok().body("Closure deleted successfully"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, Utc};
+    use validator::Validate;
+    use crate::closures::models::closures::Closure;
+    use crate::closures::models::requests::NewClosureRequest;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn closure(starts_on: &str, ends_on: &str, recurring: bool) -> Closure {
+        Closure {
+            id: 1,
+            name: "Navidad".to_string(),
+            starts_on: date(starts_on),
+            ends_on: date(ends_on),
+            recurring,
+            discipline_id: None,
+            created_by: Some(1),
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn test_one_off_closure() {
+        let closure = closure("2026-11-23", "2026-11-24", false);
+        assert_eq!(closure.days(), 2);
+        assert_eq!(closure.next_occurrence(date("2026-10-18")), Some((date("2026-11-23"), date("2026-11-24"))));
+        assert_eq!(closure.next_occurrence(date("2026-11-25")), None);
+        assert!(!closure.is_closed_on(date("2026-11-22")));
+        assert!(closure.is_closed_on(date("2026-11-24")));
+        assert!(!closure.is_closed_on(date("2027-11-24")));
+    }
+
+    #[test]
+    fn test_recurring_closure_repeats_every_year() {
+        let christmas = closure("2026-12-24", "2026-12-25", true);
+        assert_eq!(christmas.next_occurrence(date("2026-10-18")), Some((date("2026-12-24"), date("2026-12-25"))));
+        assert_eq!(christmas.next_occurrence(date("2026-12-26")), Some((date("2027-12-24"), date("2027-12-25"))));
+        assert!(christmas.is_closed_on(date("2030-12-25")));
+        assert!(!christmas.is_closed_on(date("2030-12-26")));
+        // No ocurre antes de la primera fecha cargada
+        assert!(!christmas.is_closed_on(date("2025-12-24")));
+
+        // Cruza el año: del 31/12 al 1/1
+        let new_year = closure("2026-12-31", "2027-01-01", true);
+        assert!(new_year.is_closed_on(date("2028-01-01")));
+        assert_eq!(new_year.next_occurrence(date("2027-06-01")), Some((date("2027-12-31"), date("2028-01-01"))));
+    }
+
+    #[test]
+    fn test_closure_discipline() {
+        let mut closure = closure("2026-11-23", "2026-11-23", false);
+        assert!(closure.applies_to(3));
+        closure.discipline_id = Some(2);
+        assert!(closure.applies_to(2));
+        assert!(!closure.applies_to(3));
+    }
+
+    #[test]
+    fn test_closure_request_validation() {
+        let request: NewClosureRequest = serde_json::from_str(
+            r#"{"name":"Mantenimiento","starts_on":"2026-11-02","ends_on":"2026-11-04"}"#).unwrap();
+        assert!(!request.recurring);
+        assert!(request.validate().is_ok());
+        assert!(request.validate_dates().is_ok());
+
+        let request: NewClosureRequest = serde_json::from_str(
+            r#"{"name":"Mantenimiento","starts_on":"2026-11-04","ends_on":"2026-11-02"}"#).unwrap();
+        assert!(request.validate_dates().is_err());
+        let request: NewClosureRequest = serde_json::from_str(
+            r#"{"name":"Obra","starts_on":"2026-11-01","ends_on":"2027-02-01"}"#).unwrap();
+        assert!(request.validate_dates().is_err());
+        let request: NewClosureRequest = serde_json::from_str(
+            r#"{"name":" ","starts_on":"2026-11-01","ends_on":"2026-11-01","discipline_id":0}"#).unwrap();
+        let errors = request.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("name"));
+        assert!(errors.field_errors().contains_key("discipline_id"));
+    }
+
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_missing_token_is_unauthorized() {
+            let app = init_app(crate::closures::routes).await;
+
+            let req = test::TestRequest::get().uri("/closures/").to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_manage_closures() {
+            let app = init_app(crate::closures::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/closures/")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({"name": "Feriado", "starts_on": "2026-11-23", "ends_on": "2026-11-23"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+            let req = test::TestRequest::delete()
+                .uri("/closures/1")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_invalid_dates_are_unprocessable() {
+            let app = init_app(crate::closures::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/closures/")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .set_json(serde_json::json!({"name": "Feriado", "starts_on": "2026-11-24", "ends_on": "2026-11-23"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+}
@@ -1,18 +1,63 @@
 use serde::Deserialize;
 use std::{fs, path::Path};
+use crate::auth::password_policy::PasswordPolicy;
+use crate::auth::login_throttle::LoginThrottle;
+use crate::auth::mfa::MfaPolicy;
+use crate::auth::token_policy::TokenPolicy;
+use crate::auth::keyring::{JwtKeysConfig, Keyring};
+use crate::jobs::scheduler::SchedulerConfig;
+use crate::notifications::dispatch::NotificationConfig;
+use crate::payments::debt_policy::DebtPolicy;
+use crate::validation::PhoneConfig;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct Config {
     pub database_url: String,
     pub api_bind: String,
+    // Clave HS512 histórica; solo se usa si `jwt_keys.keys` está vacío
+    #[serde(default)]
     pub jwt_secret: String,
+    #[serde(default)]
+    pub jwt_keys: JwtKeysConfig,
+    // Duración de los tokens, issuer y audience
+    #[serde(default)]
+    pub tokens: TokenPolicy,
+    // Se arma en `from_file` a partir de `jwt_keys`
+    #[serde(skip)]
+    pub keyring: Keyring,
+    // Token de un solo uso para crear el primer Admin vía /auth/bootstrap
+    #[serde(default)]
+    pub setup_token: Option<String>,
+    #[serde(default)]
+    pub password_policy: PasswordPolicy,
+    #[serde(default)]
+    pub login_throttle: LoginThrottle,
+    #[serde(default)]
+    pub mfa: MfaPolicy,
+    // Normalización de teléfonos de clientes a E.164
+    #[serde(default)]
+    pub phone: PhoneConfig,
+    // Control de deuda al registrar asistencia
+    #[serde(default)]
+    pub debt: DebtPolicy,
+    // Horarios de las tareas programadas
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    // Avisos de vencimiento y canal de entrega
+    #[serde(default)]
+    pub notifications: NotificationConfig,
 }
 
 impl Config {
     pub fn from_file(path: &str) -> Self {
         let config_str = fs::read_to_string(Path::new(path))
             .expect("Failed to read env.json");
-        serde_json::from_str(&config_str)
-            .expect("Failed to parse env.json")
+        let mut config: Config = serde_json::from_str(&config_str)
+            .expect("Failed to parse env.json");
+        config.jwt_keys.check_grace_period(&config.tokens)
+            .unwrap_or_else(|e| panic!("Invalid JWT keyring: {}", e));
+        config.keyring = Keyring::load(&config.jwt_keys, &config.jwt_secret)
+            .unwrap_or_else(|e| panic!("Invalid JWT keyring: {}", e));
+        config
     }
 }
\ No newline at end of file
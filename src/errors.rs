@@ -0,0 +1,216 @@
+use std::fmt;
+use actix_web::{error::{JsonPayloadError, QueryPayloadError}, http::{header, StatusCode}, HttpRequest, HttpResponse, ResponseError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+// Cuerpo de todas las respuestas de error de la API
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[schema(example = json!({
+    "code": "not_found",
+    "message": "Client not found",
+    "details": null
+}))]
+pub struct ErrorResponse {
+    // Identificador estable para que los clientes no dependan del texto del mensaje
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+#[derive(Debug)]
+pub enum AppError {
+    NotFound(String),
+    Validation { message: String, details: Option<Value> },
+    BadRequest(String),
+    Unauthorized(String),
+    Forbidden(String),
+    Conflict(String),
+    TooManyRequests { message: String, retry_after: i64 },
+    Database(sqlx::Error),
+    Internal(String),
+}
+
+impl AppError {
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::Validation { message: message.into(), details: None }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "not_found",
+            Self::Validation { .. } => "validation_error",
+            Self::BadRequest(_) => "bad_request",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::Forbidden(_) => "forbidden",
+            Self::Conflict(_) => "conflict",
+            Self::TooManyRequests { .. } => "too_many_requests",
+            Self::Database(_) => "database_error",
+            Self::Internal(_) => "internal_error",
+        }
+    }
+
+    // Los errores internos nunca exponen el detalle al cliente, solo quedan en el log
+    fn public_message(&self) -> String {
+        match self {
+            Self::NotFound(message)
+            | Self::Validation { message, .. }
+            | Self::BadRequest(message)
+            | Self::Unauthorized(message)
+            | Self::Forbidden(message)
+            | Self::Conflict(message)
+            | Self::TooManyRequests { message, .. } => message.clone(),
+            Self::Database(_) => "Database error".to_string(),
+            Self::Internal(_) => "Internal server error".to_string(),
+        }
+    }
+
+    pub fn to_response_body(&self) -> ErrorResponse {
+        let details = match self {
+            Self::Validation { details, .. } => details.clone(),
+            Self::TooManyRequests { retry_after, .. } => Some(serde_json::json!({ "retry_after": retry_after })),
+            _ => None,
+        };
+        ErrorResponse {
+            code: self.code().to_string(),
+            message: self.public_message(),
+            details,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Database(e) => write!(f, "database error: {}", e),
+            Self::Internal(message) => write!(f, "internal error: {}", message),
+            _ => write!(f, "{}", self.public_message()),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Validation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::Conflict(_) => StatusCode::CONFLICT,
+            Self::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Database(_) | Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if matches!(self, Self::Database(_) | Self::Internal(_)) {
+            tracing::error!("{}", self);
+        }
+        let mut response = HttpResponse::build(self.status_code());
+        if let Self::TooManyRequests { retry_after, .. } = self {
+            response.insert_header((header::RETRY_AFTER, retry_after.to_string()));
+        }
+        response.json(self.to_response_body())
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        match &error {
+            sqlx::Error::RowNotFound => Self::NotFound("Resource not found".to_string()),
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() =>
+                Self::Conflict("Resource already exists".to_string()),
+            sqlx::Error::Database(db_error) if db_error.is_foreign_key_violation() =>
+                Self::Conflict("Resource is referenced by or references missing records".to_string()),
+            _ => Self::Database(error),
+        }
+    }
+}
+
+// Respuesta de `protect` cuando el rol o scope no alcanza: `#[protect(..., error = "access_denied")]`
+pub fn access_denied() -> HttpResponse {
+    AppError::Forbidden("Insufficient permissions".to_string()).error_response()
+}
+
+// Cuerpos JSON que no se pueden deserializar (campo faltante, tipo incorrecto): 422; JSON mal formado: 400
+pub fn json_error_handler(error: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    match error {
+        JsonPayloadError::Deserialize(e) if e.is_data() => AppError::validation(e.to_string()).into(),
+        e => AppError::BadRequest(e.to_string()).into(),
+    }
+}
+
+pub fn query_error_handler(error: QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    AppError::validation(error.to_string()).into()
+}
+
+// Tests para el formato común de errores
+#[cfg(test)]
+mod tests {
+    use actix_web::{body::to_bytes, http::{header, StatusCode}, ResponseError};
+    use super::{access_denied, AppError, ErrorResponse};
+
+    async fn response_body(error: AppError) -> (StatusCode, ErrorResponse) {
+        let response = error.error_response();
+        let status = response.status();
+        let body = to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[actix_web::test]
+    async fn test_not_found_body() {
+        let (status, body) = response_body(AppError::NotFound("Client not found".to_string())).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body.code, "not_found");
+        assert_eq!(body.message, "Client not found");
+        assert!(body.details.is_none());
+    }
+
+    #[actix_web::test]
+    async fn test_validation_is_unprocessable_with_details() {
+        let error = AppError::Validation {
+            message: "Invalid client".to_string(),
+            details: Some(serde_json::json!({ "field": "client_id" })),
+        };
+        let (status, body) = response_body(error).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body.code, "validation_error");
+        assert_eq!(body.details.unwrap()["field"], "client_id");
+    }
+
+    #[actix_web::test]
+    async fn test_too_many_requests_sets_retry_after() {
+        let error = AppError::TooManyRequests { message: "Slow down".to_string(), retry_after: 30 };
+        let response = error.error_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "30");
+    }
+
+    #[actix_web::test]
+    async fn test_database_error_hides_details() {
+        let error = AppError::from(sqlx::Error::PoolTimedOut);
+        let (status, body) = response_body(error).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body.code, "database_error");
+        assert_eq!(body.message, "Database error");
+    }
+
+    #[test]
+    fn test_row_not_found_maps_to_not_found() {
+        let error = AppError::from(sqlx::Error::RowNotFound);
+        assert_eq!(error.status_code(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn test_access_denied_is_json_forbidden() {
+        let response = access_denied();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let body = to_bytes(response.into_body()).await.unwrap();
+        let body: ErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.code, "forbidden");
+    }
+}
@@ -0,0 +1,103 @@
+use sqlx::mysql::MySqlArguments;
+use sqlx::{self, Arguments, MySqlConnection, MySqlPool, Row};
+use crate::add_filter;
+use super::models::jobs::{Job, JobRun, JobSource, JobStatus};
+use super::models::requests::JobRunQueryParams;
+
+fn lock_name(job: Job) -> String {
+    format!("gym_helper:job:{}", job.as_str())
+}
+
+// Lock con nombre de MySQL: si hay varias instancias de la API, solo una corre la tarea.
+// Queda atado a la conexión, así que se libera solo si la instancia se cae a mitad de la ejecución
+pub async fn try_lock_job(
+    conn: &mut MySqlConnection,
+    job: Job,
+) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query("SELECT GET_LOCK(?, 0) AS acquired")
+        .bind(lock_name(job))
+        .fetch_one(conn)
+        .await?;
+    Ok(row.get::<Option<i64>, _>("acquired") == Some(1))
+}
+
+// true si esta conexión tenía el lock y lo liberó
+pub async fn release_job_lock(
+    conn: &mut MySqlConnection,
+    job: Job,
+) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query("SELECT RELEASE_LOCK(?) AS released")
+        .bind(lock_name(job))
+        .fetch_one(conn)
+        .await?;
+    Ok(row.get::<Option<i64>, _>("released") == Some(1))
+}
+
+pub async fn start_job_run(
+    pool: &MySqlPool,
+    job: Job,
+    source: JobSource,
+    triggered_by: Option<i32>,
+) -> Result<i32, sqlx::Error> {
+    let result = sqlx::query("INSERT INTO job_runs (job, source, triggered_by) VALUES (?, ?, ?)")
+        .bind(job.as_str())
+        .bind(source.as_str())
+        .bind(triggered_by)
+        .execute(pool)
+        .await?;
+
+    Ok(result.last_insert_id() as i32)
+}
+
+pub async fn finish_job_run(
+    pool: &MySqlPool,
+    id: i32,
+    status: JobStatus,
+    affected_rows: Option<u64>,
+    error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE job_runs
+        SET status = ?, affected_rows = ?, error = ?, finished_at = NOW()
+        WHERE id = ?
+        "#)
+        .bind(status.as_str())
+        .bind(affected_rows.map(|rows| rows as i32))
+        .bind(error)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_job_run_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<JobRun>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM job_runs WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| JobRun::from_row(&row)))
+}
+
+pub async fn get_job_runs(
+    pool: &MySqlPool,
+    params: &JobRunQueryParams,
+    limit: i64,
+) -> Result<Vec<JobRun>, sqlx::Error> {
+    let mut query = "SELECT * FROM job_runs WHERE 1=1".to_string();
+    let mut args = MySqlArguments::default();
+
+    add_filter!(query, args, params.job.as_ref().map(Job::as_str), " AND job = ?");
+    add_filter!(query, args, params.status.as_ref().map(JobStatus::as_str), " AND status = ?");
+    query.push_str(" ORDER BY started_at DESC, id DESC LIMIT ?");
+    let _ = args.add(limit);
+
+    let rows = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(JobRun::from_row).collect())
+}
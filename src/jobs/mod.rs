@@ -0,0 +1,17 @@
+pub mod models;
+pub mod handlers;
+pub mod services;
+pub mod scheduler;
+
+use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/jobs")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
+            .service(services::list_job_runs)
+            .service(services::run_job_now)
+    );
+}
@@ -0,0 +1,116 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, mysql::MySqlRow};
+use utoipa::ToSchema;
+
+// Tareas que corre el scheduler; el nombre es el que se usa en la config, en la URL y en el historial
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Job {
+    // Da de baja las suscripciones vencidas o sin clases
+    ExpireSubscriptions,
+    // Encola y envía los avisos de vencimiento y renovación
+    SendNotifications,
+    // Extiende las suscripciones por los cierres del gimnasio (compras nuevas y repeticiones anuales)
+    ApplyClosures,
+}
+
+impl Job {
+    pub const ALL: [Job; 3] = [Job::ExpireSubscriptions, Job::SendNotifications, Job::ApplyClosures];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Job::ExpireSubscriptions => "expire_subscriptions",
+            Job::SendNotifications => "send_notifications",
+            Job::ApplyClosures => "apply_closures",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Job> {
+        Job::ALL.into_iter().find(|job| job.as_str() == name)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobSource {
+    Schedule,
+    Manual,
+}
+
+impl JobSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobSource::Schedule => "schedule",
+            JobSource::Manual => "manual",
+        }
+    }
+}
+
+impl From<String> for JobSource {
+    fn from(source: String) -> Self {
+        match source.as_str() {
+            "manual" => JobSource::Manual,
+            _ => JobSource::Schedule,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+        }
+    }
+}
+
+impl From<String> for JobStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "succeeded" => JobStatus::Succeeded,
+            "failed" => JobStatus::Failed,
+            _ => JobStatus::Running,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct JobRun {
+    pub id: i32,
+    pub job: String,
+    pub source: JobSource,
+    pub status: JobStatus,
+    // Filas afectadas por la tarea (por ejemplo, suscripciones dadas de baja)
+    pub affected_rows: Option<i32>,
+    pub error: Option<String>,
+    // Admin que la lanzó a mano
+    pub triggered_by: Option<i32>,
+    pub started_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+impl JobRun {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            job: row.get("job"),
+            source: JobSource::from(row.get::<String, _>("source")),
+            status: JobStatus::from(row.get::<String, _>("status")),
+            affected_rows: row.get("affected_rows"),
+            error: row.get("error"),
+            triggered_by: row.get("triggered_by"),
+            started_at: row.get("started_at"),
+            finished_at: row.get("finished_at"),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+use super::jobs::{Job, JobStatus};
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct JobRunQueryParams {
+    pub job: Option<Job>,
+    pub status: Option<JobStatus>,
+    // Por defecto 50, máximo 500; las más recientes primero
+    pub limit: Option<i64>,
+}
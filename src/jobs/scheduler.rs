@@ -0,0 +1,195 @@
+use chrono::{Duration, NaiveDateTime, NaiveTime, Utc};
+use serde::Deserialize;
+use sqlx::{pool::PoolConnection, MySql, MySqlPool};
+use crate::closures::handlers::apply_pending_closures;
+use crate::config::Config;
+use crate::notifications::dispatch::process_notifications;
+use crate::subscription::handlers::expire_overdue_subscriptions;
+use super::handlers::{try_lock_job, release_job_lock, start_job_run, finish_job_run, get_job_run_by_id};
+use super::models::jobs::{Job, JobRun, JobSource, JobStatus};
+
+// Largo máximo de la columna job_runs.error
+const MAX_ERROR_LENGTH: usize = 1000;
+
+// Cuándo corre una tarea: todos los días a `daily_at` (UTC, "HH:MM:SS") o, si no se indica, cada `every_minutes`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct JobSchedule {
+    pub enabled: bool,
+    pub every_minutes: i64,
+    pub daily_at: Option<NaiveTime>,
+}
+
+impl Default for JobSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            every_minutes: 60,
+            daily_at: None,
+        }
+    }
+}
+
+impl JobSchedule {
+    pub fn next_run(&self, now: NaiveDateTime) -> NaiveDateTime {
+        match self.daily_at {
+            Some(at) => {
+                let today = now.date().and_time(at);
+                if today > now { today } else { today + Duration::days(1) }
+            },
+            None => now + Duration::minutes(self.every_minutes.max(1)),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    // Apagado, las tareas solo corren a mano desde /jobs
+    pub enabled: bool,
+    pub expire_subscriptions: JobSchedule,
+    pub send_notifications: JobSchedule,
+    pub apply_closures: JobSchedule,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            expire_subscriptions: JobSchedule::default(),
+            send_notifications: JobSchedule { every_minutes: 15, ..Default::default() },
+            apply_closures: JobSchedule { daily_at: NaiveTime::from_hms_opt(3, 0, 0), ..Default::default() },
+        }
+    }
+}
+
+impl SchedulerConfig {
+    pub fn schedule(&self, job: Job) -> &JobSchedule {
+        match job {
+            Job::ExpireSubscriptions => &self.expire_subscriptions,
+            Job::SendNotifications => &self.send_notifications,
+            Job::ApplyClosures => &self.apply_closures,
+        }
+    }
+}
+
+async fn execute(pool: &MySqlPool, config: &Config, job: Job) -> Result<u64, String> {
+    match job {
+        Job::ExpireSubscriptions => expire_overdue_subscriptions(pool).await.map_err(|e| e.to_string()),
+        Job::SendNotifications => process_notifications(pool, &config.notifications).await,
+        Job::ApplyClosures => apply_pending_closures(pool).await.map_err(|e| e.to_string()),
+    }
+}
+
+// Conexión que tiene tomado el lock de una tarea. Si se descarta sin haber liberado el lock (error,
+// future cancelado) no vuelve al pool: se cierra y MySQL libera el lock junto con la sesión
+struct JobLock {
+    conn: Option<PoolConnection<MySql>>,
+    job: Job,
+}
+
+impl JobLock {
+    async fn acquire(pool: &MySqlPool, job: Job) -> Result<Option<Self>, sqlx::Error> {
+        let mut conn = pool.acquire().await?;
+        if !try_lock_job(&mut conn, job).await? {
+            return Ok(None);
+        }
+        Ok(Some(Self { conn: Some(conn), job }))
+    }
+
+    async fn release(mut self) {
+        let Some(conn) = self.conn.as_mut() else { return };
+        match release_job_lock(conn, self.job).await {
+            // Liberado: la conexión vuelve al pool
+            Ok(true) => drop(self.conn.take()),
+            Ok(false) => tracing::warn!("Job {} lock was not held when releasing it", self.job.as_str()),
+            Err(e) => tracing::error!("Job {} lock could not be released: {}", self.job.as_str(), e),
+        }
+    }
+}
+
+impl Drop for JobLock {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            drop(conn.detach());
+        }
+    }
+}
+
+fn truncate_error(error: &str) -> String {
+    error.chars().take(MAX_ERROR_LENGTH).collect()
+}
+
+async fn record_run(
+    pool: &MySqlPool,
+    config: &Config,
+    job: Job,
+    source: JobSource,
+    triggered_by: Option<i32>,
+) -> Result<Option<JobRun>, sqlx::Error> {
+    let run_id = start_job_run(pool, job, source, triggered_by).await?;
+    let (status, affected, error) = match execute(pool, config, job).await {
+        Ok(affected) => {
+            tracing::info!("Job {} finished: {} rows affected", job.as_str(), affected);
+            (JobStatus::Succeeded, Some(affected), None)
+        },
+        Err(e) => {
+            let error = truncate_error(&e);
+            tracing::error!("Job {} failed: {}", job.as_str(), error);
+            (JobStatus::Failed, None, Some(error))
+        },
+    };
+    // Si no se puede guardar el resultado, se intenta al menos marcarla como fallida para que no quede
+    // en `running` para siempre
+    if let Err(e) = finish_job_run(pool, run_id, status, affected, error.as_deref()).await {
+        tracing::error!("Job {} result could not be recorded: {}", job.as_str(), e);
+        let error = truncate_error(&format!("Could not record job result: {}", e));
+        finish_job_run(pool, run_id, JobStatus::Failed, None, Some(&error)).await?;
+    }
+    get_job_run_by_id(pool, run_id).await
+}
+
+// Corre la tarea dejando registro en job_runs. None si otra instancia la está corriendo
+pub async fn run_job(
+    pool: &MySqlPool,
+    config: &Config,
+    job: Job,
+    source: JobSource,
+    triggered_by: Option<i32>,
+) -> Result<Option<JobRun>, sqlx::Error> {
+    let Some(lock) = JobLock::acquire(pool, job).await? else {
+        return Ok(None);
+    };
+    let result = record_run(pool, config, job, source, triggered_by).await;
+    lock.release().await;
+    result
+}
+
+// Lanza un loop por tarea habilitada; se llama una vez desde `main`
+pub fn start(pool: MySqlPool, config: &Config) {
+    if !config.scheduler.enabled {
+        tracing::info!("Job scheduler disabled");
+        return;
+    }
+    for job in Job::ALL {
+        let schedule = config.scheduler.schedule(job).clone();
+        if !schedule.enabled {
+            continue;
+        }
+        let pool = pool.clone();
+        let config = config.clone();
+        actix_web::rt::spawn(async move {
+            loop {
+                let now = Utc::now().naive_utc();
+                let next = schedule.next_run(now);
+                tracing::debug!("Job {} scheduled for {}", job.as_str(), next);
+                actix_web::rt::time::sleep((next - now).to_std().unwrap_or_default()).await;
+                match run_job(&pool, &config, job, JobSource::Schedule, None).await {
+                    Ok(Some(_)) => {},
+                    Ok(None) => tracing::debug!("Job {} is running in another instance", job.as_str()),
+                    Err(e) => tracing::error!("Job {} could not run: {}", job.as_str(), e),
+                }
+            }
+        });
+    }
+}
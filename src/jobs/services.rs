@@ -0,0 +1,145 @@
+use actix_web::{get, post, web, HttpResponse};
+use actix_web_grants::protect;
+use sqlx::MySqlPool;
+use crate::auth::models::jwt_models::Claims;
+use crate::config::Config;
+use crate::errors::{access_denied, AppError};
+use super::handlers::get_job_runs;
+use super::models::jobs::{Job, JobSource};
+use super::models::requests::JobRunQueryParams;
+use super::scheduler::run_job;
+
+const DEFAULT_RUNS_LIMIT: i64 = 50;
+const MAX_RUNS_LIMIT: i64 = 500;
+
+#[get("/runs")]
+#[protect("Admin", error = "access_denied")]
+pub async fn list_job_runs(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<JobRunQueryParams>,
+) -> Result<HttpResponse, AppError> {
+    let limit = query.limit.unwrap_or(DEFAULT_RUNS_LIMIT);
+    if !(1..=MAX_RUNS_LIMIT).contains(&limit) {
+        return Err(AppError::Validation {
+            message: format!("Limit must be between 1 and {}", MAX_RUNS_LIMIT),
+            details: Some(serde_json::json!({ "field": "limit" })),
+        })
+    }
+    let runs = get_job_runs(&pool, &query, limit).await?;
+    Ok(HttpResponse::Ok().json(runs))
+}
+
+// Ejecuta la tarea en el momento y devuelve el registro de la ejecución
+#[post("/{job}/run")]
+#[protect("Admin", error = "access_denied")]
+pub async fn run_job_now(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
+    claims: Option<web::ReqData<Claims>>,
+    job: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    let job = Job::from_name(&job)
+        .ok_or_else(|| AppError::NotFound("Job not found".to_string()))?;
+    let triggered_by = claims.map(|claims| claims.user_id as i32);
+
+    // Corre en su propia tarea: si el cliente se desconecta, la ejecución termina igual y libera el lock
+    let pool = pool.get_ref().clone();
+    let config = config.into_inner();
+    let run = actix_web::rt::spawn(async move {
+        run_job(&pool, &config, job, JobSource::Manual, triggered_by).await
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Job {} task failed: {}", job.as_str(), e)))??
+    .ok_or_else(|| AppError::Conflict("Job is already running".to_string()))?;
+    tracing::info!("Job {} run manually (run {})", job.as_str(), run.id);
+    Ok(HttpResponse::Ok().json(run))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+    use crate::jobs::models::jobs::{Job, JobStatus};
+    use crate::jobs::scheduler::{JobSchedule, SchedulerConfig};
+
+    #[test]
+    fn test_job_names() {
+        for job in Job::ALL {
+            assert_eq!(Job::from_name(job.as_str()), Some(job));
+            assert_eq!(serde_json::to_string(&job).unwrap(), format!(r#""{}""#, job.as_str()));
+        }
+        assert_eq!(Job::from_name("drop_tables"), None);
+        assert_eq!(JobStatus::from("failed".to_string()), JobStatus::Failed);
+    }
+
+    #[test]
+    fn test_interval_schedule() {
+        let now = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap().and_hms_opt(10, 30, 0).unwrap();
+        let schedule = JobSchedule { every_minutes: 15, ..Default::default() };
+        assert_eq!(schedule.next_run(now), now + chrono::Duration::minutes(15));
+        // Un intervalo inválido no deja el loop girando sin pausa
+        let schedule = JobSchedule { every_minutes: 0, ..Default::default() };
+        assert_eq!(schedule.next_run(now), now + chrono::Duration::minutes(1));
+    }
+
+    #[test]
+    fn test_daily_schedule() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let schedule = JobSchedule { daily_at: NaiveTime::from_hms_opt(3, 0, 0), ..Default::default() };
+        assert_eq!(schedule.next_run(day.and_hms_opt(2, 59, 0).unwrap()), day.and_hms_opt(3, 0, 0).unwrap());
+        // A la hora exacta ya corrió: la próxima es mañana
+        let tomorrow = day.succ_opt().unwrap().and_hms_opt(3, 0, 0).unwrap();
+        assert_eq!(schedule.next_run(day.and_hms_opt(3, 0, 0).unwrap()), tomorrow);
+        assert_eq!(schedule.next_run(day.and_hms_opt(23, 0, 0).unwrap()), tomorrow);
+    }
+
+    #[test]
+    fn test_scheduler_config() {
+        let config: SchedulerConfig = serde_json::from_str(
+            r#"{"expire_subscriptions": {"daily_at": "03:30:00"}}"#).unwrap();
+        assert!(config.enabled);
+        let schedule = config.schedule(Job::ExpireSubscriptions);
+        assert!(schedule.enabled);
+        assert_eq!(schedule.daily_at, NaiveTime::from_hms_opt(3, 30, 0));
+    }
+
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_only_admin_can_run_jobs() {
+            let app = init_app(crate::jobs::routes).await;
+
+            let req = test::TestRequest::post().uri("/jobs/expire_subscriptions/run").to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let req = test::TestRequest::post()
+                .uri("/jobs/expire_subscriptions/run")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_unknown_job_is_not_found() {
+            let app = init_app(crate::jobs::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/jobs/drop_tables/run")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+            let req = test::TestRequest::get()
+                .uri("/jobs/runs?limit=0")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+}
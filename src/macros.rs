@@ -6,4 +6,15 @@ macro_rules! add_filter {
             let _ = $args.add(val);
         }
     };
-}
\ No newline at end of file
+}
+
+// Igual que add_filter! pero para armar el SET de un UPDATE parcial
+#[macro_export]
+macro_rules! add_update {
+    ($sets:ident, $args:ident, $field:expr, $column:expr) => {
+        if let Some(val) = $field {
+            $sets.push(concat!($column, " = ?"));
+            let _ = $args.add(val);
+        }
+    };
+}
@@ -1,12 +1,24 @@
 mod config;
 mod db;
+mod errors;
 mod logging;
 mod macros;
 mod auth;
 mod clients;
 mod membership;
 mod subscription;
+mod users;
+mod api_keys;
 mod openapi;
+mod money;
+mod pagination;
+mod payments;
+mod cash_register;
+mod jobs;
+mod notifications;
+mod closures;
+mod schedule;
+mod validation;
 
 use actix_web::{web, App, HttpServer};
 use config::Config;
@@ -26,6 +38,15 @@ async fn main() -> std::io::Result<()> {
 
     tracing::info!("🚀 Gym Helper has been started.");
 
+    match clients::handlers::backfill_search_text(&db_pool).await {
+        Ok(0) => {}
+        Ok(updated) => tracing::info!("Search text filled for {} clients", updated),
+        Err(e) => tracing::error!("Error filling client search text: {}", e),
+    }
+
+    // Tareas programadas (vencimiento de suscripciones, avisos)
+    jobs::scheduler::start(db_pool.clone(), &config);
+
     let address = config.api_bind.clone();
  
     HttpServer::new(move || {
@@ -36,6 +57,8 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(db_pool.clone()))
             .app_data(web::Data::new(config.clone()))
             .app_data(BearerConfig::default().realm("jwt"))
+            .app_data(web::JsonConfig::default().error_handler(errors::json_error_handler))
+            .app_data(web::QueryConfig::default().error_handler(errors::query_error_handler))
             // OpenAPI/Swagger documentation
             .service(
                 SwaggerUi::new("/swagger-ui/{_:.*}")
@@ -53,6 +76,14 @@ async fn main() -> std::io::Result<()> {
             .configure(clients::routes)
             .configure(membership::routes)
             .configure(subscription::routes)
+            .configure(payments::routes)
+            .configure(cash_register::routes)
+            .configure(jobs::routes)
+            .configure(notifications::routes)
+            .configure(closures::routes)
+            .configure(schedule::routes)
+            .configure(users::routes)
+            .configure(api_keys::routes)
             .service(
                 web::scope("/api").wrap(auth)
                     .service(test_of_auth))
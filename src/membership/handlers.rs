@@ -1,7 +1,15 @@
-use sqlx::mysql::MySqlQueryResult;
-use sqlx::{self, MySqlPool};
-use super::models::membership::Membership;
-use super::models::requests::{NewMembershipRequest, NewDisciplineRequest};
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use sqlx::mysql::{MySqlArguments, MySqlQueryResult};
+use sqlx::{self, Arguments, MySqlConnection, MySqlPool, Row};
+use super::models::membership::{Discipline, Membership, MembershipPrice};
+use super::models::requests::{
+    NewMembershipRequest, NewDisciplineRequest, UpdateMembershipRequest, UpdateDisciplineRequest,
+    MembershipQueryParams, DisciplineQueryParams};
+use crate::{add_filter, add_update};
+use validator::ValidationErrors;
+use crate::errors::AppError;
+use crate::money::{apply_percentage, DEFAULT_CURRENCY};
 
 /////////////////////////////////////////////////////////////////////////////////
 /////////////////// DISCIPLINE HANDLERS //////////////////////////////////
@@ -24,6 +32,81 @@ pub async fn create_discipline_handler(
     result
 }
 
+pub async fn get_discipline_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<Discipline>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT * FROM disciplines WHERE id = ?
+        "#)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| Discipline::from_row(&row)))
+}
+
+pub async fn get_disciplines(
+    pool: &MySqlPool,
+    params: &DisciplineQueryParams,
+) -> Result<Vec<Discipline>, sqlx::Error> {
+    let mut query = String::from("SELECT * FROM disciplines WHERE 1=1");
+    let mut args = MySqlArguments::default();
+
+    add_filter!(query, args, &params.name, " AND name = ?");
+    match params.active {
+        Some(true) => query.push_str(" AND deleted_at IS NULL"),
+        Some(false) => query.push_str(" AND deleted_at IS NOT NULL"),
+        None => {},
+    }
+    query.push_str(" ORDER BY name, id");
+
+    let rows = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(Discipline::from_row).collect())
+}
+
+pub async fn update_discipline_handler(
+    pool: &MySqlPool,
+    id: i32,
+    req: NewDisciplineRequest,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE disciplines
+        SET name = ?, description = ?
+        WHERE id = ?
+        "#
+    )
+    .bind(req.name)
+    .bind(req.description)
+    .bind(id)
+    .execute(pool)
+    .await;
+
+    result
+}
+
+pub async fn patch_discipline_handler(
+    pool: &MySqlPool,
+    id: i32,
+    req: UpdateDisciplineRequest,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    let mut sets = Vec::new();
+    let mut args = MySqlArguments::default();
+
+    add_update!(sets, args, req.name, "name");
+    add_update!(sets, args, req.description, "description");
+    let _ = args.add(id);
+
+    let query = format!("UPDATE disciplines SET {} WHERE id = ?", sets.join(", "));
+    sqlx::query_with(&query, args)
+        .execute(pool)
+        .await
+}
+
 pub async fn delete_discipline_handler(
     pool: &MySqlPool,
     id: i32,
@@ -64,14 +147,26 @@ pub async fn activate_discipline_handler(
 /////////////////// MEMBERSHIP HANDLERS /////////////////////////////////////////
 /////////////////////////////////////////////////////////////////////////////////
 
+// Planes con el precio vigente según `membership_prices`; `memberships.price` queda como respaldo
+// para planes sin historial. Se usa como tabla derivada para que los filtros de precio apliquen al vigente
+const MEMBERSHIPS_IN_EFFECT: &str = r#"(
+    SELECT m.id, m.name, m.description, m.discipline_id, m.total_classes, m.active, m.duration_days,
+        m.max_freezes, m.max_freeze_days,
+        m.created_at, m.updated_at, m.deleted_at,
+        COALESCE(p.price, m.price) AS price, COALESCE(p.currency, m.currency) AS currency
+    FROM memberships m
+    LEFT JOIN membership_prices p ON p.id = (
+        SELECT id FROM membership_prices
+        WHERE membership_id = m.id AND effective_from <= NOW()
+        ORDER BY effective_from DESC, id DESC
+        LIMIT 1)
+) AS memberships"#;
+
 pub async fn get_membership_by_id(
     pool: &MySqlPool,
     id: i32,
 ) -> Result<Option<Membership>, sqlx::Error> {
-    let row = sqlx::query(
-        r#"
-        SELECT * FROM memberships WHERE id = ?
-        "#)
+    let row = sqlx::query(&format!("SELECT * FROM {} WHERE id = ?", MEMBERSHIPS_IN_EFFECT))
         .bind(id)
         .fetch_optional(pool)
         .await?;
@@ -84,29 +179,116 @@ pub async fn get_membership_by_id(
     }
 }
 
-pub async fn create_membership_handler(
+pub async fn get_memberships(
     pool: &MySqlPool,
+    params: &MembershipQueryParams,
+) -> Result<Vec<Membership>, sqlx::Error> {
+    let mut query = format!("SELECT * FROM {} WHERE 1=1", MEMBERSHIPS_IN_EFFECT);
+    let mut args = MySqlArguments::default();
+
+    add_filter!(query, args, &params.discipline_id, " AND discipline_id = ?");
+    add_filter!(query, args, &params.active, " AND active = ?");
+    add_filter!(query, args, &params.currency, " AND currency = ?");
+    add_filter!(query, args, &params.price_min, " AND price >= ?");
+    add_filter!(query, args, &params.price_max, " AND price <= ?");
+    query.push_str(" ORDER BY discipline_id, price, id");
+
+    let rows = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(Membership::from_row).collect())
+}
+
+pub async fn create_membership_handler(
+    conn: &mut MySqlConnection,
     req: NewMembershipRequest,
 ) -> Result<MySqlQueryResult, sqlx::Error> {
     let result = sqlx::query(
         r#"
-        INSERT INTO memberships (name, description, price, discipline_id, total_classes, active, duration_days) 
-        VALUES (?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO memberships
+            (name, description, price, currency, discipline_id, total_classes, active, duration_days,
+             max_freezes, max_freeze_days)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#
     )
     .bind(req.name)
     .bind(req.description)
     .bind(req.price)
+    .bind(req.currency.unwrap_or_else(|| DEFAULT_CURRENCY.to_string()))
     .bind(req.discipline_id)
     .bind(req.total_classes)
     .bind(true) // Assuming active is always true when creating a new membership
     .bind(req.duration_days)
-    .execute(pool)
+    .bind(req.max_freezes.unwrap_or(0))
+    .bind(req.max_freeze_days.unwrap_or(0))
+    .execute(conn)
     .await;
 
     result
 }
 
+pub async fn update_membership_handler(
+    conn: &mut MySqlConnection,
+    id: i32,
+    req: NewMembershipRequest,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE memberships
+        SET name = ?, description = ?, price = ?, currency = COALESCE(?, currency),
+            discipline_id = ?, total_classes = ?, duration_days = ?,
+            max_freezes = COALESCE(?, max_freezes), max_freeze_days = COALESCE(?, max_freeze_days)
+        WHERE id = ?
+        "#
+    )
+    .bind(req.name)
+    .bind(req.description)
+    .bind(req.price)
+    .bind(req.currency)
+    .bind(req.discipline_id)
+    .bind(req.total_classes)
+    .bind(req.duration_days)
+    .bind(req.max_freezes)
+    .bind(req.max_freeze_days)
+    .bind(id)
+    .execute(conn)
+    .await;
+
+    result
+}
+
+pub async fn patch_membership_handler(
+    conn: &mut MySqlConnection,
+    id: i32,
+    req: UpdateMembershipRequest,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    let mut sets = Vec::new();
+    let mut args = MySqlArguments::default();
+
+    add_update!(sets, args, req.name, "name");
+    add_update!(sets, args, req.description, "description");
+    add_update!(sets, args, req.price, "price");
+    add_update!(sets, args, req.currency, "currency");
+    add_update!(sets, args, req.discipline_id, "discipline_id");
+    add_update!(sets, args, req.total_classes, "total_classes");
+    add_update!(sets, args, req.duration_days, "duration_days");
+    add_update!(sets, args, req.max_freezes, "max_freezes");
+    add_update!(sets, args, req.max_freeze_days, "max_freeze_days");
+    add_update!(sets, args, req.active, "active");
+    // Mismo criterio que delete/activate: la baja lógica acompaña a `active`
+    match req.active {
+        Some(true) => sets.push("deleted_at = NULL"),
+        Some(false) => sets.push("deleted_at = NOW()"),
+        None => {},
+    }
+    let _ = args.add(id);
+
+    let query = format!("UPDATE memberships SET {} WHERE id = ?", sets.join(", "));
+    sqlx::query_with(&query, args)
+        .execute(conn)
+        .await
+}
+
 pub async fn delete_membership_by_discipline_handler(
     pool: &MySqlPool,
     discipline_id: i32,
@@ -160,3 +342,166 @@ pub async fn activate_membership_handler(
 
     result
 }
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// MEMBERSHIP PRICE HANDLERS ///////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+// Registra el precio de la tabla `memberships` como vigente desde ahora (alta y edición del plan)
+pub async fn record_current_price_handler(
+    conn: &mut MySqlConnection,
+    membership_id: i32,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO membership_prices (membership_id, price, currency, effective_from)
+        SELECT id, price, currency, NOW() FROM memberships WHERE id = ?
+        "#)
+        .bind(membership_id)
+        .execute(conn)
+        .await
+}
+
+pub async fn get_membership_prices(
+    pool: &MySqlPool,
+    membership_id: i32,
+) -> Result<Vec<MembershipPrice>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM membership_prices
+        WHERE membership_id = ?
+        ORDER BY effective_from DESC, id DESC
+        "#)
+        .bind(membership_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(MembershipPrice::from_row).collect())
+}
+
+// Precio y moneda vigentes para un plan en una fecha dada (incluye cambios ya programados antes de esa fecha)
+async fn price_in_effect_at(
+    conn: &mut MySqlConnection,
+    membership_id: i32,
+    at: NaiveDateTime,
+) -> Result<(Decimal, String), sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT COALESCE(p.price, m.price) AS price, COALESCE(p.currency, m.currency) AS currency
+        FROM memberships m
+        LEFT JOIN membership_prices p ON p.id = (
+            SELECT id FROM membership_prices
+            WHERE membership_id = m.id AND effective_from <= ?
+            ORDER BY effective_from DESC, id DESC
+            LIMIT 1)
+        WHERE m.id = ?
+        "#)
+        .bind(at)
+        .bind(membership_id)
+        .fetch_one(conn)
+        .await?;
+    Ok((row.get("price"), row.get("currency")))
+}
+
+async fn insert_price(
+    conn: &mut MySqlConnection,
+    membership_id: i32,
+    price: Decimal,
+    currency: &str,
+    effective_from: NaiveDateTime,
+) -> Result<MembershipPrice, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO membership_prices (membership_id, price, currency, effective_from)
+        VALUES (?, ?, ?, ?)
+        "#)
+        .bind(membership_id)
+        .bind(price)
+        .bind(currency)
+        .bind(effective_from)
+        .execute(&mut *conn)
+        .await?;
+
+    let row = sqlx::query("SELECT * FROM membership_prices WHERE id = ?")
+        .bind(result.last_insert_id())
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(MembershipPrice::from_row(&row))
+}
+
+// Precio resultante de un porcentaje; si no es válido, 422 sobre el campo `percentage`
+fn price_with_percentage(current: Decimal, percentage: Decimal) -> Result<Decimal, AppError> {
+    apply_percentage(current, percentage).map_err(|e| {
+        let mut errors = ValidationErrors::new();
+        errors.add("percentage", e);
+        errors.into()
+    })
+}
+
+// Programa un precio para un plan: explícito o como porcentaje sobre el vigente a esa fecha
+pub async fn schedule_price_handler(
+    pool: &MySqlPool,
+    membership_id: i32,
+    price: Option<Decimal>,
+    percentage: Option<Decimal>,
+    effective_from: NaiveDateTime,
+) -> Result<MembershipPrice, AppError> {
+    let mut tx = pool.begin().await?;
+    let (current, currency) = price_in_effect_at(&mut tx, membership_id, effective_from).await?;
+    let new_price = match (price, percentage) {
+        (Some(price), _) => price,
+        (None, Some(percentage)) => price_with_percentage(current, percentage)?,
+        (None, None) => current,
+    };
+    let scheduled = insert_price(&mut tx, membership_id, new_price, &currency, effective_from).await?;
+    tx.commit().await?;
+    Ok(scheduled)
+}
+
+// Aplica el mismo porcentaje a todos los planes vigentes de una disciplina, en una sola transacción:
+// si el precio de alguno no resulta válido no se programa ninguno
+pub async fn schedule_discipline_prices_handler(
+    pool: &MySqlPool,
+    discipline_id: i32,
+    percentage: Decimal,
+    effective_from: NaiveDateTime,
+) -> Result<Vec<MembershipPrice>, AppError> {
+    let mut tx = pool.begin().await?;
+    let membership_ids: Vec<i32> = sqlx::query(
+        r#"
+        SELECT id FROM memberships
+        WHERE discipline_id = ? AND active = 1 AND deleted_at IS NULL
+        ORDER BY id
+        "#)
+        .bind(discipline_id)
+        .fetch_all(&mut *tx)
+        .await?
+        .iter()
+        .map(|row| row.get("id"))
+        .collect();
+
+    let mut scheduled = Vec::with_capacity(membership_ids.len());
+    for membership_id in membership_ids {
+        let (current, currency) = price_in_effect_at(&mut tx, membership_id, effective_from).await?;
+        let new_price = price_with_percentage(current, percentage)?;
+        scheduled.push(insert_price(&mut tx, membership_id, new_price, &currency, effective_from).await?);
+    }
+    tx.commit().await?;
+    Ok(scheduled)
+}
+
+// Solo se pueden cancelar cambios que todavía no entraron en vigencia
+pub async fn delete_scheduled_price_handler(
+    pool: &MySqlPool,
+    membership_id: i32,
+    price_id: i32,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        DELETE FROM membership_prices
+        WHERE id = ? AND membership_id = ? AND effective_from > NOW()
+        "#)
+        .bind(price_id)
+        .bind(membership_id)
+        .execute(pool)
+        .await
+}
@@ -3,15 +3,29 @@ pub mod handlers;
 pub mod services;
 
 use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
 
 pub fn routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/membership")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
+            // Las rutas /discipline van antes que /{id} para que no se interpreten como un id
             .service(services::new_discipline)
-            .service(services::new_membership)
+            .service(services::list_disciplines)
+            .service(services::get_discipline)
+            .service(services::update_discipline)
             .service(services::delete_discipline)
             .service(services::activate_discipline)
+            .service(services::schedule_discipline_prices)
+            .service(services::new_membership)
+            .service(services::list_memberships)
+            .service(services::get_membership)
+            .service(services::update_membership)
             .service(services::delete_membership)
             .service(services::activate_membership)
+            .service(services::list_membership_prices)
+            .service(services::schedule_membership_price)
+            .service(services::cancel_membership_price)
     );
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::{mysql::MySqlRow, Row};
 use utoipa::ToSchema;
@@ -18,11 +19,17 @@ pub struct Membership {
     pub id: i32,
     pub name: String,
     pub description: Option<String>,
-    pub price: f32,
+    #[schema(value_type = String, example = "12999.99")]
+    pub price: Decimal,
+    // ISO 4217
+    pub currency: String,
     pub discipline_id: i32,
     pub total_classes: i32,
     pub active: bool,
     pub duration_days: i32,
+    // Congelamientos permitidos por compra, en cantidad y en días en total (0 = no se permite)
+    pub max_freezes: i32,
+    pub max_freeze_days: i32,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
@@ -35,6 +42,19 @@ pub struct ClassAttendance {
     pub attended_at: NaiveDateTime,
 }
 
+impl Discipline {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            name: row.get("name"),
+            description: row.get("description"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            deleted_at: row.get("deleted_at"),
+        }
+    }
+}
+
 impl Membership {
     pub fn from_row(row: &MySqlRow) -> Self {
         Self {
@@ -42,13 +62,40 @@ impl Membership {
             name: row.get("name"),
             description: row.get("description"),
             price: row.get("price"),
+            currency: row.get("currency"),
             discipline_id: row.get("discipline_id"),
             total_classes: row.get("total_classes"),
             active: row.get::<i8, _>("active") != 0,
             duration_days: row.get("duration_days"),
+            max_freezes: row.get("max_freezes"),
+            max_freeze_days: row.get("max_freeze_days"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
             deleted_at: row.get("deleted_at"),
         }
     }
-}
\ No newline at end of file
+}
+// Entrada del historial de precios; con `effective_from` futuro es un cambio programado
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct MembershipPrice {
+    pub id: i32,
+    pub membership_id: i32,
+    #[schema(value_type = String, example = "14500.00")]
+    pub price: Decimal,
+    pub currency: String,
+    pub effective_from: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl MembershipPrice {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            membership_id: row.get("membership_id"),
+            price: row.get("price"),
+            currency: row.get("currency"),
+            effective_from: row.get("effective_from"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
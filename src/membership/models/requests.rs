@@ -1,23 +1,172 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use validator::Validate;
+use crate::money::{validate_currency, validate_percentage, validate_price};
+use crate::validation::validate_not_blank;
 
 
-#[derive(Serialize, Deserialize, ToSchema)]
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
 #[schema(example = json!({"name": "CrossFit", "description": "High-intensity functional fitness"}))]
 pub struct NewDisciplineRequest {
+    #[validate(
+        length(min = 1, max = 100, message = "Name must have between 1 and 100 characters"),
+        custom(function = "validate_not_blank"))]
+    #[schema(min_length = 1, max_length = 100)]
     pub name: String,
+    #[validate(length(max = 1000, message = "Description must have at most 1000 characters"))]
+    #[schema(max_length = 1000)]
     pub description: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, ToSchema)]
-#[schema(example = json!({"name": "Plan Básico", "description": "Plan de entrenamiento básico", "price": 50.0, "discipline_id": 1, "total_classes": 12, "duration_days": 30}))]
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"name": "Plan Básico", "description": "Plan de entrenamiento básico", "price": "12999.99", "currency": "ARS", "discipline_id": 1, "total_classes": 12, "duration_days": 30}))]
 pub struct NewMembershipRequest {
+    #[validate(
+        length(min = 1, max = 100, message = "Name must have between 1 and 100 characters"),
+        custom(function = "validate_not_blank"))]
+    #[schema(min_length = 1, max_length = 100)]
     pub name: String,
+    #[validate(length(max = 1000, message = "Description must have at most 1000 characters"))]
+    #[schema(max_length = 1000)]
     pub description: Option<String>,
-    pub price: f32,
+    // Como string o número, con hasta dos decimales
+    #[validate(custom(function = "validate_price"))]
+    #[schema(value_type = String, example = "12999.99")]
+    pub price: Decimal,
+    // Por defecto ARS
+    #[validate(custom(function = "validate_currency"))]
+    #[schema(min_length = 3, max_length = 3, pattern = "^[A-Z]{3}$")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[validate(range(min = 1, message = "Invalid discipline"))]
+    #[schema(minimum = 1)]
     pub discipline_id: i32,
+    #[validate(range(min = 1, max = 1000, message = "Total classes must be between 1 and 1000"))]
+    #[schema(minimum = 1, maximum = 1000)]
     pub total_classes: i32,
+    // Sin duración la membresía no vence por tiempo
+    #[validate(range(min = 1, max = 3650, message = "Duration must be between 1 and 3650 days"))]
+    #[schema(minimum = 1, maximum = 3650)]
     pub duration_days: Option<i32>,
+    // Congelamientos por compra; por defecto 0 (el plan no se puede congelar)
+    #[validate(range(min = 0, max = 12, message = "Max freezes must be between 0 and 12"))]
+    #[schema(minimum = 0, maximum = 12)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_freezes: Option<i32>,
+    #[validate(range(min = 0, max = 365, message = "Max freeze days must be between 0 and 365"))]
+    #[schema(minimum = 0, maximum = 365)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_freeze_days: Option<i32>,
+}
+
+// PATCH: solo se actualizan los campos presentes
+#[derive(Serialize, Deserialize, ToSchema, Validate, Default)]
+#[schema(example = json!({"description": "Incluye open box"}))]
+pub struct UpdateDisciplineRequest {
+    #[validate(
+        length(min = 1, max = 100, message = "Name must have between 1 and 100 characters"),
+        custom(function = "validate_not_blank"))]
+    #[schema(min_length = 1, max_length = 100)]
+    pub name: Option<String>,
+    #[validate(length(max = 1000, message = "Description must have at most 1000 characters"))]
+    #[schema(max_length = 1000)]
+    pub description: Option<String>,
+}
+
+impl UpdateDisciplineRequest {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.description.is_none()
+    }
+}
+
+// PATCH: solo se actualizan los campos presentes; `active` permite dar de baja o reactivar el plan
+#[derive(Serialize, Deserialize, ToSchema, Validate, Default)]
+#[schema(example = json!({"price": "14500.00", "total_classes": 16}))]
+pub struct UpdateMembershipRequest {
+    #[validate(
+        length(min = 1, max = 100, message = "Name must have between 1 and 100 characters"),
+        custom(function = "validate_not_blank"))]
+    #[schema(min_length = 1, max_length = 100)]
+    pub name: Option<String>,
+    #[validate(length(max = 1000, message = "Description must have at most 1000 characters"))]
+    #[schema(max_length = 1000)]
+    pub description: Option<String>,
+    #[validate(custom(function = "validate_price"))]
+    #[schema(value_type = Option<String>, example = "12999.99")]
+    pub price: Option<Decimal>,
+    #[validate(custom(function = "validate_currency"))]
+    #[schema(min_length = 3, max_length = 3, pattern = "^[A-Z]{3}$")]
+    pub currency: Option<String>,
+    #[validate(range(min = 1, message = "Invalid discipline"))]
+    #[schema(minimum = 1)]
+    pub discipline_id: Option<i32>,
+    #[validate(range(min = 1, max = 1000, message = "Total classes must be between 1 and 1000"))]
+    #[schema(minimum = 1, maximum = 1000)]
+    pub total_classes: Option<i32>,
+    #[validate(range(min = 1, max = 3650, message = "Duration must be between 1 and 3650 days"))]
+    #[schema(minimum = 1, maximum = 3650)]
+    pub duration_days: Option<i32>,
+    #[validate(range(min = 0, max = 12, message = "Max freezes must be between 0 and 12"))]
+    #[schema(minimum = 0, maximum = 12)]
+    pub max_freezes: Option<i32>,
+    #[validate(range(min = 0, max = 365, message = "Max freeze days must be between 0 and 365"))]
+    #[schema(minimum = 0, maximum = 365)]
+    pub max_freeze_days: Option<i32>,
+    pub active: Option<bool>,
+}
+
+impl UpdateMembershipRequest {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.description.is_none() && self.price.is_none()
+            && self.currency.is_none() && self.discipline_id.is_none() && self.total_classes.is_none()
+            && self.duration_days.is_none() && self.max_freezes.is_none() && self.max_freeze_days.is_none()
+            && self.active.is_none()
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct DisciplineQueryParams {
+    pub name: Option<String>,
+    // true: solo las vigentes; false: solo las dadas de baja
+    pub active: Option<bool>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct MembershipQueryParams {
+    pub discipline_id: Option<i32>,
+    pub active: Option<bool>,
+    pub currency: Option<String>,
+    #[schema(value_type = Option<String>)]
+    pub price_min: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub price_max: Option<Decimal>,
+}
+
+// Cambio de precio programado para un plan: `price` fija el precio nuevo y `percentage` lo calcula
+// sobre el precio vigente a `effective_from` (se envía uno de los dos)
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"percentage": "8.5", "effective_from": "2026-11-01T00:00:00"}))]
+pub struct SchedulePriceRequest {
+    #[validate(custom(function = "validate_price"))]
+    #[schema(value_type = Option<String>, example = "14500.00")]
+    pub price: Option<Decimal>,
+    #[validate(custom(function = "validate_percentage"))]
+    #[schema(value_type = Option<String>, example = "8.5")]
+    pub percentage: Option<Decimal>,
+    // UTC, en el futuro
+    pub effective_from: NaiveDateTime,
+}
+
+// Aumento programado para todos los planes vigentes de una disciplina
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"percentage": "10", "effective_from": "2026-11-01T00:00:00"}))]
+pub struct ScheduleDisciplinePriceRequest {
+    #[validate(custom(function = "validate_percentage"))]
+    #[schema(value_type = String, example = "10")]
+    pub percentage: Decimal,
+    pub effective_from: NaiveDateTime,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -29,4 +178,4 @@ pub struct NewClientMembershipRequest {
 #[derive(Serialize, Deserialize)]
 pub struct NewClassAttendanceRequest {
     pub client_membership_id: i32,
-}
\ No newline at end of file
+}
@@ -1,68 +1,173 @@
 use sqlx::MySqlPool;
-use actix_web::{delete, post, web, patch, HttpResponse};
-use super::models::requests::{NewMembershipRequest, NewDisciplineRequest};
-use super::models::membership::{Discipline, Membership};
+use actix_web::{delete, get, patch, post, put, web, HttpResponse};
+use actix_web_grants::protect;
+use chrono::{NaiveDateTime, Utc};
+use serde::de::DeserializeOwned;
+use validator::Validate;
+use crate::errors::{access_denied, AppError};
+use super::models::requests::{
+    NewMembershipRequest, NewDisciplineRequest, UpdateMembershipRequest, UpdateDisciplineRequest,
+    MembershipQueryParams, DisciplineQueryParams, SchedulePriceRequest, ScheduleDisciplinePriceRequest};
 use super::handlers::{
     create_discipline_handler, create_membership_handler,
     delete_discipline_handler, delete_membership_by_discipline_handler,
     activate_discipline_handler, delete_membership_handler,
-    activate_membership_handler};
+    activate_membership_handler, get_discipline_by_id, get_disciplines,
+    update_discipline_handler, patch_discipline_handler, get_membership_by_id,
+    get_memberships, update_membership_handler, patch_membership_handler,
+    record_current_price_handler, get_membership_prices, schedule_price_handler,
+    schedule_discipline_prices_handler, delete_scheduled_price_handler};
+
+// Helper para los PATCH: sin body se mantiene el comportamiento histórico (reactivar);
+// con body es una actualización parcial
+fn parse_patch_body<T: DeserializeOwned + Validate>(body: &[u8]) -> Result<Option<T>, AppError> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+    let req: T = serde_json::from_slice(body).map_err(|e| match e.classify() {
+        serde_json::error::Category::Data => AppError::validation(e.to_string()),
+        _ => AppError::BadRequest(e.to_string()),
+    })?;
+    req.validate()?;
+    Ok(Some(req))
+}
+
+// Una membresía solo puede apuntar a una disciplina existente y vigente
+async fn ensure_discipline(pool: &MySqlPool, discipline_id: i32) -> Result<(), AppError> {
+    match get_discipline_by_id(pool, discipline_id).await? {
+        Some(discipline) if discipline.deleted_at.is_none() => Ok(()),
+        _ => Err(AppError::Validation {
+            message: "Invalid discipline".to_string(),
+            details: Some(serde_json::json!({ "field": "discipline_id" })),
+        }),
+    }
+}
+
+// Los cambios programados son a futuro; los inmediatos se hacen editando el plan
+fn ensure_future(effective_from: NaiveDateTime) -> Result<(), AppError> {
+    if effective_from <= Utc::now().naive_utc() {
+        return Err(AppError::Validation {
+            message: "effective_from must be in the future".to_string(),
+            details: Some(serde_json::json!({ "field": "effective_from" })),
+        });
+    }
+    Ok(())
+}
 
 /////////////////////////////////////////////////////////////////////////////////
 /////////////////// DISCIPLINE ENDPOINTS //////////////////////////////////
 /////////////////////////////////////////////////////////////////////////////////
 
 #[post("/discipline")]
+#[protect("Admin", error = "access_denied")]
 pub async fn new_discipline(
     pool: web::Data<MySqlPool>,
     req: web::Json<NewDisciplineRequest>
-) -> HttpResponse {
-    match create_discipline_handler(&pool, req.into_inner()).await {
-        Ok(_) => {
-            tracing::info!("Discipline created successfully");
-            HttpResponse::Created().body("Discipline created successfully")
-        },
-        Err(e) => {
-            tracing::error!("Error creating discipline: {}", e);
-            HttpResponse::InternalServerError().body("Error creating discipline")
-        }
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    create_discipline_handler(&pool, req.into_inner()).await?;
+    tracing::info!("Discipline created successfully");
+    Ok(HttpResponse::Created().body("Discipline created successfully"))
+}
+
+#[get("/discipline")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn list_disciplines(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<DisciplineQueryParams>,
+) -> Result<HttpResponse, AppError> {
+    let disciplines = get_disciplines(&pool, &query).await?;
+    Ok(HttpResponse::Ok().json(disciplines))
+}
+
+#[get("/discipline/{id}")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn get_discipline(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>
+) -> Result<HttpResponse, AppError> {
+    match get_discipline_by_id(&pool, id.into_inner()).await? {
+        Some(discipline) => Ok(HttpResponse::Ok().json(discipline)),
+        None => Err(AppError::NotFound("Discipline not found".to_string())),
     }
 }
 
+#[put("/discipline/{id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn update_discipline(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+    req: web::Json<NewDisciplineRequest>
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    let discipline_id = id.into_inner();
+    if get_discipline_by_id(&pool, discipline_id).await?.is_none() {
+        return Err(AppError::NotFound("Discipline not found".to_string()))
+    }
+    update_discipline_handler(&pool, discipline_id, req.into_inner()).await?;
+    tracing::info!("Discipline updated successfully");
+    Ok(HttpResponse::Ok().body("Discipline updated successfully"))
+}
+
 #[delete("/discipline/{id}")]
+#[protect("Admin", error = "access_denied")]
 pub async fn delete_discipline(
     pool: web::Data<MySqlPool>,
     id: web::Path<i32>
-) -> HttpResponse {
+) -> Result<HttpResponse, AppError> {
     let discipline_id = id.into_inner();
-    match delete_discipline_handler(&pool, discipline_id).await {
-        Ok(_) => {
-            delete_membership_by_discipline_handler(&pool, discipline_id).await.unwrap();
-            tracing::info!("Discipline deleted successfully");
-            HttpResponse::Ok().body("Discipline deleted successfully")
-        },
-        Err(e) => {
-            tracing::error!("Error deleting discipline: {}", e);
-            HttpResponse::InternalServerError().body("Error deleting discipline")
-        }
+    if delete_discipline_handler(&pool, discipline_id).await?.rows_affected() == 0 {
+        return Err(AppError::NotFound("Discipline not found".to_string()))
     }
+    delete_membership_by_discipline_handler(&pool, discipline_id).await?;
+    tracing::info!("Discipline deleted successfully");
+    Ok(HttpResponse::Ok().body("Discipline deleted successfully"))
 }
 
 #[patch("/discipline/{id}")]
+#[protect("Admin", error = "access_denied")]
 pub async fn activate_discipline(
     pool: web::Data<MySqlPool>,
-    id: web::Path<i32>
-) -> HttpResponse {
-    match activate_discipline_handler(&pool, id.into_inner()).await {
-        Ok(_) => {
-            tracing::info!("Discipline activated successfully");
-            HttpResponse::Ok().body("Discipline activated successfully")
-        },
-        Err(e) => {
-            tracing::error!("Error activating discipline: {}", e);
-            HttpResponse::InternalServerError().body("Error activating discipline")
+    id: web::Path<i32>,
+    body: web::Bytes,
+) -> Result<HttpResponse, AppError> {
+    let discipline_id = id.into_inner();
+    let Some(req) = parse_patch_body::<UpdateDisciplineRequest>(&body)? else {
+        if activate_discipline_handler(&pool, discipline_id).await?.rows_affected() == 0 {
+            return Err(AppError::NotFound("Discipline not found".to_string()))
         }
+        tracing::info!("Discipline activated successfully");
+        return Ok(HttpResponse::Ok().body("Discipline activated successfully"))
+    };
+
+    if req.is_empty() {
+        return Err(AppError::validation("Nothing to update"))
+    }
+    if get_discipline_by_id(&pool, discipline_id).await?.is_none() {
+        return Err(AppError::NotFound("Discipline not found".to_string()))
     }
+    patch_discipline_handler(&pool, discipline_id, req).await?;
+    tracing::info!("Discipline updated successfully");
+    Ok(HttpResponse::Ok().body("Discipline updated successfully"))
+}
+
+#[post("/discipline/{id}/prices")]
+#[protect("Admin", error = "access_denied")]
+pub async fn schedule_discipline_prices(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+    req: web::Json<ScheduleDisciplinePriceRequest>
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    ensure_future(req.effective_from)?;
+    let discipline_id = id.into_inner();
+    if get_discipline_by_id(&pool, discipline_id).await?.is_none() {
+        return Err(AppError::NotFound("Discipline not found".to_string()))
+    }
+    let scheduled = schedule_discipline_prices_handler(
+        &pool, discipline_id, req.percentage, req.effective_from).await?;
+    tracing::info!("Scheduled {} price changes for discipline {}", scheduled.len(), discipline_id);
+    Ok(HttpResponse::Created().json(scheduled))
 }
 
 /////////////////////////////////////////////////////////////////////////////////
@@ -70,63 +175,174 @@ pub async fn activate_discipline(
 /////////////////////////////////////////////////////////////////////////////////
 
 #[post("/")]
+#[protect("Admin", error = "access_denied")]
 pub async fn new_membership(
     pool: web::Data<MySqlPool>,
     req: web::Json<NewMembershipRequest>
-) -> HttpResponse {
-    match create_membership_handler(&pool, req.into_inner()).await {
-        Ok(_) => {
-            tracing::info!("Membership created successfully");
-            HttpResponse::Created().body("Membership created successfully")
-        },
-        Err(e) => {
-            tracing::error!("Error creating membership: {}", e);
-            HttpResponse::InternalServerError().body("Error creating membership")
-        }
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    ensure_discipline(&pool, req.discipline_id).await?;
+    // El plan y su primer precio del historial se crean juntos
+    let mut tx = pool.begin().await?;
+    let result = create_membership_handler(&mut tx, req.into_inner()).await?;
+    record_current_price_handler(&mut tx, result.last_insert_id() as i32).await?;
+    tx.commit().await?;
+    tracing::info!("Membership created successfully");
+    Ok(HttpResponse::Created().body("Membership created successfully"))
+}
+
+#[get("/")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn list_memberships(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<MembershipQueryParams>,
+) -> Result<HttpResponse, AppError> {
+    let memberships = get_memberships(&pool, &query).await?;
+    Ok(HttpResponse::Ok().json(memberships))
+}
+
+#[get("/{id}")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn get_membership(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>
+) -> Result<HttpResponse, AppError> {
+    match get_membership_by_id(&pool, id.into_inner()).await? {
+        Some(membership) => Ok(HttpResponse::Ok().json(membership)),
+        None => Err(AppError::NotFound("Membership not found".to_string())),
+    }
+}
+
+#[put("/{id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn update_membership(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+    req: web::Json<NewMembershipRequest>
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    let membership_id = id.into_inner();
+    if get_membership_by_id(&pool, membership_id).await?.is_none() {
+        return Err(AppError::NotFound("Membership not found".to_string()))
     }
+    ensure_discipline(&pool, req.discipline_id).await?;
+    let mut tx = pool.begin().await?;
+    update_membership_handler(&mut tx, membership_id, req.into_inner()).await?;
+    record_current_price_handler(&mut tx, membership_id).await?;
+    tx.commit().await?;
+    tracing::info!("Membership updated successfully");
+    Ok(HttpResponse::Ok().body("Membership updated successfully"))
 }
 
 #[delete("/{id}")]
+#[protect("Admin", error = "access_denied")]
 pub async fn delete_membership(
     pool: web::Data<MySqlPool>,
     id: web::Path<i32>
-) -> HttpResponse {
-    match delete_membership_handler(&pool, id.into_inner()).await {
-        Ok(_) => {
-            tracing::info!("Membership deleted successfully");
-            HttpResponse::Ok().body("Membership deleted successfully")
-        },
-        Err(e) => {
-            tracing::error!("Error deleting membership: {}", e);
-            HttpResponse::InternalServerError().body("Error deleting membership")
-        }
+) -> Result<HttpResponse, AppError> {
+    if delete_membership_handler(&pool, id.into_inner()).await?.rows_affected() == 0 {
+        return Err(AppError::NotFound("Membership not found".to_string()))
     }
+    tracing::info!("Membership deleted successfully");
+    Ok(HttpResponse::Ok().body("Membership deleted successfully"))
 }
 
 #[patch("/{id}")]
+#[protect("Admin", error = "access_denied")]
 pub async fn activate_membership(
     pool: web::Data<MySqlPool>,
-    id: web::Path<i32>
-) -> HttpResponse {
-    match activate_membership_handler(&pool, id.into_inner()).await {
-        Ok(_) => {
-            tracing::info!("Membership activated successfully");
-            HttpResponse::Ok().body("Membership activated successfully")
-        },
-        Err(e) => {
-            tracing::error!("Error activating membership: {}", e);
-            HttpResponse::InternalServerError().body("Error activating membership")
+    id: web::Path<i32>,
+    body: web::Bytes,
+) -> Result<HttpResponse, AppError> {
+    let membership_id = id.into_inner();
+    let Some(req) = parse_patch_body::<UpdateMembershipRequest>(&body)? else {
+        if activate_membership_handler(&pool, membership_id).await?.rows_affected() == 0 {
+            return Err(AppError::NotFound("Membership not found".to_string()))
         }
+        tracing::info!("Membership activated successfully");
+        return Ok(HttpResponse::Ok().body("Membership activated successfully"))
+    };
+
+    if req.is_empty() {
+        return Err(AppError::validation("Nothing to update"))
+    }
+    if get_membership_by_id(&pool, membership_id).await?.is_none() {
+        return Err(AppError::NotFound("Membership not found".to_string()))
+    }
+    if let Some(discipline_id) = req.discipline_id {
+        ensure_discipline(&pool, discipline_id).await?;
+    }
+    let price_changed = req.price.is_some() || req.currency.is_some();
+    let mut tx = pool.begin().await?;
+    patch_membership_handler(&mut tx, membership_id, req).await?;
+    if price_changed {
+        record_current_price_handler(&mut tx, membership_id).await?;
+    }
+    tx.commit().await?;
+    tracing::info!("Membership updated successfully");
+    Ok(HttpResponse::Ok().body("Membership updated successfully"))
+}
+
+#[get("/{id}/prices")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn list_membership_prices(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>
+) -> Result<HttpResponse, AppError> {
+    let membership_id = id.into_inner();
+    if get_membership_by_id(&pool, membership_id).await?.is_none() {
+        return Err(AppError::NotFound("Membership not found".to_string()))
     }
+    let prices = get_membership_prices(&pool, membership_id).await?;
+    Ok(HttpResponse::Ok().json(prices))
+}
+
+#[post("/{id}/prices")]
+#[protect("Admin", error = "access_denied")]
+pub async fn schedule_membership_price(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+    req: web::Json<SchedulePriceRequest>
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    if req.price.is_some() == req.percentage.is_some() {
+        return Err(AppError::Validation {
+            message: "Either price or percentage must be provided".to_string(),
+            details: Some(serde_json::json!({ "field": "price" })),
+        })
+    }
+    ensure_future(req.effective_from)?;
+    let membership_id = id.into_inner();
+    if get_membership_by_id(&pool, membership_id).await?.is_none() {
+        return Err(AppError::NotFound("Membership not found".to_string()))
+    }
+    let scheduled = schedule_price_handler(
+        &pool, membership_id, req.price, req.percentage, req.effective_from).await?;
+    tracing::info!("Price change scheduled for membership {}", membership_id);
+    Ok(HttpResponse::Created().json(scheduled))
+}
+
+#[delete("/{id}/prices/{price_id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn cancel_membership_price(
+    pool: web::Data<MySqlPool>,
+    path: web::Path<(i32, i32)>
+) -> Result<HttpResponse, AppError> {
+    let (membership_id, price_id) = path.into_inner();
+    if delete_scheduled_price_handler(&pool, membership_id, price_id).await?.rows_affected() == 0 {
+        return Err(AppError::NotFound("Scheduled price not found".to_string()))
+    }
+    tracing::info!("Scheduled price {} cancelled", price_id);
+    Ok(HttpResponse::Ok().body("Scheduled price cancelled successfully"))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
     use chrono::Utc;
+    use rust_decimal::Decimal;
     use crate::membership::models::{
         membership::{Discipline, Membership},
-        requests::{NewDisciplineRequest, NewMembershipRequest}
+        requests::{NewDisciplineRequest, NewMembershipRequest, UpdateMembershipRequest}
     };
 
     // Helper function para crear una discipline de prueba
@@ -149,11 +365,14 @@ mod tests {
             id: 1,
             name: "Plan Básico".to_string(),
             description: Some("Plan de entrenamiento básico".to_string()),
-            price: 50.0,
+            price: Decimal::new(5000, 2),
+            currency: "ARS".to_string(),
             discipline_id: 1,
             total_classes: 12,
             active: true,
             duration_days: 30,
+            max_freezes: 1,
+            max_freeze_days: 15,
             created_at: now,
             updated_at: now,
             deleted_at: None,
@@ -173,10 +392,13 @@ mod tests {
         NewMembershipRequest {
             name: "Plan Básico".to_string(),
             description: Some("Plan de entrenamiento básico".to_string()),
-            price: 50.0,
+            price: Decimal::new(5000, 2),
+            currency: None,
             discipline_id: 1,
             total_classes: 12,
             duration_days: Some(30),
+            max_freezes: None,
+            max_freeze_days: None,
         }
     }
 
@@ -200,16 +422,17 @@ mod tests {
     fn test_new_membership_request_serialization() {
         let request = create_test_new_membership_request();
         let json = serde_json::to_string(&request).unwrap();
-        let expected = r#"{"name":"Plan Básico","description":"Plan de entrenamiento básico","price":50.0,"discipline_id":1,"total_classes":12,"duration_days":30}"#;
+        let expected = r#"{"name":"Plan Básico","description":"Plan de entrenamiento básico","price":"50.00","discipline_id":1,"total_classes":12,"duration_days":30}"#;
         assert_eq!(json, expected);
     }
 
     #[test]
     fn test_new_membership_request_deserialization() {
-        let json = r#"{"name":"Plan Básico","description":"Plan de entrenamiento básico","price":50.0,"discipline_id":1,"total_classes":12,"duration_days":30}"#;
+        let json = r#"{"name":"Plan Básico","description":"Plan de entrenamiento básico","price":"50.00","discipline_id":1,"total_classes":12,"duration_days":30}"#;
         let request: NewMembershipRequest = serde_json::from_str(json).unwrap();
         assert_eq!(request.name, "Plan Básico");
-        assert_eq!(request.price, 50.0);
+        assert_eq!(request.price, Decimal::new(5000, 2));
+        assert!(request.currency.is_none());
         assert_eq!(request.discipline_id, 1);
         assert_eq!(request.total_classes, 12);
         assert_eq!(request.duration_days, Some(30));
@@ -231,10 +454,11 @@ mod tests {
         
         assert_eq!(membership.id, 1);
         assert_eq!(membership.name, "Plan Básico");
-        assert_eq!(membership.price, 50.0);
+        assert_eq!(membership.price, Decimal::new(5000, 2));
+        assert_eq!(membership.currency, "ARS");
         assert_eq!(membership.discipline_id, 1);
         assert_eq!(membership.total_classes, 12);
-        assert_eq!(membership.active, true);
+        assert!(membership.active);
         assert_eq!(membership.duration_days, 30);
         assert!(membership.deleted_at.is_none());
     }
@@ -255,15 +479,18 @@ mod tests {
         let request = NewMembershipRequest {
             name: "Plan Premium".to_string(),
             description: None,
-            price: 100.0,
+            price: Decimal::new(100, 0),
+            currency: Some("USD".to_string()),
             discipline_id: 2,
             total_classes: 20,
             duration_days: None,
+            max_freezes: None,
+            max_freeze_days: None,
         };
         
         assert_eq!(request.name, "Plan Premium");
         assert!(request.description.is_none());
-        assert_eq!(request.price, 100.0);
+        assert_eq!(request.price, Decimal::new(100, 0));
         assert!(request.duration_days.is_none());
     }
 
@@ -285,119 +512,159 @@ mod tests {
     // Tests para validación de entrada
     mod validation_tests {
         use super::*;
+        use validator::Validate;
+
+        fn membership_request(price: &str, total_classes: i32, duration_days: Option<i32>) -> NewMembershipRequest {
+            NewMembershipRequest {
+                name: "Test Plan".to_string(),
+                description: None,
+                price: price.parse().unwrap(),
+                currency: None,
+                discipline_id: 1,
+                total_classes,
+                duration_days,
+                max_freezes: None,
+                max_freeze_days: None,
+            }
+        }
 
         #[test]
         fn test_discipline_name_validation() {
-            let valid_names = vec![
-                "CrossFit".to_string(),
-                "Yoga".to_string(),
-                "Pilates".to_string(),
-                "Funcional".to_string(),
-            ];
-
-            for name in valid_names {
+            for name in ["CrossFit", "Yoga", "Pilates", "Funcional"] {
                 let request = NewDisciplineRequest {
-                    name: name.clone(),
+                    name: name.to_string(),
                     description: Some("Test description".to_string()),
                 };
-                assert!(!name.is_empty());
-                assert!(name.len() >= 3);
+                assert!(request.validate().is_ok());
+            }
+            for name in ["", "  ", &"x".repeat(101)] {
+                let request = NewDisciplineRequest { name: name.to_string(), description: None };
+                assert!(request.validate().unwrap_err().field_errors().contains_key("name"));
             }
         }
 
         #[test]
         fn test_membership_price_validation() {
-            let valid_prices = vec![10.0, 25.5, 50.0, 100.0, 150.75];
-            let invalid_prices = vec![-10.0, 0.0, -1.0];
+            for price in ["10", "25.5", "50.00", "12999.99", "9999999999.99"] {
+                assert!(membership_request(price, 10, Some(30)).validate().is_ok());
+            }
+            for price in ["-10", "0", "-1.00", "10.999", "10000000000"] {
+                let errors = membership_request(price, 10, Some(30)).validate().unwrap_err();
+                assert!(errors.field_errors().contains_key("price"));
+            }
+        }
 
-            for price in valid_prices {
-                let request = NewMembershipRequest {
-                    name: "Test Plan".to_string(),
-                    description: None,
-                    price,
-                    discipline_id: 1,
-                    total_classes: 10,
-                    duration_days: Some(30),
-                };
-                assert!(price > 0.0);
+        // 12999.99 como FLOAT se leía 12999.990234: con Decimal el importe es exacto de punta a punta
+        #[test]
+        fn test_price_is_exact_and_serialized_as_string() {
+            let request: NewMembershipRequest = serde_json::from_str(
+                r#"{"name":"Plan","price":12999.99,"discipline_id":1,"total_classes":12}"#).unwrap();
+            assert_eq!(request.price, Decimal::new(1299999, 2));
+            let json = serde_json::to_value(&request).unwrap();
+            assert_eq!(json["price"], "12999.99");
+
+            let request: NewMembershipRequest = serde_json::from_str(
+                r#"{"name":"Plan","price":"12999.99","currency":"USD","discipline_id":1,"total_classes":12}"#).unwrap();
+            assert_eq!(request.price, Decimal::new(1299999, 2));
+            assert!(request.validate().is_ok());
+        }
+
+        #[test]
+        fn test_currency_validation() {
+            let mut request = membership_request("50", 10, Some(30));
+            request.currency = Some("USD".to_string());
+            assert!(request.validate().is_ok());
+            for currency in ["usd", "US", "PESOS", "AR$"] {
+                request.currency = Some(currency.to_string());
+                assert!(request.validate().unwrap_err().field_errors().contains_key("currency"));
             }
+        }
 
-            for price in invalid_prices {
-                let request = NewMembershipRequest {
-                    name: "Test Plan".to_string(),
-                    description: None,
-                    price,
-                    discipline_id: 1,
-                    total_classes: 10,
-                    duration_days: Some(30),
-                };
-                assert!(price <= 0.0);
+        #[test]
+        fn test_round_money() {
+            use crate::money::round_money;
+            let cases = [("0.125", "0.13"), ("-0.125", "-0.13"), ("10.004", "10.00"), ("33.333", "33.33"), ("99.995", "100.00")];
+            for (amount, expected) in cases {
+                let rounded = round_money(amount.parse().unwrap());
+                assert_eq!(rounded, expected.parse::<Decimal>().unwrap());
             }
         }
 
         #[test]
-        fn test_membership_classes_validation() {
-            let valid_classes = vec![1, 5, 10, 15, 20, 50];
-            let invalid_classes = vec![-1, 0, -10];
+        fn test_apply_percentage() {
+            use crate::money::apply_percentage;
+            let price = Decimal::new(1200000, 2);
+            assert_eq!(apply_percentage(price, "8.5".parse().unwrap()).unwrap(), Decimal::new(1302000, 2));
+            assert_eq!(apply_percentage(price, "-10".parse().unwrap()).unwrap(), Decimal::new(1080000, 2));
+            // 12999.99 * 1.033 = 13428.98967 -> 13428.99
+            assert_eq!(apply_percentage(Decimal::new(1299999, 2), "3.3".parse().unwrap()).unwrap(), Decimal::new(1342899, 2));
+        }
 
-            for classes in valid_classes {
-                let request = NewMembershipRequest {
-                    name: "Test Plan".to_string(),
-                    description: None,
-                    price: 50.0,
-                    discipline_id: 1,
-                    total_classes: classes,
-                    duration_days: Some(30),
+        #[test]
+        fn test_apply_percentage_validates_resulting_price() {
+            use crate::money::apply_percentage;
+            // -99.99% de 1.00 redondea a 0.00
+            assert!(apply_percentage(Decimal::new(100, 2), "-99.99".parse().unwrap()).is_err());
+            // +1000% sobre un precio alto se pasa de DECIMAL(12,2)
+            assert!(apply_percentage("1000000000.00".parse().unwrap(), "1000".parse().unwrap()).is_err());
+            assert!(apply_percentage("909090909.09".parse().unwrap(), "1000".parse().unwrap()).is_ok());
+        }
+
+        #[test]
+        fn test_schedule_price_request_validation() {
+            use crate::membership::models::requests::{SchedulePriceRequest, ScheduleDisciplinePriceRequest};
+            let request: SchedulePriceRequest = serde_json::from_str(
+                r#"{"percentage":"8.5","effective_from":"2026-11-01T00:00:00"}"#).unwrap();
+            assert!(request.validate().is_ok());
+            assert!(request.price.is_none());
+
+            for percentage in ["-100", "1000.01", "2.555"] {
+                let request = ScheduleDisciplinePriceRequest {
+                    percentage: percentage.parse().unwrap(),
+                    effective_from: Utc::now().naive_utc(),
                 };
-                assert!(classes > 0);
+                assert!(request.validate().unwrap_err().field_errors().contains_key("percentage"));
             }
+        }
 
-            for classes in invalid_classes {
-                let request = NewMembershipRequest {
-                    name: "Test Plan".to_string(),
-                    description: None,
-                    price: 50.0,
-                    discipline_id: 1,
-                    total_classes: classes,
-                    duration_days: Some(30),
-                };
-                assert!(classes <= 0);
+        #[test]
+        fn test_membership_classes_validation() {
+            for classes in [1, 5, 10, 15, 20, 50] {
+                assert!(membership_request("50", classes, Some(30)).validate().is_ok());
+            }
+            for classes in [-1, 0, -10] {
+                let errors = membership_request("50", classes, Some(30)).validate().unwrap_err();
+                assert!(errors.field_errors().contains_key("total_classes"));
             }
         }
 
         #[test]
         fn test_membership_duration_validation() {
-            let valid_durations = vec![Some(7), Some(15), Some(30), Some(60), Some(90)];
-            let invalid_durations = vec![Some(-1), Some(0), Some(-30)];
-
-            for duration in valid_durations {
-                let request = NewMembershipRequest {
-                    name: "Test Plan".to_string(),
-                    description: None,
-                    price: 50.0,
-                    discipline_id: 1,
-                    total_classes: 10,
-                    duration_days: duration,
-                };
-                if let Some(days) = duration {
-                    assert!(days > 0);
-                }
+            for duration in [None, Some(7), Some(15), Some(30), Some(60), Some(90)] {
+                assert!(membership_request("50", 10, duration).validate().is_ok());
             }
-
-            for duration in invalid_durations {
-                let request = NewMembershipRequest {
-                    name: "Test Plan".to_string(),
-                    description: None,
-                    price: 50.0,
-                    discipline_id: 1,
-                    total_classes: 10,
-                    duration_days: duration,
-                };
-                if let Some(days) = duration {
-                    assert!(days <= 0);
-                }
+            for duration in [Some(-1), Some(0), Some(-30)] {
+                let errors = membership_request("50", 10, duration).validate().unwrap_err();
+                assert!(errors.field_errors().contains_key("duration_days"));
             }
         }
+
+        #[test]
+        fn test_membership_freeze_limits_validation() {
+            let mut request = membership_request("50", 10, Some(30));
+            request.max_freezes = Some(2);
+            request.max_freeze_days = Some(30);
+            assert!(request.validate().is_ok());
+
+            request.max_freezes = Some(-1);
+            request.max_freeze_days = Some(366);
+            let errors = request.validate().unwrap_err();
+            assert!(errors.field_errors().contains_key("max_freezes"));
+            assert!(errors.field_errors().contains_key("max_freeze_days"));
+
+            let patch: UpdateMembershipRequest = serde_json::from_str(r#"{"max_freeze_days":15}"#).unwrap();
+            assert!(!patch.is_empty());
+        }
     }
 
     // Tests para manejo de errores
@@ -483,10 +750,10 @@ mod tests {
         #[test]
         fn test_basic_plans() {
             let plans = vec![
-                ("Plan Básico", 8, 30, 25.0),
-                ("Plan Intermedio", 12, 30, 40.0),
-                ("Plan Avanzado", 16, 30, 55.0),
-                ("Plan Premium", 20, 30, 70.0),
+                ("Plan Básico", 8, 30, Decimal::new(25, 0)),
+                ("Plan Intermedio", 12, 30, Decimal::new(40, 0)),
+                ("Plan Avanzado", 16, 30, Decimal::new(55, 0)),
+                ("Plan Premium", 20, 30, Decimal::new(70, 0)),
             ];
 
             for (name, classes, days, price) in plans {
@@ -494,19 +761,210 @@ mod tests {
                     name: name.to_string(),
                     description: Some(format!("Plan con {} clases por mes", classes)),
                     price,
+                    currency: None,
                     discipline_id: 1,
                     total_classes: classes,
                     duration_days: Some(days),
+                    max_freezes: None,
+                    max_freeze_days: None,
                 };
                 
                 assert!(!request.name.is_empty());
                 assert!(request.total_classes > 0);
-                assert!(request.price > 0.0);
+                assert!(request.price > Decimal::ZERO);
                 assert!(request.duration_days.is_some());
             }
         }
     }
 
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_missing_token_is_unauthorized() {
+            let app = init_app(crate::membership::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/membership/discipline")
+                .set_json(super::create_test_new_discipline_request())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_delete_discipline() {
+            let app = init_app(crate::membership::routes).await;
+
+            let req = test::TestRequest::delete()
+                .uri("/membership/discipline/1")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_create_membership() {
+            let app = init_app(crate::membership::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/membership/")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(super::create_test_new_membership_request())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_admin_passes_authorization() {
+            let app = init_app(crate::membership::routes).await;
+
+            let req = test::TestRequest::patch()
+                .uri("/membership/1")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_ne!(resp.status(), StatusCode::UNAUTHORIZED);
+            assert_ne!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_update_membership() {
+            let app = init_app(crate::membership::routes).await;
+
+            let req = test::TestRequest::put()
+                .uri("/membership/1")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(super::create_test_new_membership_request())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // El body del PATCH se valida antes de consultar la base
+        #[actix_web::test]
+        async fn test_invalid_patch_is_unprocessable() {
+            let app = init_app(crate::membership::routes).await;
+
+            let req = test::TestRequest::patch()
+                .uri("/membership/1")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .set_json(serde_json::json!({"price": -5.0}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+            let req = test::TestRequest::patch()
+                .uri("/membership/discipline/1")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .set_json(serde_json::json!({}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+
+    // Tests de los cambios de precio programados: se rechazan antes de consultar la base
+    mod price_schedule_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_schedule_prices() {
+            let app = init_app(crate::membership::routes).await;
+
+            for uri in ["/membership/1/prices", "/membership/discipline/1/prices"] {
+                let req = test::TestRequest::post()
+                    .uri(uri)
+                    .insert_header(bearer("Trainer", TokenType::Access))
+                    .set_json(serde_json::json!({"percentage": "10", "effective_from": "2099-01-01T00:00:00"}))
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+            }
+        }
+
+        #[actix_web::test]
+        async fn test_invalid_schedules_are_unprocessable() {
+            let app = init_app(crate::membership::routes).await;
+
+            let bodies = [
+                // precio y porcentaje a la vez
+                serde_json::json!({"price": "100", "percentage": "10", "effective_from": "2099-01-01T00:00:00"}),
+                // ninguno de los dos
+                serde_json::json!({"effective_from": "2099-01-01T00:00:00"}),
+                // fecha pasada
+                serde_json::json!({"percentage": "10", "effective_from": "2020-01-01T00:00:00"}),
+            ];
+            for body in bodies {
+                let req = test::TestRequest::post()
+                    .uri("/membership/1/prices")
+                    .insert_header(bearer("Admin", TokenType::Access))
+                    .set_json(body)
+                    .to_request();
+                let resp = test::call_service(&app, req).await;
+                assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+            }
+
+            let req = test::TestRequest::post()
+                .uri("/membership/discipline/1/prices")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .set_json(serde_json::json!({"percentage": "-150", "effective_from": "2099-01-01T00:00:00"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+
+    // Tests de las actualizaciones parciales
+    mod update_tests {
+        use super::*;
+        use crate::membership::models::requests::{UpdateDisciplineRequest, UpdateMembershipRequest};
+        use crate::membership::services::parse_patch_body;
+
+        #[test]
+        fn test_empty_patch_body_means_activation() {
+            assert!(parse_patch_body::<UpdateMembershipRequest>(b"").unwrap().is_none());
+            assert!(parse_patch_body::<UpdateMembershipRequest>(b"  \n").unwrap().is_none());
+        }
+
+        #[test]
+        fn test_patch_body_is_parsed_and_validated() {
+            let req = parse_patch_body::<UpdateMembershipRequest>(br#"{"price": 60.5, "active": false}"#)
+                .unwrap()
+                .unwrap();
+            assert_eq!(req.price, Some(Decimal::new(605, 1)));
+            assert_eq!(req.active, Some(false));
+            assert!(!req.is_empty());
+
+            assert!(parse_patch_body::<UpdateMembershipRequest>(br#"{"total_classes": 0}"#).is_err());
+            assert!(parse_patch_body::<UpdateMembershipRequest>(br#"{"price": "caro"}"#).is_err());
+            assert!(parse_patch_body::<UpdateMembershipRequest>(br#"{"currency": "usd"}"#).is_err());
+            assert!(parse_patch_body::<UpdateDisciplineRequest>(b"{not json").is_err());
+        }
+
+        #[test]
+        fn test_empty_update_requests() {
+            assert!(UpdateMembershipRequest::default().is_empty());
+            assert!(UpdateDisciplineRequest::default().is_empty());
+            let req = UpdateDisciplineRequest { description: Some(String::new()), ..Default::default() };
+            assert!(!req.is_empty());
+        }
+
+        #[test]
+        fn test_discipline_serialization_exposes_deleted_at() {
+            let discipline = create_test_discipline();
+            let json = serde_json::to_value(&discipline).unwrap();
+            assert_eq!(json["name"], "CrossFit");
+            assert!(json["deleted_at"].is_null());
+        }
+    }
+
     // Tests de integración más avanzados (requieren configuración de DB de prueba)
     #[cfg(feature = "integration-tests")]
     mod integration_tests {
@@ -0,0 +1,81 @@
+use std::borrow::Cow;
+use rust_decimal::{Decimal, RoundingStrategy};
+use validator::ValidationError;
+
+// Importes: DECIMAL(12,2) en la base y `Decimal` en Rust; en JSON viajan como string ("12999.99")
+pub const MONEY_SCALE: u32 = 2;
+pub const DEFAULT_CURRENCY: &str = "ARS";
+
+// Máximo representable en DECIMAL(12,2)
+const MAX_AMOUNT: Decimal = Decimal::from_parts(3_567_587_327, 232, 0, false, 2); // 9999999999.99
+
+// Redondeo comercial a centavos (la mitad se aleja de cero: 0.125 -> 0.13, -0.125 -> -0.13).
+// Todo importe calculado (totales, porcentajes, prorrateos) pasa por acá antes de guardarse
+pub fn round_money(amount: Decimal) -> Decimal {
+    amount.round_dp_with_strategy(MONEY_SCALE, RoundingStrategy::MidpointAwayFromZero)
+}
+
+// Aumento (o descuento, si es negativo) porcentual: 12000.00 + 8.5% = 13020.00.
+// El resultado se valida como un precio ingresado: un aumento grande puede superar DECIMAL(12,2)
+// y un descuento cercano al 100% redondear a 0.00
+pub fn apply_percentage(amount: Decimal, percentage: Decimal) -> Result<Decimal, ValidationError> {
+    let price = round_money(amount + amount * percentage / Decimal::ONE_HUNDRED);
+    validate_price(&price)?;
+    Ok(price)
+}
+
+fn error(code: &'static str, message: &'static str) -> ValidationError {
+    ValidationError::new(code).with_message(Cow::Borrowed(message))
+}
+
+// Importe ingresado: positivo, representable en DECIMAL(12,2) y con hasta dos decimales.
+// No se redondea en silencio: más de dos decimales es un error
+fn check_amount(amount: &Decimal) -> Result<(), &'static str> {
+    if *amount <= Decimal::ZERO {
+        return Err("must be greater than 0");
+    }
+    if *amount > MAX_AMOUNT {
+        return Err("must be at most 9999999999.99");
+    }
+    if round_money(*amount) != *amount {
+        return Err("must have at most 2 decimal places");
+    }
+    Ok(())
+}
+
+pub fn validate_price(price: &Decimal) -> Result<(), ValidationError> {
+    check_amount(price).map_err(|reason| ValidationError::new("price")
+        .with_message(Cow::Owned(format!("Price {}", reason))))
+}
+
+pub fn validate_amount(amount: &Decimal) -> Result<(), ValidationError> {
+    check_amount(amount).map_err(|reason| ValidationError::new("amount")
+        .with_message(Cow::Owned(format!("Amount {}", reason))))
+}
+
+// Efectivo contado o fondo de caja: puede ser cero
+pub fn validate_cash(amount: &Decimal) -> Result<(), ValidationError> {
+    if *amount < Decimal::ZERO || *amount > MAX_AMOUNT || round_money(*amount) != *amount {
+        return Err(error("cash", "Cash must be between 0 and 9999999999.99, with at most 2 decimal places"));
+    }
+    Ok(())
+}
+
+// Código ISO 4217 en mayúsculas ("ARS", "USD")
+pub fn validate_currency(currency: &str) -> Result<(), ValidationError> {
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(error("currency", "Currency must be an ISO 4217 code (e.g. ARS)"));
+    }
+    Ok(())
+}
+
+// Entre -99.99% y 1000%, con hasta dos decimales
+pub fn validate_percentage(percentage: &Decimal) -> Result<(), ValidationError> {
+    if *percentage <= -Decimal::ONE_HUNDRED || *percentage > Decimal::ONE_THOUSAND {
+        return Err(error("percentage", "Percentage must be greater than -100 and at most 1000"));
+    }
+    if round_money(*percentage) != *percentage {
+        return Err(error("percentage", "Percentage must have at most 2 decimal places"));
+    }
+    Ok(())
+}
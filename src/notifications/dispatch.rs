@@ -0,0 +1,164 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use serde::Deserialize;
+use sqlx::MySqlPool;
+use super::handlers::{
+    get_reminder_candidates, insert_notification, get_due_notifications, mark_notification_sent,
+    mark_notification_attempt_failed, fail_other_channel_notifications, NewNotification};
+use super::models::notifications::{Channel, NotificationKind, ReminderCandidate};
+use super::notifier::{LogNotifier, Notifier, SmtpConfig, SmtpNotifier, WebhookConfig, WebhookNotifier};
+use super::templates::{render, TemplateData};
+
+// Largo máximo de notification_outbox.last_error
+const MAX_ERROR_LENGTH: usize = 1000;
+// Tope de espera entre reintentos
+const MAX_RETRY_MINUTES: i64 = 24 * 60;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct NotificationConfig {
+    // Apagado por defecto: la tarea send_notifications no hace nada
+    pub enabled: bool,
+    pub channel: Channel,
+    // Avisar cuando el plan vence dentro de estos días...
+    pub expiring_within_days: i64,
+    // ...o cuando quedan estas clases o menos
+    pub remaining_classes_at_most: i32,
+    pub max_attempts: i32,
+    // Espera antes del primer reintento; se duplica en cada intento fallido
+    pub retry_minutes: i64,
+    // Avisos enviados por ejecución
+    pub batch_size: i64,
+    // Archivo JSON lines del canal `log`
+    pub log_file: Option<String>,
+    pub smtp: SmtpConfig,
+    pub webhook: WebhookConfig,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            channel: Channel::Log,
+            expiring_within_days: 3,
+            remaining_classes_at_most: 2,
+            max_attempts: 5,
+            retry_minutes: 15,
+            batch_size: 100,
+            log_file: None,
+            smtp: SmtpConfig::default(),
+            webhook: WebhookConfig::default(),
+        }
+    }
+}
+
+impl NotificationConfig {
+    // Próximo intento después de `attempts` fallos; None si ya no quedan reintentos
+    pub fn next_attempt(&self, attempts: i32, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        if attempts >= self.max_attempts {
+            return None;
+        }
+        let minutes = self.retry_minutes.max(1)
+            .saturating_mul(1_i64 << (attempts - 1).clamp(0, 20))
+            .min(MAX_RETRY_MINUTES);
+        Some(now + Duration::minutes(minutes))
+    }
+
+    // Un aviso por suscripción y vencimiento: si se cumplen las dos condiciones gana la de vencimiento,
+    // y renovar (cambia expires_at) habilita un aviso nuevo
+    pub fn reminder_kind(&self, candidate: &ReminderCandidate, now: NaiveDateTime) -> NotificationKind {
+        if candidate.expires_at <= now + Duration::days(self.expiring_within_days) {
+            NotificationKind::Expiring
+        } else {
+            NotificationKind::LowClasses
+        }
+    }
+}
+
+pub fn dedupe_key(candidate: &ReminderCandidate) -> String {
+    format!("renewal:{}:{}", candidate.subscription_id, candidate.expires_at.format("%Y%m%d%H%M%S"))
+}
+
+pub fn build_notifier(config: &NotificationConfig) -> Result<Box<dyn Notifier>, String> {
+    Ok(match config.channel {
+        Channel::Log => Box::new(LogNotifier { path: config.log_file.clone() }),
+        Channel::Email => Box::new(SmtpNotifier::new(&config.smtp)?),
+        Channel::Webhook => Box::new(WebhookNotifier::new(&config.webhook)?),
+    })
+}
+
+// Detecta suscripciones por vencer o con pocas clases y encola los avisos que falten. Devuelve cuántos encoló
+pub async fn enqueue_reminders(pool: &MySqlPool, config: &NotificationConfig) -> Result<u64, sqlx::Error> {
+    let now = Utc::now().naive_utc();
+    let candidates = get_reminder_candidates(
+        pool, config.expiring_within_days, config.remaining_classes_at_most).await?;
+
+    let mut queued = 0;
+    for candidate in candidates {
+        let Some(recipient) = config.channel.recipient(candidate.email.as_deref(), &candidate.phone) else {
+            continue;
+        };
+        let kind = config.reminder_kind(&candidate, now);
+        let (subject, body) = render(kind, candidate.language, &TemplateData {
+            name: &candidate.name,
+            last_name: &candidate.last_name,
+            discipline: &candidate.discipline,
+            remaining_classes: candidate.remaining_classes,
+            expires_at: candidate.expires_at,
+        });
+        let inserted = insert_notification(pool, NewNotification {
+            client_id: candidate.client_id,
+            subscription_id: Some(candidate.subscription_id),
+            kind,
+            channel: config.channel,
+            recipient: &recipient,
+            language: candidate.language.as_str(),
+            subject: &subject,
+            body: &body,
+            dedupe_key: &dedupe_key(&candidate),
+        }).await?;
+        if inserted {
+            queued += 1;
+        }
+    }
+    Ok(queued)
+}
+
+// Envía los avisos pendientes; los fallidos se reprograman con espera exponencial. Devuelve cuántos se enviaron
+pub async fn deliver_pending(
+    pool: &MySqlPool,
+    config: &NotificationConfig,
+    notifier: &dyn Notifier,
+) -> Result<u64, sqlx::Error> {
+    let stale = fail_other_channel_notifications(pool, notifier.channel()).await?;
+    if stale > 0 {
+        tracing::warn!("{} pending notifications failed: their channel is no longer configured", stale);
+    }
+    let mut sent = 0;
+    for notification in get_due_notifications(pool, notifier.channel(), config.batch_size).await? {
+        match notifier.send(&notification).await {
+            Ok(()) => {
+                mark_notification_sent(pool, notification.id).await?;
+                sent += 1;
+            },
+            Err(e) => {
+                let error: String = e.chars().take(MAX_ERROR_LENGTH).collect();
+                let next_attempt = config.next_attempt(notification.attempts + 1, Utc::now().naive_utc());
+                tracing::warn!("Notification {} failed (attempt {}): {}", notification.id, notification.attempts + 1, error);
+                mark_notification_attempt_failed(pool, notification.id, &error, next_attempt).await?;
+            },
+        }
+    }
+    Ok(sent)
+}
+
+// Tarea programada send_notifications: encola y envía. Devuelve la cantidad de avisos enviados
+pub async fn process_notifications(pool: &MySqlPool, config: &NotificationConfig) -> Result<u64, String> {
+    if !config.enabled {
+        return Ok(0);
+    }
+    let notifier = build_notifier(config)?;
+    let queued = enqueue_reminders(pool, config).await.map_err(|e| e.to_string())?;
+    let sent = deliver_pending(pool, config, notifier.as_ref()).await.map_err(|e| e.to_string())?;
+    tracing::info!("Notifications: {} queued, {} sent", queued, sent);
+    Ok(sent)
+}
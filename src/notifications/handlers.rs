@@ -0,0 +1,199 @@
+use chrono::NaiveDateTime;
+use sqlx::mysql::MySqlArguments;
+use sqlx::{self, Arguments, MySqlPool};
+use crate::add_filter;
+use super::models::notifications::{Channel, Notification, NotificationKind, NotificationStatus, ReminderCandidate};
+use super::models::requests::NotificationQueryParams;
+
+// Aviso renderizado a encolar
+pub struct NewNotification<'a> {
+    pub client_id: i32,
+    pub subscription_id: Option<i32>,
+    pub kind: NotificationKind,
+    pub channel: Channel,
+    pub recipient: &'a str,
+    pub language: &'a str,
+    pub subject: &'a str,
+    pub body: &'a str,
+    pub dedupe_key: &'a str,
+}
+
+// Suscripciones activas de clientes activos que vencen dentro de `within_days` o tienen `max_classes` clases o menos.
+// Las congeladas se avisan cuando se descongelan, con el vencimiento ya corrido
+pub async fn get_reminder_candidates(
+    pool: &MySqlPool,
+    within_days: i64,
+    max_classes: i32,
+) -> Result<Vec<ReminderCandidate>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT s.id AS subscription_id, s.client_id, s.remaining_classes, s.expires_at,
+               c.name, c.last_name, c.email, c.phone, c.language, d.name AS discipline
+        FROM subscriptions s
+        JOIN clients c ON c.id = s.client_id AND c.active = true
+        JOIN disciplines d ON d.id = s.discipline_id
+        WHERE s.active = true AND s.expires_at >= NOW()
+          AND (s.expires_at <= NOW() + INTERVAL ? DAY OR s.remaining_classes <= ?)
+          AND NOT EXISTS (
+            SELECT 1 FROM subscription_freezes f WHERE f.subscription_id = s.id AND f.ended_at IS NULL)
+        ORDER BY s.expires_at, s.id
+        "#)
+        .bind(within_days)
+        .bind(max_classes)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(ReminderCandidate::from_row).collect())
+}
+
+// Encola el aviso; false si ya existía uno con la misma dedupe_key. INSERT IGNORE y no
+// ON DUPLICATE KEY UPDATE: con CLIENT_FOUND_ROWS el duplicado también informaría una fila afectada
+pub async fn insert_notification(
+    pool: &MySqlPool,
+    notification: NewNotification<'_>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT IGNORE INTO notification_outbox
+            (client_id, subscription_id, kind, channel, recipient, language, subject, body, dedupe_key)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(notification.client_id)
+        .bind(notification.subscription_id)
+        .bind(notification.kind.as_str())
+        .bind(notification.channel.as_str())
+        .bind(notification.recipient)
+        .bind(notification.language)
+        .bind(notification.subject)
+        .bind(notification.body)
+        .bind(notification.dedupe_key)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+// Avisos pendientes del canal cuyo próximo intento ya llegó, en orden de alta
+pub async fn get_due_notifications(
+    pool: &MySqlPool,
+    channel: Channel,
+    limit: i64,
+) -> Result<Vec<Notification>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM notification_outbox
+        WHERE status = 'pending' AND channel = ? AND next_attempt_at <= NOW()
+        ORDER BY id
+        LIMIT ?
+        "#)
+        .bind(channel.as_str())
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(Notification::from_row).collect())
+}
+
+// Los pendientes de otro canal quedaron de una configuración anterior: el destinatario es de ese canal y
+// su notifier ya no está configurado, así que se marcan como fallidos en vez de quedar pendientes para siempre
+pub async fn fail_other_channel_notifications(
+    pool: &MySqlPool,
+    channel: Channel,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE notification_outbox
+        SET status = 'failed', last_error = CONCAT('Channel ', channel, ' is no longer configured')
+        WHERE status = 'pending' AND channel <> ?
+        "#)
+        .bind(channel.as_str())
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn mark_notification_sent(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE notification_outbox
+        SET status = 'sent', attempts = attempts + 1, sent_at = NOW(), last_error = NULL
+        WHERE id = ?
+        "#)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// Registra un intento fallido: vuelve a quedar pendiente para `next_attempt_at` o, sin reintentos, como fallido
+pub async fn mark_notification_attempt_failed(
+    pool: &MySqlPool,
+    id: i32,
+    error: &str,
+    next_attempt_at: Option<NaiveDateTime>,
+) -> Result<(), sqlx::Error> {
+    let status = if next_attempt_at.is_some() { NotificationStatus::Pending } else { NotificationStatus::Failed };
+    sqlx::query(
+        r#"
+        UPDATE notification_outbox
+        SET status = ?, attempts = attempts + 1, last_error = ?, next_attempt_at = COALESCE(?, next_attempt_at)
+        WHERE id = ?
+        "#)
+        .bind(status.as_str())
+        .bind(error)
+        .bind(next_attempt_at)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_notification_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<Notification>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM notification_outbox WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| Notification::from_row(&row)))
+}
+
+pub async fn get_notifications(
+    pool: &MySqlPool,
+    params: &NotificationQueryParams,
+    limit: i64,
+) -> Result<Vec<Notification>, sqlx::Error> {
+    let mut query = "SELECT * FROM notification_outbox WHERE 1=1".to_string();
+    let mut args = MySqlArguments::default();
+
+    add_filter!(query, args, &params.client_id, " AND client_id = ?");
+    add_filter!(query, args, params.kind.as_ref().map(NotificationKind::as_str), " AND kind = ?");
+    add_filter!(query, args, params.status.as_ref().map(NotificationStatus::as_str), " AND status = ?");
+    query.push_str(" ORDER BY created_at DESC, id DESC LIMIT ?");
+    let _ = args.add(limit);
+
+    let rows = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(Notification::from_row).collect())
+}
+
+// Vuelve a poner en cola un aviso no enviado, con los intentos en cero. false si ya se había enviado
+pub async fn requeue_notification(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE notification_outbox
+        SET status = 'pending', attempts = 0, next_attempt_at = NOW()
+        WHERE id = ? AND status <> 'sent'
+        "#)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
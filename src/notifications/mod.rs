@@ -0,0 +1,20 @@
+pub mod models;
+pub mod handlers;
+pub mod services;
+pub mod templates;
+pub mod notifier;
+pub mod dispatch;
+
+use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/notifications")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
+            .service(services::list_notifications)
+            .service(services::get_notification)
+            .service(services::retry_notification)
+    );
+}
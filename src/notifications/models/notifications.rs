@@ -0,0 +1,174 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, mysql::MySqlRow};
+use utoipa::ToSchema;
+use crate::clients::models::clients::Language;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    // El plan vence dentro de `expiring_within_days`
+    Expiring,
+    // Quedan `remaining_classes_at_most` clases o menos
+    LowClasses,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::Expiring => "expiring",
+            NotificationKind::LowClasses => "low_classes",
+        }
+    }
+}
+
+impl From<String> for NotificationKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            "low_classes" => NotificationKind::LowClasses,
+            _ => NotificationKind::Expiring,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    Email,
+    // Gateway HTTP genérico (WhatsApp, SMS); el destinatario es el teléfono
+    Webhook,
+    // Log de la aplicación y, opcionalmente, un archivo JSON lines
+    Log,
+}
+
+impl Channel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Channel::Email => "email",
+            Channel::Webhook => "webhook",
+            Channel::Log => "log",
+        }
+    }
+
+    // Destinatario del cliente para el canal; None si no tiene el dato (email sin cargar)
+    pub fn recipient(&self, email: Option<&str>, phone: &str) -> Option<String> {
+        match self {
+            Channel::Email => email.map(str::to_string),
+            Channel::Webhook => Some(phone.to_string()),
+            Channel::Log => Some(email.unwrap_or(phone).to_string()),
+        }
+    }
+}
+
+impl From<String> for Channel {
+    fn from(channel: String) -> Self {
+        match channel.as_str() {
+            "email" => Channel::Email,
+            "webhook" => Channel::Webhook,
+            _ => Channel::Log,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationStatus {
+    Pending,
+    Sent,
+    // Agotó los reintentos
+    Failed,
+}
+
+impl NotificationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationStatus::Pending => "pending",
+            NotificationStatus::Sent => "sent",
+            NotificationStatus::Failed => "failed",
+        }
+    }
+}
+
+impl From<String> for NotificationStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "sent" => NotificationStatus::Sent,
+            "failed" => NotificationStatus::Failed,
+            _ => NotificationStatus::Pending,
+        }
+    }
+}
+
+// Mensaje del outbox, guardado ya renderizado
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct Notification {
+    pub id: i32,
+    pub client_id: i32,
+    pub subscription_id: Option<i32>,
+    pub kind: NotificationKind,
+    pub channel: Channel,
+    pub recipient: String,
+    pub language: Language,
+    pub subject: String,
+    pub body: String,
+    pub status: NotificationStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub sent_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl Notification {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            subscription_id: row.get("subscription_id"),
+            kind: NotificationKind::from(row.get::<String, _>("kind")),
+            channel: Channel::from(row.get::<String, _>("channel")),
+            recipient: row.get("recipient"),
+            language: Language::from(row.get::<String, _>("language")),
+            subject: row.get("subject"),
+            body: row.get("body"),
+            status: NotificationStatus::from(row.get::<String, _>("status")),
+            attempts: row.get("attempts"),
+            last_error: row.get("last_error"),
+            next_attempt_at: row.get("next_attempt_at"),
+            sent_at: row.get("sent_at"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+// Suscripción activa que cumple alguna de las condiciones de aviso, con los datos para la plantilla
+#[derive(Debug)]
+pub struct ReminderCandidate {
+    pub subscription_id: i32,
+    pub client_id: i32,
+    pub name: String,
+    pub last_name: String,
+    pub email: Option<String>,
+    pub phone: String,
+    pub language: Language,
+    pub discipline: String,
+    pub remaining_classes: i32,
+    pub expires_at: NaiveDateTime,
+}
+
+impl ReminderCandidate {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            subscription_id: row.get("subscription_id"),
+            client_id: row.get("client_id"),
+            name: row.get("name"),
+            last_name: row.get("last_name"),
+            email: row.get("email"),
+            phone: row.get("phone"),
+            language: Language::from(row.get::<String, _>("language")),
+            discipline: row.get("discipline"),
+            remaining_classes: row.get("remaining_classes"),
+            expires_at: row.get("expires_at"),
+        }
+    }
+}
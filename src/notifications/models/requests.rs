@@ -0,0 +1,12 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+use super::notifications::{NotificationKind, NotificationStatus};
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct NotificationQueryParams {
+    pub client_id: Option<i32>,
+    pub kind: Option<NotificationKind>,
+    pub status: Option<NotificationStatus>,
+    // Por defecto 50, máximo 500; los más recientes primero
+    pub limit: Option<i64>,
+}
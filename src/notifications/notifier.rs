@@ -0,0 +1,180 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::Duration;
+use actix_web::web;
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::Deserialize;
+use super::models::notifications::{Channel, Notification};
+
+// Canal de entrega de avisos. Devuelve el error como texto: queda en el outbox para el reintento
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn channel(&self) -> Channel;
+    async fn send(&self, notification: &Notification) -> Result<(), String>;
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    // Remitente, por ejemplo "Gym Helper <avisos@gymhelper.com>"
+    pub from: String,
+    // STARTTLS; apagado solo para servidores locales de prueba
+    pub starttls: bool,
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 587,
+            username: None,
+            password: None,
+            from: "Gym Helper <no-reply@gymhelper.com>".to_string(),
+            starttls: true,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub url: String,
+    // Se envía como `Authorization: Bearer ...` si está configurado
+    pub bearer_token: Option<String>,
+    pub timeout_seconds: u64,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            bearer_token: None,
+            timeout_seconds: 10,
+        }
+    }
+}
+
+// Escribe los avisos en el log y, si hay archivo, una línea JSON por aviso (útil en desarrollo y tests)
+pub struct LogNotifier {
+    pub path: Option<String>,
+}
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    fn channel(&self) -> Channel {
+        Channel::Log
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), String> {
+        tracing::info!("Notification {} to {}: {}", notification.id, notification.recipient, notification.subject);
+        if let Some(path) = &self.path {
+            let line = serde_json::json!({
+                "id": notification.id,
+                "recipient": notification.recipient,
+                "language": notification.language,
+                "subject": notification.subject,
+                "body": notification.body,
+            });
+            // El archivo se escribe fuera del runtime para no bloquear el worker
+            let file_path = path.clone();
+            web::block(move || {
+                let mut file = OpenOptions::new().create(true).append(true).open(&file_path)?;
+                writeln!(file, "{}", line)
+            })
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Cannot write {}: {}", path, e))?;
+        }
+        Ok(())
+    }
+}
+
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: String,
+}
+
+impl SmtpNotifier {
+    pub fn new(config: &SmtpConfig) -> Result<Self, String> {
+        let mut builder = if config.starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host).map_err(|e| e.to_string())?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+        };
+        builder = builder.port(config.port);
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(Self { transport: builder.build(), from: config.from.clone() })
+    }
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    fn channel(&self) -> Channel {
+        Channel::Email
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), String> {
+        let message = Message::builder()
+            .from(self.from.parse().map_err(|e| format!("Invalid sender: {}", e))?)
+            .to(notification.recipient.parse().map_err(|e| format!("Invalid recipient: {}", e))?)
+            .subject(&notification.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(notification.body.clone())
+            .map_err(|e| e.to_string())?;
+        self.transport.send(message).await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+// POST JSON genérico para gateways de WhatsApp/SMS: {to, subject, body, language, kind, notification_id}
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    config: WebhookConfig,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: &WebhookConfig) -> Result<Self, String> {
+        if config.url.is_empty() {
+            return Err("Webhook url is not configured".to_string());
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { client, config: config.clone() })
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn channel(&self) -> Channel {
+        Channel::Webhook
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), String> {
+        let mut request = self.client.post(&self.config.url).json(&serde_json::json!({
+            "notification_id": notification.id,
+            "to": notification.recipient,
+            "subject": notification.subject,
+            "body": notification.body,
+            "language": notification.language,
+            "kind": notification.kind,
+        }));
+        if let Some(token) = &self.config.bearer_token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
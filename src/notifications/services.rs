@@ -0,0 +1,227 @@
+use actix_web::{get, post, web, HttpResponse};
+use actix_web_grants::protect;
+use sqlx::MySqlPool;
+use crate::config::Config;
+use crate::errors::{access_denied, AppError};
+use super::handlers::{get_notifications, get_notification_by_id, requeue_notification};
+use super::models::requests::NotificationQueryParams;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+#[get("/")]
+#[protect("Admin", error = "access_denied")]
+pub async fn list_notifications(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<NotificationQueryParams>,
+) -> Result<HttpResponse, AppError> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(AppError::Validation {
+            message: format!("Limit must be between 1 and {}", MAX_LIMIT),
+            details: Some(serde_json::json!({ "field": "limit" })),
+        })
+    }
+    let notifications = get_notifications(&pool, &query, limit).await?;
+    Ok(HttpResponse::Ok().json(notifications))
+}
+
+#[get("/{id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn get_notification(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    match get_notification_by_id(&pool, id.into_inner()).await? {
+        Some(notification) => Ok(HttpResponse::Ok().json(notification)),
+        None => Err(AppError::NotFound("Notification not found".to_string())),
+    }
+}
+
+// Reintenta un aviso fallido (o adelanta uno pendiente); sale en la próxima ejecución de send_notifications
+#[post("/{id}/retry")]
+#[protect("Admin", error = "access_denied")]
+pub async fn retry_notification(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+    config: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let id = id.into_inner();
+    let notification = get_notification_by_id(&pool, id).await?
+        .ok_or_else(|| AppError::NotFound("Notification not found".to_string()))?;
+    // El destinatario es del canal con que se encoló: con otro canal configurado no se puede enviar
+    if notification.channel != config.notifications.channel {
+        return Err(AppError::Conflict("Notification channel is no longer configured".to_string()))
+    }
+    if !requeue_notification(&pool, id).await? {
+        return Err(AppError::Conflict("Notification was already sent".to_string()))
+    }
+    let notification = get_notification_by_id(&pool, id).await?
+        .ok_or_else(|| AppError::NotFound("Notification not found".to_string()))?;
+    tracing::info!("Notification {} queued for retry", id);
+    Ok(HttpResponse::Ok().json(notification))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate, Utc};
+    use crate::clients::models::clients::Language;
+    use crate::notifications::dispatch::{dedupe_key, NotificationConfig};
+    use crate::notifications::models::notifications::{
+        Channel, Notification, NotificationKind, NotificationStatus, ReminderCandidate};
+    use crate::notifications::templates::{render, TemplateData};
+
+    fn create_test_candidate(days_left: i64, remaining_classes: i32) -> ReminderCandidate {
+        ReminderCandidate {
+            subscription_id: 7,
+            client_id: 1,
+            name: "Juan".to_string(),
+            last_name: "Pérez".to_string(),
+            email: None,
+            phone: "+5491112345678".to_string(),
+            language: Language::Es,
+            discipline: "Crossfit".to_string(),
+            remaining_classes,
+            expires_at: Utc::now().naive_utc() + Duration::days(days_left),
+        }
+    }
+
+    fn template_data() -> TemplateData<'static> {
+        TemplateData {
+            name: "Juan",
+            last_name: "Pérez",
+            discipline: "Crossfit",
+            remaining_classes: 2,
+            expires_at: NaiveDate::from_ymd_opt(2026, 10, 21).unwrap().and_hms_opt(0, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_templates_by_language() {
+        let (subject, body) = render(NotificationKind::Expiring, Language::Es, &template_data());
+        assert_eq!(subject, "Tu plan de Crossfit vence el 21/10/2026");
+        assert!(body.starts_with("Hola Juan,"));
+        assert!(body.contains("te quedan 2 clases"));
+
+        let (subject, body) = render(NotificationKind::LowClasses, Language::En, &template_data());
+        assert_eq!(subject, "You have 2 Crossfit classes left");
+        assert!(body.contains("Oct 21, 2026"));
+        assert!(!body.contains('{'));
+    }
+
+    #[test]
+    fn test_client_data_is_not_expanded() {
+        let data = TemplateData { name: "{discipline}", last_name: "{expires_at}", ..template_data() };
+        let (subject, body) = render(NotificationKind::Expiring, Language::Es, &data);
+        assert_eq!(subject, "Tu plan de Crossfit vence el 21/10/2026");
+        assert!(body.starts_with("Hola {discipline}, tu plan de Crossfit"));
+    }
+
+    #[test]
+    fn test_recipient_by_channel() {
+        let phone = "+5491112345678";
+        assert_eq!(Channel::Email.recipient(None, phone), None);
+        assert_eq!(Channel::Email.recipient(Some("juan@mail.com"), phone).as_deref(), Some("juan@mail.com"));
+        assert_eq!(Channel::Webhook.recipient(Some("juan@mail.com"), phone).as_deref(), Some(phone));
+        assert_eq!(Channel::Log.recipient(None, phone).as_deref(), Some(phone));
+    }
+
+    #[test]
+    fn test_reminder_kind_and_dedupe() {
+        let config = NotificationConfig::default();
+        let now = Utc::now().naive_utc();
+        // Vence pronto y con pocas clases: un solo aviso, el de vencimiento
+        assert_eq!(config.reminder_kind(&create_test_candidate(2, 1), now), NotificationKind::Expiring);
+        assert_eq!(config.reminder_kind(&create_test_candidate(20, 1), now), NotificationKind::LowClasses);
+
+        // Al renovar cambia el vencimiento y el aviso se puede volver a mandar
+        let before = create_test_candidate(2, 1);
+        let renewed = create_test_candidate(32, 9);
+        assert_ne!(dedupe_key(&before), dedupe_key(&renewed));
+        assert_eq!(dedupe_key(&before), dedupe_key(&before));
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let config = NotificationConfig { retry_minutes: 10, max_attempts: 4, ..Default::default() };
+        let now = Utc::now().naive_utc();
+        assert_eq!(config.next_attempt(1, now), Some(now + Duration::minutes(10)));
+        assert_eq!(config.next_attempt(2, now), Some(now + Duration::minutes(20)));
+        assert_eq!(config.next_attempt(3, now), Some(now + Duration::minutes(40)));
+        assert_eq!(config.next_attempt(4, now), None);
+
+        let config = NotificationConfig { retry_minutes: 600, max_attempts: 10, ..Default::default() };
+        assert_eq!(config.next_attempt(9, now), Some(now + Duration::hours(24)));
+    }
+
+    #[test]
+    fn test_notification_config() {
+        let config: NotificationConfig = serde_json::from_str(
+            r#"{"enabled": true, "channel": "webhook", "webhook": {"url": "https://wa.example.com/send"}}"#).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.channel, Channel::Webhook);
+        assert_eq!(config.webhook.timeout_seconds, 10);
+        assert_eq!(config.expiring_within_days, 3);
+        assert!(!NotificationConfig::default().enabled);
+    }
+
+    #[actix_web::test]
+    async fn test_log_notifier_writes_json_lines() {
+        use crate::notifications::notifier::{LogNotifier, Notifier};
+
+        let path = std::env::temp_dir().join(format!("gym_helper_notifications_{}.jsonl", uuid::Uuid::new_v4()));
+        let notifier = LogNotifier { path: Some(path.to_string_lossy().to_string()) };
+        let now = Utc::now().naive_utc();
+        let notification = Notification {
+            id: 1,
+            client_id: 1,
+            subscription_id: Some(7),
+            kind: NotificationKind::Expiring,
+            channel: Channel::Log,
+            recipient: "+5491112345678".to_string(),
+            language: Language::Es,
+            subject: "Tu plan de Crossfit vence el 21/10/2026".to_string(),
+            body: "Hola Juan".to_string(),
+            status: NotificationStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            next_attempt_at: now,
+            sent_at: None,
+            created_at: now,
+        };
+        notifier.send(&notification).await.unwrap();
+        notifier.send(&notification).await.unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["recipient"], "+5491112345678");
+        assert_eq!(lines[0]["language"], "es");
+    }
+
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_only_admin_sees_outbox() {
+            let app = init_app(crate::notifications::routes).await;
+
+            let req = test::TestRequest::get()
+                .uri("/notifications/")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+            let req = test::TestRequest::get()
+                .uri("/notifications/?limit=1000")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+}
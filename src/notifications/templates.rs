@@ -0,0 +1,88 @@
+use chrono::NaiveDateTime;
+use crate::clients::models::clients::Language;
+use super::models::notifications::NotificationKind;
+
+// Plantillas de los avisos: (asunto, cuerpo). Los {marcadores} se reemplazan con los datos del cliente
+const EXPIRING_ES: (&str, &str) = (
+    "Tu plan de {discipline} vence el {expires_at}",
+    "Hola {name}, tu plan de {discipline} vence el {expires_at} y te quedan {remaining_classes} clases. \
+     Renovalo en recepción para seguir entrenando sin cortes. ¡Te esperamos!",
+);
+const LOW_CLASSES_ES: (&str, &str) = (
+    "Te quedan {remaining_classes} clases de {discipline}",
+    "Hola {name}, te quedan {remaining_classes} clases de {discipline} (tu plan vence el {expires_at}). \
+     Acercate a recepción para renovarlo.",
+);
+const EXPIRING_EN: (&str, &str) = (
+    "Your {discipline} plan expires on {expires_at}",
+    "Hi {name}, your {discipline} plan expires on {expires_at} and you have {remaining_classes} classes left. \
+     Renew it at the front desk to keep training without interruptions. See you soon!",
+);
+const LOW_CLASSES_EN: (&str, &str) = (
+    "You have {remaining_classes} {discipline} classes left",
+    "Hi {name}, you have {remaining_classes} {discipline} classes left (your plan expires on {expires_at}). \
+     Stop by the front desk to renew it.",
+);
+
+pub struct TemplateData<'a> {
+    pub name: &'a str,
+    pub last_name: &'a str,
+    pub discipline: &'a str,
+    pub remaining_classes: i32,
+    pub expires_at: NaiveDateTime,
+}
+
+fn template(kind: NotificationKind, language: Language) -> (&'static str, &'static str) {
+    match (kind, language) {
+        (NotificationKind::Expiring, Language::Es) => EXPIRING_ES,
+        (NotificationKind::LowClasses, Language::Es) => LOW_CLASSES_ES,
+        (NotificationKind::Expiring, Language::En) => EXPIRING_EN,
+        (NotificationKind::LowClasses, Language::En) => LOW_CLASSES_EN,
+    }
+}
+
+fn value(key: &str, data: &TemplateData, language: Language) -> Option<String> {
+    let date_format = match language {
+        Language::Es => "%d/%m/%Y",
+        Language::En => "%b %-d, %Y",
+    };
+    match key {
+        "name" => Some(data.name.to_string()),
+        "last_name" => Some(data.last_name.to_string()),
+        "discipline" => Some(data.discipline.to_string()),
+        "remaining_classes" => Some(data.remaining_classes.to_string()),
+        "expires_at" => Some(data.expires_at.format(date_format).to_string()),
+        _ => None,
+    }
+}
+
+// Reemplaza los marcadores en una sola pasada sobre la plantilla: los datos del
+// cliente se copian tal cual aunque contengan llaves
+fn fill(text: &str, data: &TemplateData, language: Language) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let token = &rest[start..];
+        let filled = token.find('}')
+            .and_then(|end| value(&token[1..end], data, language).map(|value| (end, value)));
+        match filled {
+            Some((end, value)) => {
+                result.push_str(&value);
+                rest = &token[end + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = &token[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+// Devuelve (asunto, cuerpo) en el idioma del cliente
+pub fn render(kind: NotificationKind, language: Language, data: &TemplateData) -> (String, String) {
+    let (subject, body) = template(kind, language);
+    (fill(subject, data, language), fill(body, data, language))
+}
@@ -1,16 +1,70 @@
-use utoipa::OpenApi;
+use utoipa::{Modify, OpenApi};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
 
 // Importar todos los modelos necesarios
+use crate::errors::ErrorResponse;
+use crate::pagination::{ClientPage, SubscriptionPage, PaymentPage, PageLinks, SortOrder};
 use crate::subscription::models::{
-    Subscription, NewSubscriptionRequest, ClassAttendanceRequest, SubscriptionQueryParams
+    Subscription, SubscriptionPeriod, SubscriptionHistory, NewSubscriptionRequest, ClassAttendanceRequest,
+    SubscriptionQueryParams, SubscriptionFreeze, FreezeRequest, UnfreezeResponse
 };
 use crate::clients::models::{
-    clients::Client,
-    requests::{CreateClientRequest, ClientQueryParams}
+    clients::{Client, Language},
+    requests::{CreateClientRequest, ClientQueryParams, ClientSearchParams},
+    responses::ClientSearchResult,
+};
+use crate::auth::models::{
+    users::UserRole,
+    login_attempts::{LoginAttempt, LoginAttemptResult},
+    requests::RegisterRequest
+};
+use crate::users::models::{
+    requests::{UpdateRoleRequest, ResetPasswordRequest},
+    responses::UserResponse
+};
+use crate::api_keys::models::{
+    requests::NewApiKeyRequest,
+    responses::{ApiKeyResponse, CreatedApiKeyResponse}
 };
 use crate::membership::models::{
-    membership::{Discipline, Membership},
-    requests::{NewDisciplineRequest, NewMembershipRequest}
+    membership::{Discipline, Membership, MembershipPrice},
+    requests::{
+        NewDisciplineRequest, NewMembershipRequest, UpdateDisciplineRequest, UpdateMembershipRequest,
+        DisciplineQueryParams, MembershipQueryParams, SchedulePriceRequest, ScheduleDisciplinePriceRequest},
+};
+
+use crate::payments::models::{
+    payments::{Charge, ChargeStatus, Payment, PaymentMethod},
+    requests::{
+        NewChargeRequest, NewPaymentRequest, RefundRequest, PurchasePayment, ChargeQueryParams, PaymentQueryParams,
+        DebtorQueryParams},
+    responses::{
+        ClientOutstanding, CurrencyBalance, Debt, Debtor, AccountStatement, StatementEntry, StatementEntryKind},
+};
+use crate::cash_register::models::{
+    cash_register::{CashSession, CashMovement, MovementKind},
+    requests::{OpenSessionRequest, CloseSessionRequest, NewMovementRequest, ManualMovementKind, DailyReportParams},
+    responses::{SessionSummary, MovementTotal, MethodTotal, DailyClosingReport},
+};
+use crate::jobs::models::{
+    jobs::{Job, JobRun, JobSource, JobStatus},
+    requests::JobRunQueryParams,
+};
+use crate::notifications::models::{
+    notifications::{Notification, NotificationKind, NotificationStatus, Channel},
+    requests::NotificationQueryParams,
+};
+use crate::closures::models::{
+    closures::Closure,
+    requests::{NewClosureRequest, ClosureQueryParams},
+    responses::ClosureSummary,
+};
+use crate::schedule::models::{
+    schedule::{ClassTemplate, ClassSession, SessionStatus, Weekday},
+    requests::{
+        NewTemplateRequest, TemplateQueryParams, GenerateSessionsRequest, CancelSessionRequest, MoveSessionRequest,
+        TimetableParams},
+    responses::{GeneratedSessions, SessionDetail, TimetableDay, Timetable},
 };
 
 #[derive(OpenApi)]
@@ -32,30 +86,176 @@ use crate::membership::models::{
         schemas(
             // Subscription schemas
             Subscription,
+            SubscriptionPeriod,
+            SubscriptionHistory,
+            SubscriptionFreeze,
+            FreezeRequest,
+            UnfreezeResponse,
             NewSubscriptionRequest,
             ClassAttendanceRequest,
             SubscriptionQueryParams,
             
             // Client schemas
             Client,
+            Language,
             CreateClientRequest,
             ClientQueryParams,
+            ClientSearchParams,
+            ClientSearchResult,
             
             // Membership schemas
             Discipline,
             Membership,
             NewDisciplineRequest,
             NewMembershipRequest,
+            UpdateDisciplineRequest,
+            UpdateMembershipRequest,
+            DisciplineQueryParams,
+            MembershipQueryParams,
+            MembershipPrice,
+            SchedulePriceRequest,
+            ScheduleDisciplinePriceRequest,
+
+            // Payment schemas
+            Charge,
+            ChargeStatus,
+            Payment,
+            PaymentMethod,
+            NewChargeRequest,
+            NewPaymentRequest,
+            RefundRequest,
+            PurchasePayment,
+            ChargeQueryParams,
+            PaymentQueryParams,
+            ClientOutstanding,
+            CurrencyBalance,
+            Debt,
+            Debtor,
+            DebtorQueryParams,
+            AccountStatement,
+            StatementEntry,
+            StatementEntryKind,
+
+            // Cash register schemas
+            CashSession,
+            CashMovement,
+            MovementKind,
+            OpenSessionRequest,
+            CloseSessionRequest,
+            NewMovementRequest,
+            ManualMovementKind,
+            DailyReportParams,
+            SessionSummary,
+            MovementTotal,
+            MethodTotal,
+            DailyClosingReport,
+
+            // Job schemas
+            Job,
+            JobRun,
+            JobSource,
+            JobStatus,
+            JobRunQueryParams,
+
+            // Notification schemas
+            Notification,
+            NotificationKind,
+            NotificationStatus,
+            Channel,
+            NotificationQueryParams,
+
+            // Closure schemas
+            Closure,
+            NewClosureRequest,
+            ClosureQueryParams,
+            ClosureSummary,
+
+            // Schedule schemas
+            Weekday,
+            SessionStatus,
+            ClassTemplate,
+            ClassSession,
+            NewTemplateRequest,
+            TemplateQueryParams,
+            GenerateSessionsRequest,
+            CancelSessionRequest,
+            MoveSessionRequest,
+            TimetableParams,
+            GeneratedSessions,
+            SessionDetail,
+            TimetableDay,
+            Timetable,
+
+            // User schemas
+            UserRole,
+            UserResponse,
+            RegisterRequest,
+            UpdateRoleRequest,
+            ResetPasswordRequest,
+            LoginAttempt,
+            LoginAttemptResult,
+
+            // API key schemas
+            NewApiKeyRequest,
+            ApiKeyResponse,
+            CreatedApiKeyResponse,
+
+            // Error schema
+            ErrorResponse,
+
+            // Pagination schemas
+            ClientPage,
+            SubscriptionPage,
+            PaymentPage,
+            PageLinks,
+            SortOrder,
         )
     ),
     tags(
         (name = "Subscriptions", description = "Operaciones relacionadas con suscripciones de clientes"),
         (name = "Clients", description = "Gestión de clientes del gimnasio"),
         (name = "Memberships", description = "Administración de membresías y disciplinas"),
+        (name = "Users", description = "Administración de usuarios del sistema"),
+        (name = "API Keys", description = "Keys con scopes para dispositivos e integraciones"),
+        (name = "Payments", description = "Cargos, pagos y devoluciones de clientes"),
+        (name = "Notifications", description = "Avisos de vencimiento y renovación a clientes"),
+        (name = "Jobs", description = "Tareas programadas e historial de ejecuciones"),
+        (name = "Closures", description = "Calendario de cierres y feriados del gimnasio"),
+        (name = "Schedule", description = "Grilla semanal de clases por disciplina"),
+        (name = "Cash Register", description = "Apertura y cierre de caja, movimientos y reporte diario"),
+    ),
+    modifiers(&SecurityAddon),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
     ),
     servers(
         (url = "http://localhost:8080", description = "Servidor de desarrollo"),
         (url = "https://api.gymhelper.com", description = "Servidor de producción")
     )
 )]
-pub struct ApiDoc; 
\ No newline at end of file
+pub struct ApiDoc;
+
+// Esquema Bearer JWT o API key: sin credenciales las rutas protegidas responden 401,
+// con un rol o scope insuficiente 403
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_auth",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build()
+                ),
+            );
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+            );
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{mysql::{MySqlArguments, MySqlRow}, Arguments, MySqlPool, Row};
+use utoipa::{IntoParams, ToSchema};
+use crate::clients::models::clients::Client;
+use crate::errors::AppError;
+use crate::payments::models::payments::Payment;
+use crate::subscription::models::Subscription;
+
+pub const DEFAULT_LIMIT: u32 = 50;
+pub const MAX_LIMIT: u32 = 200;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    fn as_sql(self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+
+    fn comparator(self) -> &'static str {
+        match self {
+            SortOrder::Asc => ">",
+            SortOrder::Desc => "<",
+        }
+    }
+}
+
+// Parámetros comunes de los listados; se extraen aparte de los filtros de cada recurso
+#[derive(Deserialize, Debug, Default, IntoParams)]
+pub struct PageParams {
+    // Entre 1 y 200 (por defecto 50)
+    pub limit: Option<u32>,
+    // Paginación por offset; se ignora si viene `cursor`
+    pub offset: Option<u32>,
+    // `next_cursor` de la página anterior (keyset: no se degrada con offsets grandes)
+    pub cursor: Option<String>,
+    pub sort_by: Option<String>,
+    pub order: Option<SortOrder>,
+}
+
+// Posición después de la cual empieza la página: valor de la columna de orden e id del último registro
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Cursor {
+    sort_by: String,
+    order: SortOrder,
+    value: String,
+    id: i32,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).expect("Cursor is always serializable"))
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()
+    }
+}
+
+fn invalid(field: &str, message: &str) -> AppError {
+    AppError::Validation {
+        message: message.to_string(),
+        details: Some(serde_json::json!({ "field": field })),
+    }
+}
+
+// Página ya validada contra las columnas ordenables del recurso
+#[derive(Debug)]
+pub struct PageRequest {
+    pub limit: u32,
+    pub offset: u32,
+    pub sort_by: &'static str,
+    pub order: SortOrder,
+    after: Option<Cursor>,
+}
+
+impl PageParams {
+    pub fn resolve<T: Sortable>(&self) -> Result<PageRequest, AppError> {
+        let limit = self.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(invalid("limit", &format!("Limit must be between 1 and {}", MAX_LIMIT)));
+        }
+        let sort_by = match self.sort_by.as_deref() {
+            None => "id",
+            Some(sort_by) => T::SORT_COLUMNS.iter().copied()
+                .find(|column| *column == sort_by)
+                .ok_or_else(|| invalid("sort_by", &format!(
+                    "sort_by must be one of: {}", T::SORT_COLUMNS.join(", "))))?,
+        };
+        let order = self.order.unwrap_or_default();
+
+        let after = match self.cursor.as_deref() {
+            None => None,
+            Some(cursor) => {
+                let cursor = Cursor::decode(cursor).ok_or_else(|| invalid("cursor", "Invalid cursor"))?;
+                // El cursor solo tiene sentido con el mismo orden con el que se generó
+                if cursor.sort_by != sort_by || cursor.order != order {
+                    return Err(invalid("cursor", "Cursor does not match sort_by/order"));
+                }
+                Some(cursor)
+            },
+        };
+
+        Ok(PageRequest {
+            limit,
+            offset: if after.is_some() { 0 } else { self.offset.unwrap_or(0) },
+            sort_by,
+            order,
+            after,
+        })
+    }
+}
+
+// Modelos listables: columnas por las que se puede ordenar y valor de cada una para armar el cursor
+pub trait Sortable {
+    const SORT_COLUMNS: &'static [&'static str];
+
+    fn id(&self) -> i32;
+    fn sort_value(&self, column: &str) -> String;
+}
+
+fn datetime_value(value: &NaiveDateTime) -> String {
+    value.format("%Y-%m-%d %H:%M:%S%.f").to_string()
+}
+
+impl Sortable for Client {
+    const SORT_COLUMNS: &'static [&'static str] = &["id", "name", "last_name", "age", "created_at", "updated_at"];
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn sort_value(&self, column: &str) -> String {
+        match column {
+            "name" => self.name.clone(),
+            "last_name" => self.last_name.clone(),
+            "age" => self.age.to_string(),
+            "created_at" => datetime_value(&self.created_at),
+            "updated_at" => datetime_value(&self.updated_at),
+            _ => self.id.to_string(),
+        }
+    }
+}
+
+impl Sortable for Subscription {
+    const SORT_COLUMNS: &'static [&'static str] = &[
+        "id", "client_id", "discipline_id", "remaining_classes", "expires_at", "created_at", "updated_at"];
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn sort_value(&self, column: &str) -> String {
+        match column {
+            "client_id" => self.client_id.to_string(),
+            "discipline_id" => self.discipline_id.to_string(),
+            "remaining_classes" => self.remaining_classes.to_string(),
+            "expires_at" => datetime_value(&self.expires_at),
+            "created_at" => datetime_value(&self.created_at),
+            "updated_at" => datetime_value(&self.updated_at),
+            _ => self.id.to_string(),
+        }
+    }
+}
+
+impl Sortable for Payment {
+    const SORT_COLUMNS: &'static [&'static str] = &["id", "client_id", "amount", "paid_at"];
+
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn sort_value(&self, column: &str) -> String {
+        match column {
+            "client_id" => self.client_id.to_string(),
+            "amount" => self.amount.to_string(),
+            "paid_at" => datetime_value(&self.paid_at),
+            _ => self.id.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct PageLinks {
+    #[serde(rename = "self")]
+    pub self_link: String,
+    pub next: Option<String>,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+#[aliases(ClientPage = Page<Client>, SubscriptionPage = Page<Subscription>, PaymentPage = Page<Payment>)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    // Total de registros que cumplen los filtros, sin paginar
+    pub total: i64,
+    pub limit: u32,
+    pub offset: u32,
+    pub next_cursor: Option<String>,
+    pub links: PageLinks,
+}
+
+// Resultado de la consulta antes de armar los links (que dependen de la URL del request)
+pub struct PageResult<T> {
+    pub data: Vec<T>,
+    pub total: i64,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> PageResult<T> {
+    pub fn into_page(self, page: &PageRequest, path: &str, query_string: &str) -> Page<T> {
+        let next = self.next_cursor.as_deref()
+            .map(|cursor| format!("{}?{}", path, with_cursor(query_string, cursor)));
+        let self_link = if query_string.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}?{}", path, query_string)
+        };
+        Page {
+            data: self.data,
+            total: self.total,
+            limit: page.limit,
+            offset: page.offset,
+            next_cursor: self.next_cursor,
+            links: PageLinks { self_link, next },
+        }
+    }
+}
+
+// Conserva filtros y orden del request, reemplazando offset/cursor por el cursor siguiente
+fn with_cursor(query_string: &str, cursor: &str) -> String {
+    query_string.split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| !pair.starts_with("cursor=") && !pair.starts_with("offset="))
+        .chain(std::iter::once(format!("cursor={}", cursor).as_str()))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// Ejecuta un listado paginado. `filters` arma el WHERE (con `add_filter!`) y se llama dos veces:
+// una para el total y otra para la página, porque los argumentos no se pueden reutilizar
+pub async fn fetch_page<T: Sortable>(
+    pool: &MySqlPool,
+    table: &str,
+    filters: impl Fn() -> (String, MySqlArguments),
+    page: &PageRequest,
+    from_row: fn(&MySqlRow) -> T,
+) -> Result<PageResult<T>, sqlx::Error> {
+    let (conditions, args) = filters();
+    let total: i64 = sqlx::query_with(&format!("SELECT COUNT(*) AS total FROM {} WHERE 1=1{}", table, conditions), args)
+        .fetch_one(pool)
+        .await?
+        .get("total");
+
+    let (conditions, mut args) = filters();
+    let mut query = format!("SELECT * FROM {} WHERE 1=1{}", table, conditions);
+    if let Some(after) = &page.after {
+        let comparator = page.order.comparator();
+        query.push_str(&format!(
+            " AND ({column} {cmp} ? OR ({column} = ? AND id {cmp} ?))",
+            column = page.sort_by, cmp = comparator));
+        let _ = args.add(after.value.clone());
+        let _ = args.add(after.value.clone());
+        let _ = args.add(after.id);
+    }
+    // Se pide un registro de más para saber si hay página siguiente
+    query.push_str(&format!(
+        " ORDER BY {column} {order}, id {order} LIMIT ? OFFSET ?",
+        column = page.sort_by, order = page.order.as_sql()));
+    let _ = args.add(page.limit + 1);
+    let _ = args.add(page.offset);
+
+    let rows = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?;
+    let mut data: Vec<T> = rows.iter().map(from_row).collect();
+
+    let next_cursor = if data.len() > page.limit as usize {
+        data.truncate(page.limit as usize);
+        data.last().map(|last| Cursor {
+            sort_by: page.sort_by.to_string(),
+            order: page.order,
+            value: last.sort_value(page.sort_by),
+            id: last.id(),
+        }.encode())
+    } else {
+        None
+    };
+
+    Ok(PageResult { data, total, next_cursor })
+}
+
+#[cfg(test)]
+pub(crate) fn test_cursor(sort_by: &str, order: SortOrder, value: &str, id: i32) -> String {
+    Cursor { sort_by: sort_by.to_string(), order, value: value.to_string(), id }.encode()
+}
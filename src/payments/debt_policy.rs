@@ -0,0 +1,71 @@
+use chrono::{Duration, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use crate::errors::AppError;
+use super::models::responses::Debt;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DebtAction {
+    // No se controla la deuda al registrar asistencia
+    Allow,
+    // Se registra la asistencia con una advertencia
+    Warn,
+    // Se rechaza la asistencia
+    Block,
+}
+
+// Qué hacer al registrar asistencia de un cliente con saldo pendiente
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DebtPolicy {
+    pub action: DebtAction,
+    // Días desde el cargo impago más viejo antes de advertir o bloquear
+    pub grace_days: i64,
+    // Saldos por debajo de este importe se ignoran (redondeos, centavos)
+    pub min_amount: Decimal,
+}
+
+impl Default for DebtPolicy {
+    fn default() -> Self {
+        Self {
+            action: DebtAction::Warn,
+            grace_days: 0,
+            min_amount: Decimal::ZERO,
+        }
+    }
+}
+
+impl DebtPolicy {
+    // Deudas que superan la tolerancia y ya pasaron el período de gracia
+    pub fn overdue<'a>(&self, debts: &'a [Debt], now: NaiveDateTime) -> Vec<&'a Debt> {
+        debts.iter()
+            .filter(|debt| debt.outstanding > self.min_amount)
+            .filter(|debt| now - debt.oldest_unpaid_at > Duration::days(self.grace_days))
+            .collect()
+    }
+
+    // Ok(None) si puede pasar, Ok(Some(advertencia)) si pasa con aviso y Err si la deuda bloquea el acceso
+    pub fn evaluate(&self, debts: &[Debt], now: NaiveDateTime) -> Result<Option<String>, AppError> {
+        if self.action == DebtAction::Allow {
+            return Ok(None);
+        }
+        let overdue = self.overdue(debts, now);
+        if overdue.is_empty() {
+            return Ok(None);
+        }
+
+        let summary = overdue.iter()
+            .map(|debt| format!("{} {} ({} days)", debt.outstanding, debt.currency, debt.days_overdue))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let message = format!("Client has an outstanding balance: {}", summary);
+        match self.action {
+            DebtAction::Block => Err(AppError::Validation {
+                message,
+                details: Some(serde_json::json!({ "reason": "outstanding_balance", "debts": overdue })),
+            }),
+            _ => Ok(Some(message)),
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use chrono::{NaiveDateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::mysql::{MySqlArguments, MySqlRow};
+use sqlx::{self, Arguments, MySqlConnection, MySqlPool, Row};
+use crate::add_filter;
+use crate::pagination::{fetch_page, PageRequest, PageResult};
+use super::models::payments::{Charge, ChargeStatus, Payment, PaymentMethod};
+use super::models::requests::{ChargeQueryParams, DebtorQueryParams, PaymentQueryParams};
+use super::models::responses::{CurrencyBalance, Debt, Debtor, StatementEntry, StatementEntryKind};
+
+// Cargos con lo pagado hasta ahora (neto de devoluciones); se usa como tabla derivada para filtrar por estado
+const CHARGES_WITH_PAID: &str = r#"(
+    SELECT c.*, COALESCE((SELECT SUM(amount) FROM payments WHERE charge_id = c.id), 0) AS paid
+    FROM charges c
+) AS charges"#;
+
+// Datos de un movimiento a registrar; el cliente y la moneda se toman del cargo
+pub struct PaymentEntry<'a> {
+    pub charge: &'a Charge,
+    pub amount: Decimal,
+    pub method: PaymentMethod,
+    pub reference: Option<&'a str>,
+    pub notes: Option<&'a str>,
+    pub refund_of: Option<i32>,
+    pub recorded_by: Option<i32>,
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// CHARGE HANDLERS /////////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+// Recibe la conexión para poder crear el cargo en la misma transacción que la compra del plan
+pub async fn insert_charge(
+    conn: &mut MySqlConnection,
+    client_id: i32,
+    period_id: Option<i32>,
+    description: &str,
+    amount: Decimal,
+    currency: &str,
+    created_by: Option<i32>,
+) -> Result<i32, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO charges (client_id, period_id, description, amount, currency, created_by)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(client_id)
+        .bind(period_id)
+        .bind(description)
+        .bind(amount)
+        .bind(currency)
+        .bind(created_by)
+        .execute(conn)
+        .await?;
+
+    Ok(result.last_insert_id() as i32)
+}
+
+pub async fn get_charge_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<Charge>, sqlx::Error> {
+    let row = sqlx::query(&format!("SELECT * FROM {} WHERE id = ?", CHARGES_WITH_PAID))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| Charge::from_row(&row)))
+}
+
+// Bloquea el cargo hasta el fin de la transacción: dos pagos simultáneos no pueden superar lo adeudado
+pub async fn lock_charge(
+    conn: &mut MySqlConnection,
+    id: i32,
+) -> Result<Option<Charge>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT c.*, COALESCE((SELECT SUM(amount) FROM payments WHERE charge_id = c.id), 0) AS paid
+        FROM charges c
+        WHERE c.id = ?
+        FOR UPDATE
+        "#)
+        .bind(id)
+        .fetch_optional(conn)
+        .await?;
+
+    Ok(row.map(|row| Charge::from_row(&row)))
+}
+
+pub async fn get_charges(
+    pool: &MySqlPool,
+    params: &ChargeQueryParams,
+) -> Result<Vec<Charge>, sqlx::Error> {
+    let mut query = format!("SELECT * FROM {} WHERE 1=1", CHARGES_WITH_PAID);
+    let mut args = MySqlArguments::default();
+
+    add_filter!(query, args, &params.client_id, " AND client_id = ?");
+    match params.status {
+        Some(ChargeStatus::Unpaid) => query.push_str(" AND paid <= 0"),
+        Some(ChargeStatus::Partial) => query.push_str(" AND paid > 0 AND paid < amount"),
+        Some(ChargeStatus::Paid) => query.push_str(" AND paid >= amount"),
+        None => {},
+    }
+    query.push_str(" ORDER BY created_at DESC, id DESC");
+
+    let rows = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(Charge::from_row).collect())
+}
+
+pub async fn get_open_charges(
+    pool: &MySqlPool,
+    client_id: i32,
+) -> Result<Vec<Charge>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        "SELECT * FROM {} WHERE client_id = ? AND paid < amount ORDER BY created_at, id", CHARGES_WITH_PAID))
+        .bind(client_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(Charge::from_row).collect())
+}
+
+// Totales del cliente por moneda: cargado, pagado (neto de devoluciones) y pendiente
+pub async fn get_client_balances(
+    pool: &MySqlPool,
+    client_id: i32,
+) -> Result<Vec<CurrencyBalance>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT currency, SUM(amount) AS charged, SUM(paid) AS paid
+        FROM {}
+        WHERE client_id = ?
+        GROUP BY currency
+        ORDER BY currency
+        "#, CHARGES_WITH_PAID))
+        .bind(client_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(|row: &MySqlRow| {
+        let charged: Decimal = row.get("charged");
+        let paid: Decimal = row.get("paid");
+        CurrencyBalance {
+            currency: row.get("currency"),
+            charged,
+            paid,
+            outstanding: charged - paid,
+        }
+    }).collect())
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// DEBT HANDLERS ///////////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+fn debt_from_row(row: &MySqlRow, now: NaiveDateTime) -> Debt {
+    let oldest_unpaid_at: NaiveDateTime = row.get("oldest_unpaid_at");
+    Debt {
+        currency: row.get("currency"),
+        outstanding: row.get("outstanding"),
+        open_charges: row.get("open_charges"),
+        oldest_unpaid_at,
+        days_overdue: (now - oldest_unpaid_at).num_days(),
+    }
+}
+
+// Deuda del cliente por moneda (solo monedas con cargos impagos)
+pub async fn get_client_debts(
+    pool: &MySqlPool,
+    client_id: i32,
+) -> Result<Vec<Debt>, sqlx::Error> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT currency, SUM(amount - paid) AS outstanding, COUNT(*) AS open_charges,
+               MIN(created_at) AS oldest_unpaid_at
+        FROM {}
+        WHERE client_id = ? AND paid < amount
+        GROUP BY currency
+        ORDER BY currency
+        "#, CHARGES_WITH_PAID))
+        .bind(client_id)
+        .fetch_all(pool)
+        .await?;
+
+    let now = Utc::now().naive_utc();
+    Ok(rows.iter().map(|row| debt_from_row(row, now)).collect())
+}
+
+// Clientes con saldo pendiente, una fila por moneda, de la deuda más vieja a la más nueva
+pub async fn get_debtors(
+    pool: &MySqlPool,
+    params: &DebtorQueryParams,
+) -> Result<Vec<Debtor>, sqlx::Error> {
+    let now = Utc::now().naive_utc();
+    let mut query = format!(
+        r#"
+        SELECT charges.client_id, cl.name, cl.last_name, cl.phone, charges.currency,
+               SUM(charges.amount - charges.paid) AS outstanding, COUNT(*) AS open_charges,
+               MIN(charges.created_at) AS oldest_unpaid_at
+        FROM {}
+        JOIN clients cl ON cl.id = charges.client_id
+        WHERE charges.paid < charges.amount
+        "#, CHARGES_WITH_PAID);
+    let mut args = MySqlArguments::default();
+
+    add_filter!(query, args, &params.currency, " AND charges.currency = ?");
+    query.push_str(" GROUP BY charges.client_id, cl.name, cl.last_name, cl.phone, charges.currency HAVING 1=1");
+    add_filter!(query, args, &params.min_amount, " AND outstanding >= ?");
+    add_filter!(query, args, params.min_days.map(|days| now - chrono::Duration::days(days)),
+        " AND oldest_unpaid_at <= ?");
+    query.push_str(" ORDER BY oldest_unpaid_at, outstanding DESC");
+
+    let rows = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(|row| Debtor {
+        client_id: row.get("client_id"),
+        name: row.get("name"),
+        last_name: row.get("last_name"),
+        phone: row.get("phone"),
+        debt: debt_from_row(row, now),
+    }).collect())
+}
+
+// Estado de cuenta: cargos y pagos en orden cronológico con el saldo acumulado de cada moneda
+pub async fn get_statement_entries(
+    pool: &MySqlPool,
+    client_id: i32,
+) -> Result<Vec<StatementEntry>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT 'charge' AS kind, created_at AS date, id AS charge_id, NULL AS payment_id,
+               description, NULL AS method, amount, currency
+        FROM charges
+        WHERE client_id = ?
+        UNION ALL
+        SELECT IF(p.refund_of IS NULL, 'payment', 'refund'), p.paid_at, p.charge_id, p.id,
+               COALESCE(p.notes, c.description), p.method, -p.amount, p.currency
+        FROM payments p
+        JOIN charges c ON c.id = p.charge_id
+        WHERE p.client_id = ?
+        ORDER BY date, payment_id IS NOT NULL, charge_id, payment_id
+        "#)
+        .bind(client_id)
+        .bind(client_id)
+        .fetch_all(pool)
+        .await?;
+
+    let mut balances: HashMap<String, Decimal> = HashMap::new();
+    Ok(rows.iter().map(|row| {
+        let currency: String = row.get("currency");
+        let amount: Decimal = row.get("amount");
+        let balance = balances.entry(currency.clone()).or_default();
+        *balance += amount;
+        StatementEntry {
+            kind: match row.get::<String, _>("kind").as_str() {
+                "payment" => StatementEntryKind::Payment,
+                "refund" => StatementEntryKind::Refund,
+                _ => StatementEntryKind::Charge,
+            },
+            date: row.get("date"),
+            charge_id: row.get("charge_id"),
+            payment_id: row.get("payment_id"),
+            description: row.get("description"),
+            method: row.get::<Option<String>, _>("method").map(PaymentMethod::from),
+            amount,
+            currency,
+            balance: *balance,
+        }
+    }).collect())
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// PAYMENT HANDLERS ////////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+pub async fn insert_payment(
+    conn: &mut MySqlConnection,
+    entry: PaymentEntry<'_>,
+) -> Result<Payment, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO payments (charge_id, client_id, amount, currency, method, reference, notes, refund_of, recorded_by)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(entry.charge.id)
+        .bind(entry.charge.client_id)
+        .bind(entry.amount)
+        .bind(&entry.charge.currency)
+        .bind(entry.method.as_str())
+        .bind(entry.reference)
+        .bind(entry.notes)
+        .bind(entry.refund_of)
+        .bind(entry.recorded_by)
+        .execute(&mut *conn)
+        .await?;
+
+    let row = sqlx::query("SELECT * FROM payments WHERE id = ?")
+        .bind(result.last_insert_id())
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(Payment::from_row(&row))
+}
+
+pub async fn get_payment_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<Payment>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM payments WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| Payment::from_row(&row)))
+}
+
+// Total ya devuelto de un pago (positivo)
+pub async fn get_refunded_amount(
+    conn: &mut MySqlConnection,
+    payment_id: i32,
+) -> Result<Decimal, sqlx::Error> {
+    let row = sqlx::query("SELECT COALESCE(-SUM(amount), 0) AS refunded FROM payments WHERE refund_of = ?")
+        .bind(payment_id)
+        .fetch_one(conn)
+        .await?;
+    Ok(row.get("refunded"))
+}
+
+fn payment_filters(params: &PaymentQueryParams) -> (String, MySqlArguments) {
+    let mut query = String::new();
+    let mut args = MySqlArguments::default();
+
+    add_filter!(query, args, &params.client_id, " AND client_id = ?");
+    add_filter!(query, args, &params.charge_id, " AND charge_id = ?");
+    add_filter!(query, args, params.method.as_ref().map(PaymentMethod::as_str), " AND method = ?");
+    add_filter!(query, args, &params.paid_at_from, " AND paid_at >= ?");
+    add_filter!(query, args, &params.paid_at_to, " AND paid_at <= ?");
+
+    (query, args)
+}
+
+pub async fn get_payments_handler(
+    pool: &MySqlPool,
+    params: &PaymentQueryParams,
+    page: &PageRequest,
+) -> Result<PageResult<Payment>, sqlx::Error> {
+    fetch_page(pool, "payments", || payment_filters(params), page, Payment::from_row).await
+}
@@ -0,0 +1,24 @@
+pub mod models;
+pub mod handlers;
+pub mod services;
+pub mod debt_policy;
+
+use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/payments")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
+            // Las rutas fijas van antes que /{id}
+            .service(services::new_charge)
+            .service(services::list_charges)
+            .service(services::get_charge)
+            .service(services::get_client_outstanding)
+            .service(services::new_payment)
+            .service(services::list_payments)
+            .service(services::get_payment)
+            .service(services::refund_payment)
+    );
+}
@@ -0,0 +1,3 @@
+pub mod payments;
+pub mod requests;
+pub mod responses;
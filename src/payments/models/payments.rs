@@ -0,0 +1,136 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, mysql::MySqlRow};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentMethod {
+    Cash,
+    Debit,
+    Credit,
+    Transfer,
+    MercadoPago,
+}
+
+impl PaymentMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentMethod::Cash => "cash",
+            PaymentMethod::Debit => "debit",
+            PaymentMethod::Credit => "credit",
+            PaymentMethod::Transfer => "transfer",
+            PaymentMethod::MercadoPago => "mercado_pago",
+        }
+    }
+}
+
+impl From<String> for PaymentMethod {
+    fn from(method: String) -> Self {
+        match method.as_str() {
+            "debit" => PaymentMethod::Debit,
+            "credit" => PaymentMethod::Credit,
+            "transfer" => PaymentMethod::Transfer,
+            "mercado_pago" => PaymentMethod::MercadoPago,
+            _ => PaymentMethod::Cash,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChargeStatus {
+    Unpaid,
+    Partial,
+    Paid,
+}
+
+impl ChargeStatus {
+    pub fn from_amounts(amount: Decimal, paid: Decimal) -> Self {
+        if paid <= Decimal::ZERO {
+            ChargeStatus::Unpaid
+        } else if paid < amount {
+            ChargeStatus::Partial
+        } else {
+            ChargeStatus::Paid
+        }
+    }
+}
+
+// Lo que debe un cliente; `paid` es la suma neta de pagos y devoluciones
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Charge {
+    pub id: i32,
+    pub client_id: i32,
+    // Período de suscripción comprado; null en cargos sueltos
+    pub period_id: Option<i32>,
+    pub description: String,
+    #[schema(value_type = String, example = "12999.99")]
+    pub amount: Decimal,
+    pub currency: String,
+    #[schema(value_type = String, example = "5000.00")]
+    pub paid: Decimal,
+    #[schema(value_type = String, example = "7999.99")]
+    pub outstanding: Decimal,
+    pub status: ChargeStatus,
+    pub created_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+impl Charge {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        let amount: Decimal = row.get("amount");
+        let paid: Decimal = row.get("paid");
+        Self {
+            id: row.get("id"),
+            client_id: row.get("client_id"),
+            period_id: row.get("period_id"),
+            description: row.get("description"),
+            amount,
+            currency: row.get("currency"),
+            paid,
+            outstanding: amount - paid,
+            status: ChargeStatus::from_amounts(amount, paid),
+            created_by: row.get("created_by"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+// Movimiento de dinero contra un cargo; las devoluciones tienen importe negativo y `refund_of`
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Payment {
+    pub id: i32,
+    pub charge_id: i32,
+    pub client_id: i32,
+    #[schema(value_type = String, example = "5000.00")]
+    pub amount: Decimal,
+    pub currency: String,
+    pub method: PaymentMethod,
+    pub reference: Option<String>,
+    pub notes: Option<String>,
+    pub refund_of: Option<i32>,
+    pub paid_at: NaiveDateTime,
+    pub recorded_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+}
+
+impl Payment {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            charge_id: row.get("charge_id"),
+            client_id: row.get("client_id"),
+            amount: row.get("amount"),
+            currency: row.get("currency"),
+            method: PaymentMethod::from(row.get::<String, _>("method")),
+            reference: row.get("reference"),
+            notes: row.get("notes"),
+            refund_of: row.get("refund_of"),
+            paid_at: row.get("paid_at"),
+            recorded_by: row.get("recorded_by"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
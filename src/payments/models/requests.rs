@@ -0,0 +1,97 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+use crate::money::{validate_amount, validate_currency};
+use crate::validation::validate_not_blank;
+use super::payments::{ChargeStatus, PaymentMethod};
+
+// Cargo suelto (no asociado a la compra de un plan)
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"client_id": 1, "description": "Matrícula anual", "amount": "15000.00"}))]
+pub struct NewChargeRequest {
+    #[validate(range(min = 1, message = "Invalid client"))]
+    pub client_id: i32,
+    #[validate(
+        length(min = 1, max = 255, message = "Description must have between 1 and 255 characters"),
+        custom(function = "validate_not_blank"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub description: String,
+    #[validate(custom(function = "validate_amount"))]
+    #[schema(value_type = String, example = "15000.00")]
+    pub amount: Decimal,
+    // Por defecto ARS
+    #[validate(custom(function = "validate_currency"))]
+    #[schema(min_length = 3, max_length = 3, pattern = "^[A-Z]{3}$")]
+    pub currency: Option<String>,
+}
+
+// Pago (total o parcial) de un cargo; no puede superar lo adeudado
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"charge_id": 1, "amount": "5000.00", "method": "mercado_pago", "reference": "MP-123456"}))]
+pub struct NewPaymentRequest {
+    #[validate(range(min = 1, message = "Invalid charge"))]
+    pub charge_id: i32,
+    #[validate(custom(function = "validate_amount"))]
+    #[schema(value_type = String, example = "5000.00")]
+    pub amount: Decimal,
+    pub method: PaymentMethod,
+    // Nro. de operación, últimos dígitos de la tarjeta, etc.
+    #[validate(length(max = 100, message = "Reference must have at most 100 characters"))]
+    #[schema(max_length = 100)]
+    pub reference: Option<String>,
+    #[validate(length(max = 255, message = "Notes must have at most 255 characters"))]
+    #[schema(max_length = 255)]
+    pub notes: Option<String>,
+}
+
+// Devolución de un pago: por defecto el total no devuelto y por el mismo medio
+#[derive(Serialize, Deserialize, ToSchema, Validate, Default)]
+#[schema(example = json!({"amount": "2000.00", "notes": "Clase cancelada"}))]
+pub struct RefundRequest {
+    #[validate(custom(function = "validate_amount"))]
+    #[schema(value_type = Option<String>, example = "2000.00")]
+    pub amount: Option<Decimal>,
+    pub method: Option<PaymentMethod>,
+    #[validate(length(max = 255, message = "Notes must have at most 255 characters"))]
+    #[schema(max_length = 255)]
+    pub notes: Option<String>,
+}
+
+// Pago al momento de comprar un plan (POST /subscriptions); sin `amount` se paga el precio completo
+#[derive(Serialize, Deserialize, Debug, ToSchema, Validate)]
+#[schema(example = json!({"method": "cash"}))]
+pub struct PurchasePayment {
+    pub method: PaymentMethod,
+    #[validate(custom(function = "validate_amount"))]
+    #[schema(value_type = Option<String>, example = "5000.00")]
+    pub amount: Option<Decimal>,
+    #[validate(length(max = 100, message = "Reference must have at most 100 characters"))]
+    #[schema(max_length = 100)]
+    pub reference: Option<String>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct PaymentQueryParams {
+    pub client_id: Option<i32>,
+    pub charge_id: Option<i32>,
+    pub method: Option<PaymentMethod>,
+    pub paid_at_from: Option<NaiveDateTime>,
+    pub paid_at_to: Option<NaiveDateTime>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct ChargeQueryParams {
+    pub client_id: Option<i32>,
+    pub status: Option<ChargeStatus>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct DebtorQueryParams {
+    pub currency: Option<String>,
+    // Solo deudas con al menos esta antigüedad (días desde el cargo impago más viejo)
+    pub min_days: Option<i64>,
+    #[schema(value_type = Option<String>, example = "1000.00")]
+    pub min_amount: Option<Decimal>,
+}
@@ -0,0 +1,78 @@
+use chrono::NaiveDateTime;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use utoipa::ToSchema;
+use super::payments::{Charge, PaymentMethod};
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct CurrencyBalance {
+    pub currency: String,
+    #[schema(value_type = String, example = "25999.98")]
+    pub charged: Decimal,
+    #[schema(value_type = String, example = "12999.99")]
+    pub paid: Decimal,
+    #[schema(value_type = String, example = "12999.99")]
+    pub outstanding: Decimal,
+}
+
+// Saldo pendiente de un cliente, por moneda, y los cargos que todavía no están pagos
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ClientOutstanding {
+    pub client_id: i32,
+    pub balances: Vec<CurrencyBalance>,
+    pub open_charges: Vec<Charge>,
+}
+
+// Deuda de un cliente en una moneda; la antigüedad se cuenta desde el cargo impago más viejo
+#[derive(Serialize, Debug, ToSchema)]
+pub struct Debt {
+    pub currency: String,
+    #[schema(value_type = String, example = "12999.99")]
+    pub outstanding: Decimal,
+    pub open_charges: i64,
+    pub oldest_unpaid_at: NaiveDateTime,
+    pub days_overdue: i64,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementEntryKind {
+    Charge,
+    Payment,
+    Refund,
+}
+
+// Renglón del estado de cuenta: los cargos suman al saldo, los pagos restan y las devoluciones vuelven a sumar
+#[derive(Serialize, Debug, ToSchema)]
+pub struct StatementEntry {
+    pub kind: StatementEntryKind,
+    pub date: NaiveDateTime,
+    pub charge_id: i32,
+    pub payment_id: Option<i32>,
+    pub description: String,
+    pub method: Option<PaymentMethod>,
+    #[schema(value_type = String, example = "-5000.00")]
+    pub amount: Decimal,
+    pub currency: String,
+    // Saldo de la moneda después del movimiento
+    #[schema(value_type = String, example = "7999.99")]
+    pub balance: Decimal,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct AccountStatement {
+    pub client_id: i32,
+    pub balances: Vec<CurrencyBalance>,
+    pub debts: Vec<Debt>,
+    pub entries: Vec<StatementEntry>,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct Debtor {
+    pub client_id: i32,
+    pub name: String,
+    pub last_name: String,
+    pub phone: String,
+    #[serde(flatten)]
+    pub debt: Debt,
+}
@@ -0,0 +1,380 @@
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+use actix_web_grants::protect;
+use rust_decimal::Decimal;
+use sqlx::MySqlPool;
+use validator::Validate;
+use crate::auth::models::jwt_models::Claims;
+use crate::cash_register::handlers::record_payment_movement;
+use crate::clients::handlers::obtain_client_by_id;
+use crate::errors::{access_denied, AppError};
+use crate::money::DEFAULT_CURRENCY;
+use crate::pagination::PageParams;
+use super::handlers::{
+    insert_charge, get_charge_by_id, lock_charge, get_charges, get_open_charges, get_client_balances,
+    insert_payment, get_payment_by_id, get_refunded_amount, get_payments_handler, PaymentEntry};
+use super::models::payments::{Charge, Payment, PaymentMethod};
+use super::models::requests::{
+    NewChargeRequest, NewPaymentRequest, RefundRequest, ChargeQueryParams, PaymentQueryParams};
+use super::models::responses::ClientOutstanding;
+
+// Un pago no puede superar lo que falta pagar del cargo
+pub fn check_outstanding(charge: &Charge, amount: Decimal) -> Result<(), AppError> {
+    if amount > charge.outstanding {
+        return Err(AppError::Validation {
+            message: "Amount exceeds the outstanding balance of the charge".to_string(),
+            details: Some(serde_json::json!({ "field": "amount", "outstanding": charge.outstanding })),
+        });
+    }
+    Ok(())
+}
+
+// Usuario que registra el movimiento; las API keys no tienen usuario asociado
+fn recorded_by(claims: Option<web::ReqData<Claims>>) -> Option<i32> {
+    claims.map(|claims| claims.user_id as i32)
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// CHARGE ENDPOINTS ////////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+#[post("/charges")]
+#[protect(any("Admin", "Trainer", "payments:write"), error = "access_denied")]
+pub async fn new_charge(
+    pool: web::Data<MySqlPool>,
+    claims: Option<web::ReqData<Claims>>,
+    req: web::Json<NewChargeRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    if obtain_client_by_id(&pool, req.client_id).await?.is_none() {
+        return Err(AppError::Validation {
+            message: "Client not found".to_string(),
+            details: Some(serde_json::json!({ "field": "client_id" })),
+        })
+    }
+
+    let currency = req.currency.as_deref().unwrap_or(DEFAULT_CURRENCY);
+    let mut conn = pool.acquire().await?;
+    let charge_id = insert_charge(
+        &mut conn, req.client_id, None, req.description.trim(), req.amount, currency, recorded_by(claims)).await?;
+    let charge = get_charge_by_id(&pool, charge_id).await?
+        .ok_or_else(|| AppError::NotFound("Charge not found".to_string()))?;
+    tracing::info!("Charge {} created for client {}", charge.id, charge.client_id);
+    Ok(HttpResponse::Created().json(charge))
+}
+
+#[get("/charges")]
+#[protect(any("Admin", "Trainer", "payments:read"), error = "access_denied")]
+pub async fn list_charges(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<ChargeQueryParams>,
+) -> Result<HttpResponse, AppError> {
+    let charges = get_charges(&pool, &query).await?;
+    Ok(HttpResponse::Ok().json(charges))
+}
+
+#[get("/charges/{id}")]
+#[protect(any("Admin", "Trainer", "payments:read"), error = "access_denied")]
+pub async fn get_charge(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    match get_charge_by_id(&pool, id.into_inner()).await? {
+        Some(charge) => Ok(HttpResponse::Ok().json(charge)),
+        None => Err(AppError::NotFound("Charge not found".to_string())),
+    }
+}
+
+#[get("/outstanding/{client_id}")]
+#[protect(any("Admin", "Trainer", "payments:read"), error = "access_denied")]
+pub async fn get_client_outstanding(
+    pool: web::Data<MySqlPool>,
+    client_id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let client_id = client_id.into_inner();
+    if obtain_client_by_id(&pool, client_id).await?.is_none() {
+        return Err(AppError::NotFound("Client not found".to_string()))
+    }
+    let balances = get_client_balances(&pool, client_id).await?;
+    let open_charges = get_open_charges(&pool, client_id).await?;
+    Ok(HttpResponse::Ok().json(ClientOutstanding { client_id, balances, open_charges }))
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// PAYMENT ENDPOINTS ///////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+#[post("/")]
+#[protect(any("Admin", "Trainer", "payments:write"), error = "access_denied")]
+pub async fn new_payment(
+    pool: web::Data<MySqlPool>,
+    claims: Option<web::ReqData<Claims>>,
+    req: web::Json<NewPaymentRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+
+    let mut tx = pool.begin().await?;
+    let charge = lock_charge(&mut tx, req.charge_id).await?
+        .ok_or_else(|| AppError::Validation {
+            message: "Charge not found".to_string(),
+            details: Some(serde_json::json!({ "field": "charge_id" })),
+        })?;
+    check_outstanding(&charge, req.amount)?;
+    let payment = insert_payment(&mut tx, PaymentEntry {
+        charge: &charge,
+        amount: req.amount,
+        method: req.method,
+        reference: req.reference.as_deref(),
+        notes: req.notes.as_deref(),
+        refund_of: None,
+        recorded_by: recorded_by(claims),
+    }).await?;
+    // Todo pago entra en la caja abierta; en efectivo no se aceptan con la caja cerrada
+    if !record_payment_movement(&mut tx, &payment).await? && payment.method == PaymentMethod::Cash {
+        return Err(AppError::Conflict("Cash register is closed".to_string()))
+    }
+    tx.commit().await?;
+
+    tracing::info!("Payment {} recorded for charge {}", payment.id, charge.id);
+    Ok(HttpResponse::Created().json(payment))
+}
+
+#[get("/")]
+#[protect(any("Admin", "Trainer", "payments:read"), error = "access_denied")]
+pub async fn list_payments(
+    pool: web::Data<MySqlPool>,
+    http_req: HttpRequest,
+    query: web::Query<PaymentQueryParams>,
+    page: web::Query<PageParams>,
+) -> Result<HttpResponse, AppError> {
+    let page = page.resolve::<Payment>()?;
+    let payments = get_payments_handler(&pool, &query, &page).await?;
+    Ok(HttpResponse::Ok().json(payments.into_page(&page, http_req.path(), http_req.query_string())))
+}
+
+#[get("/{id}")]
+#[protect(any("Admin", "Trainer", "payments:read"), error = "access_denied")]
+pub async fn get_payment(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    match get_payment_by_id(&pool, id.into_inner()).await? {
+        Some(payment) => Ok(HttpResponse::Ok().json(payment)),
+        None => Err(AppError::NotFound("Payment not found".to_string())),
+    }
+}
+
+// Devolución total o parcial: se registra como un pago negativo contra el mismo cargo
+#[post("/{id}/refund")]
+#[protect("Admin", error = "access_denied")]
+pub async fn refund_payment(
+    pool: web::Data<MySqlPool>,
+    claims: Option<web::ReqData<Claims>>,
+    id: web::Path<i32>,
+    req: web::Json<RefundRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    let original = get_payment_by_id(&pool, id.into_inner()).await?
+        .ok_or_else(|| AppError::NotFound("Payment not found".to_string()))?;
+    if original.refund_of.is_some() {
+        return Err(AppError::validation("A refund cannot be refunded"))
+    }
+
+    let mut tx = pool.begin().await?;
+    let charge = lock_charge(&mut tx, original.charge_id).await?
+        .ok_or_else(|| AppError::NotFound("Charge not found".to_string()))?;
+    let refundable = original.amount - get_refunded_amount(&mut tx, original.id).await?;
+    if refundable <= Decimal::ZERO {
+        return Err(AppError::Conflict("Payment already fully refunded".to_string()))
+    }
+    let amount = req.amount.unwrap_or(refundable);
+    if amount > refundable {
+        return Err(AppError::Validation {
+            message: "Amount exceeds the refundable amount of the payment".to_string(),
+            details: Some(serde_json::json!({ "field": "amount", "refundable": refundable })),
+        })
+    }
+    let refund = insert_payment(&mut tx, PaymentEntry {
+        charge: &charge,
+        amount: -amount,
+        method: req.method.unwrap_or(original.method),
+        reference: original.reference.as_deref(),
+        notes: req.notes.as_deref(),
+        refund_of: Some(original.id),
+        recorded_by: recorded_by(claims),
+    }).await?;
+    if !record_payment_movement(&mut tx, &refund).await? && refund.method == PaymentMethod::Cash {
+        return Err(AppError::Conflict("Cash register is closed".to_string()))
+    }
+    tx.commit().await?;
+
+    tracing::info!("Payment {} refunded ({}) as payment {}", original.id, amount, refund.id);
+    Ok(HttpResponse::Created().json(refund))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use validator::Validate;
+    use crate::payments::models::payments::{Charge, ChargeStatus, PaymentMethod};
+    use crate::payments::models::requests::{NewPaymentRequest, RefundRequest};
+
+    fn create_test_charge(amount: i64, paid: i64) -> Charge {
+        let (amount, paid) = (Decimal::new(amount, 2), Decimal::new(paid, 2));
+        Charge {
+            id: 1,
+            client_id: 1,
+            period_id: Some(1),
+            description: "Plan Básico".to_string(),
+            amount,
+            currency: "ARS".to_string(),
+            paid,
+            outstanding: amount - paid,
+            status: ChargeStatus::from_amounts(amount, paid),
+            created_by: None,
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn test_charge_status() {
+        assert_eq!(create_test_charge(1299999, 0).status, ChargeStatus::Unpaid);
+        assert_eq!(create_test_charge(1299999, 500000).status, ChargeStatus::Partial);
+        assert_eq!(create_test_charge(1299999, 1299999).status, ChargeStatus::Paid);
+        // Pagado y devuelto por completo vuelve a estar impago
+        assert_eq!(ChargeStatus::from_amounts(Decimal::new(100, 0), Decimal::ZERO), ChargeStatus::Unpaid);
+    }
+
+    #[test]
+    fn test_payment_method_serialization() {
+        assert_eq!(serde_json::to_string(&PaymentMethod::MercadoPago).unwrap(), r#""mercado_pago""#);
+        let method: PaymentMethod = serde_json::from_str(r#""transfer""#).unwrap();
+        assert_eq!(method, PaymentMethod::Transfer);
+        assert!(serde_json::from_str::<PaymentMethod>(r#""cheque""#).is_err());
+        for method in [PaymentMethod::Cash, PaymentMethod::Debit, PaymentMethod::Credit,
+                       PaymentMethod::Transfer, PaymentMethod::MercadoPago] {
+            assert_eq!(PaymentMethod::from(method.as_str().to_string()), method);
+        }
+    }
+
+    #[test]
+    fn test_payment_cannot_exceed_outstanding() {
+        let charge = create_test_charge(1299999, 500000);
+        assert!(super::check_outstanding(&charge, Decimal::new(799999, 2)).is_ok());
+        assert!(super::check_outstanding(&charge, Decimal::new(800000, 2)).is_err());
+    }
+
+    #[test]
+    fn test_payment_request_validation() {
+        let json = r#"{"charge_id":1,"amount":"5000.00","method":"mercado_pago","reference":"MP-1"}"#;
+        let request: NewPaymentRequest = serde_json::from_str(json).unwrap();
+        assert!(request.validate().is_ok());
+        assert_eq!(request.method, PaymentMethod::MercadoPago);
+
+        for amount in ["0", "-10", "10.001"] {
+            let json = format!(r#"{{"charge_id":1,"amount":"{}","method":"cash"}}"#, amount);
+            let request: NewPaymentRequest = serde_json::from_str(&json).unwrap();
+            assert!(request.validate().unwrap_err().field_errors().contains_key("amount"));
+        }
+
+        // Las devoluciones se piden en positivo y se guardan en negativo
+        let refund = RefundRequest { amount: Some(Decimal::new(-100, 0)), ..Default::default() };
+        assert!(refund.validate().is_err());
+        assert!(RefundRequest::default().validate().is_ok());
+    }
+
+    mod debt_policy_tests {
+        use chrono::{Duration, Utc};
+        use rust_decimal::Decimal;
+        use crate::payments::debt_policy::{DebtAction, DebtPolicy};
+        use crate::payments::models::responses::Debt;
+
+        fn debt(outstanding: i64, days: i64) -> Debt {
+            Debt {
+                currency: "ARS".to_string(),
+                outstanding: Decimal::new(outstanding, 2),
+                open_charges: 1,
+                oldest_unpaid_at: Utc::now().naive_utc() - Duration::days(days) - Duration::hours(1),
+                days_overdue: days,
+            }
+        }
+
+        #[test]
+        fn test_default_policy_warns() {
+            let policy = DebtPolicy::default();
+            let now = Utc::now().naive_utc();
+            assert!(policy.evaluate(&[], now).unwrap().is_none());
+            let warning = policy.evaluate(&[debt(500000, 0)], now).unwrap().unwrap();
+            assert!(warning.contains("5000.00 ARS"));
+        }
+
+        #[test]
+        fn test_block_after_grace_days() {
+            let policy: DebtPolicy = serde_json::from_str(r#"{"action":"block","grace_days":10}"#).unwrap();
+            assert_eq!(policy.action, DebtAction::Block);
+            let now = Utc::now().naive_utc();
+            assert!(policy.evaluate(&[debt(500000, 9)], now).unwrap().is_none());
+            assert!(policy.evaluate(&[debt(500000, 10)], now).is_err());
+        }
+
+        #[test]
+        fn test_small_balances_and_allow_are_ignored() {
+            let now = Utc::now().naive_utc();
+            let policy: DebtPolicy = serde_json::from_str(r#"{"action":"block","min_amount":"100"}"#).unwrap();
+            assert!(policy.evaluate(&[debt(9999, 30)], now).unwrap().is_none());
+            assert!(policy.evaluate(&[debt(10001, 30)], now).is_err());
+
+            let policy = DebtPolicy { action: DebtAction::Allow, ..Default::default() };
+            assert!(policy.evaluate(&[debt(500000, 365)], now).unwrap().is_none());
+        }
+    }
+
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_missing_token_is_unauthorized() {
+            let app = init_app(crate::payments::routes).await;
+
+            let req = test::TestRequest::get().uri("/payments/").to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_refund() {
+            let app = init_app(crate::payments::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/payments/1/refund")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_invalid_payment_is_unprocessable() {
+            let app = init_app(crate::payments::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/payments/")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({"charge_id": 1, "amount": "-5", "method": "cash"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+            let req = test::TestRequest::post()
+                .uri("/payments/charges")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .set_json(serde_json::json!({"client_id": 1, "description": " ", "amount": "100"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+}
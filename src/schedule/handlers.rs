@@ -0,0 +1,244 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use sqlx::mysql::{MySqlArguments, MySqlQueryResult};
+use sqlx::{self, Arguments, MySqlPool, Row};
+use crate::add_filter;
+use crate::closures::handlers::get_closures;
+use crate::closures::models::requests::ClosureQueryParams;
+use super::models::requests::{MoveSessionRequest, NewTemplateRequest, TemplateQueryParams};
+use super::models::responses::GeneratedSessions;
+use super::models::schedule::{ClassSession, ClassTemplate, SessionStatus};
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// TEMPLATE HANDLERS ///////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+pub async fn get_template_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<ClassTemplate>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM class_templates WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| ClassTemplate::from_row(&row)))
+}
+
+// Plantillas vigentes, en el orden de la grilla
+pub async fn get_templates(
+    pool: &MySqlPool,
+    params: &TemplateQueryParams,
+) -> Result<Vec<ClassTemplate>, sqlx::Error> {
+    let mut query = String::from("SELECT * FROM class_templates WHERE active = true");
+    let mut args = MySqlArguments::default();
+
+    add_filter!(query, args, &params.discipline_id, " AND discipline_id = ?");
+    add_filter!(query, args, &params.weekday.map(|weekday| weekday.number()), " AND weekday = ?");
+    query.push_str(" ORDER BY weekday, starts_at, id");
+
+    let rows = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(ClassTemplate::from_row).collect())
+}
+
+pub async fn create_template_handler(
+    pool: &MySqlPool,
+    req: &NewTemplateRequest,
+) -> Result<ClassTemplate, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO class_templates (discipline_id, weekday, starts_at, duration_minutes, room, trainer_id)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#)
+        .bind(req.discipline_id)
+        .bind(req.weekday.number())
+        .bind(req.starts_at)
+        .bind(req.duration_minutes)
+        .bind(req.room.as_deref().map(str::trim))
+        .bind(req.trainer_id)
+        .execute(pool)
+        .await?;
+
+    get_template_by_id(pool, result.last_insert_id() as i32).await?
+        .ok_or(sqlx::Error::RowNotFound)
+}
+
+// Da de baja la plantilla y borra sus clases futuras que todavía no tienen asistencias
+pub async fn deactivate_template_handler(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query("UPDATE class_templates SET active = false WHERE id = ? AND active = true")
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM class_sessions
+        WHERE template_id = ? AND starts_at > NOW()
+          AND NOT EXISTS (SELECT 1 FROM class_attendance a WHERE a.session_id = class_sessions.id)
+        "#)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(result)
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// SESSION HANDLERS ////////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+pub async fn get_session_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<ClassSession>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM class_sessions WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| ClassSession::from_row(&row)))
+}
+
+// Clases que empiezan en [from, to), en orden de horario
+pub async fn get_sessions_between(
+    pool: &MySqlPool,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+    discipline_id: Option<i32>,
+    trainer_id: Option<i32>,
+) -> Result<Vec<ClassSession>, sqlx::Error> {
+    let mut query = String::from("SELECT * FROM class_sessions WHERE starts_at >= ? AND starts_at < ?");
+    let mut args = MySqlArguments::default();
+    let _ = args.add(from);
+    let _ = args.add(to);
+
+    add_filter!(query, args, &discipline_id, " AND discipline_id = ?");
+    add_filter!(query, args, &trainer_id, " AND trainer_id = ?");
+    query.push_str(" ORDER BY starts_at, id");
+
+    let rows = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.iter().map(ClassSession::from_row).collect())
+}
+
+// Crea las clases de las plantillas vigentes entre `from` y `to`, salteando los días de cierre.
+// Una plantilla genera una sola clase por fecha, así que regenerar un rango no duplica ni pisa excepciones.
+// Solo se cuentan las clases nuevas: INSERT IGNORE informa 0 filas si otra generación la creó primero
+pub async fn generate_sessions_handler(
+    pool: &MySqlPool,
+    from: NaiveDate,
+    to: NaiveDate,
+    discipline_id: Option<i32>,
+) -> Result<GeneratedSessions, sqlx::Error> {
+    let templates = get_templates(pool, &TemplateQueryParams { discipline_id, weekday: None }).await?;
+    let mut generated = GeneratedSessions { created: 0, skipped_closed: 0 };
+
+    for template in templates {
+        let closures = get_closures(pool, &ClosureQueryParams {
+            discipline_id: Some(template.discipline_id),
+            from: Some(from),
+        }).await?;
+        let existing: Vec<NaiveDate> = sqlx::query(
+            "SELECT scheduled_on FROM class_sessions WHERE template_id = ? AND scheduled_on BETWEEN ? AND ?")
+            .bind(template.id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| row.get("scheduled_on"))
+            .collect();
+
+        let (missing, skipped_closed) = template.missing_occurrences(
+            from, to, &existing, |date| closures.iter().any(|closure| closure.is_closed_on(date)));
+        generated.skipped_closed += skipped_closed;
+
+        for date in missing {
+            let result = sqlx::query(
+                r#"
+                INSERT IGNORE INTO class_sessions
+                    (template_id, discipline_id, scheduled_on, starts_at, duration_minutes, room, trainer_id)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#)
+                .bind(template.id)
+                .bind(template.discipline_id)
+                .bind(date)
+                .bind(date.and_time(template.starts_at))
+                .bind(template.duration_minutes)
+                .bind(&template.room)
+                .bind(template.trainer_id)
+                .execute(pool)
+                .await?;
+            generated.created += result.rows_affected();
+        }
+    }
+    Ok(generated)
+}
+
+// Cancela una clase programada; false si no existe o ya estaba cancelada
+pub async fn cancel_session_handler(
+    pool: &MySqlPool,
+    id: i32,
+    reason: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE class_sessions
+        SET status = ?, notes = COALESCE(?, notes)
+        WHERE id = ? AND status = ?
+        "#)
+        .bind(SessionStatus::Cancelled.as_str())
+        .bind(reason)
+        .bind(id)
+        .bind(SessionStatus::Scheduled.as_str())
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+// Mueve una clase programada guardando el horario original (el primero, si se mueve más de una vez).
+// false si no existe o está cancelada
+pub async fn move_session_handler(
+    pool: &MySqlPool,
+    id: i32,
+    req: &MoveSessionRequest,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE class_sessions
+        SET original_starts_at = COALESCE(original_starts_at, starts_at), starts_at = ?,
+            duration_minutes = COALESCE(?, duration_minutes), room = COALESCE(?, room),
+            trainer_id = COALESCE(?, trainer_id), notes = COALESCE(?, notes)
+        WHERE id = ? AND status = ?
+        "#)
+        .bind(req.starts_at)
+        .bind(req.duration_minutes)
+        .bind(req.room.as_deref().map(str::trim))
+        .bind(req.trainer_id)
+        .bind(req.notes.as_deref())
+        .bind(id)
+        .bind(SessionStatus::Scheduled.as_str())
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn count_session_attendance(
+    pool: &MySqlPool,
+    session_id: i32,
+) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query("SELECT COUNT(*) AS total FROM class_attendance WHERE session_id = ?")
+        .bind(session_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(row.get("total"))
+}
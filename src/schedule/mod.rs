@@ -0,0 +1,22 @@
+pub mod models;
+pub mod handlers;
+pub mod services;
+
+use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/schedule")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
+            .service(services::list_templates)
+            .service(services::new_template)
+            .service(services::delete_template)
+            .service(services::generate_sessions)
+            .service(services::get_session)
+            .service(services::cancel_session)
+            .service(services::move_session)
+            .service(services::get_timetable)
+    );
+}
@@ -0,0 +1,3 @@
+pub mod schedule;
+pub mod requests;
+pub mod responses;
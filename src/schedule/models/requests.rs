@@ -0,0 +1,94 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+use crate::errors::AppError;
+use super::schedule::Weekday;
+
+// Rango máximo de una generación de clases
+pub const MAX_GENERATION_DAYS: i64 = 92;
+
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"discipline_id": 1, "weekday": "monday", "starts_at": "18:30:00", "duration_minutes": 60, "room": "Sala 1", "trainer_id": 2}))]
+pub struct NewTemplateRequest {
+    #[validate(range(min = 1, message = "Invalid discipline"))]
+    #[schema(minimum = 1)]
+    pub discipline_id: i32,
+    pub weekday: Weekday,
+    // UTC, "HH:MM:SS"
+    #[schema(value_type = String, example = "18:30:00")]
+    pub starts_at: NaiveTime,
+    #[validate(range(min = 1, max = 480, message = "Duration must be between 1 and 480 minutes"))]
+    #[schema(minimum = 1, maximum = 480)]
+    pub duration_minutes: i32,
+    #[validate(length(max = 100, message = "Room must have at most 100 characters"))]
+    #[schema(max_length = 100)]
+    pub room: Option<String>,
+    #[validate(range(min = 1, message = "Invalid trainer"))]
+    #[schema(minimum = 1)]
+    pub trainer_id: Option<i32>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct TemplateQueryParams {
+    pub discipline_id: Option<i32>,
+    pub weekday: Option<Weekday>,
+}
+
+// Genera las clases de las plantillas vigentes entre `from` y `to` inclusive (de una disciplina o de todas)
+#[derive(Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({"from": "2026-10-19", "to": "2026-11-15"}))]
+pub struct GenerateSessionsRequest {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discipline_id: Option<i32>,
+}
+
+impl GenerateSessionsRequest {
+    pub fn validate_range(&self) -> Result<(), AppError> {
+        let message = if self.to < self.from {
+            "to must not be before from".to_string()
+        } else if (self.to - self.from).num_days() >= MAX_GENERATION_DAYS {
+            format!("Sessions can be generated for at most {} days at a time", MAX_GENERATION_DAYS)
+        } else {
+            return Ok(());
+        };
+        Err(AppError::Validation { message, details: Some(serde_json::json!({ "field": "to" })) })
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Validate, Default)]
+#[schema(example = json!({"reason": "Profesor enfermo"}))]
+pub struct CancelSessionRequest {
+    #[validate(length(max = 255, message = "Reason must have at most 255 characters"))]
+    #[schema(max_length = 255)]
+    pub reason: Option<String>,
+}
+
+// Mueve una clase puntual; sin `room` ni `trainer_id` se mantienen los de la clase
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"starts_at": "2026-10-21T19:00:00", "room": "Sala 2"}))]
+pub struct MoveSessionRequest {
+    pub starts_at: NaiveDateTime,
+    #[validate(range(min = 1, max = 480, message = "Duration must be between 1 and 480 minutes"))]
+    #[schema(minimum = 1, maximum = 480)]
+    pub duration_minutes: Option<i32>,
+    #[validate(length(max = 100, message = "Room must have at most 100 characters"))]
+    #[schema(max_length = 100)]
+    pub room: Option<String>,
+    #[validate(range(min = 1, message = "Invalid trainer"))]
+    #[schema(minimum = 1)]
+    pub trainer_id: Option<i32>,
+    #[validate(length(max = 255, message = "Notes must have at most 255 characters"))]
+    #[schema(max_length = 255)]
+    pub notes: Option<String>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct TimetableParams {
+    // Cualquier día de la semana a mostrar; por defecto hoy
+    pub date: Option<NaiveDate>,
+    pub discipline_id: Option<i32>,
+    pub trainer_id: Option<i32>,
+}
@@ -0,0 +1,58 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use utoipa::ToSchema;
+use super::schedule::{ClassSession, Weekday};
+
+#[derive(Serialize, ToSchema)]
+pub struct GeneratedSessions {
+    pub created: u64,
+    // Fechas salteadas por un cierre del gimnasio
+    pub skipped_closed: u64,
+}
+
+// Clase con la cantidad de asistencias registradas
+#[derive(Serialize, ToSchema)]
+pub struct SessionDetail {
+    #[serde(flatten)]
+    pub session: ClassSession,
+    pub attendance: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TimetableDay {
+    pub date: NaiveDate,
+    pub weekday: Weekday,
+    pub sessions: Vec<ClassSession>,
+}
+
+// Grilla de lunes a domingo, incluidas las clases canceladas
+#[derive(Serialize, ToSchema)]
+pub struct Timetable {
+    pub week_start: NaiveDate,
+    pub week_end: NaiveDate,
+    pub days: Vec<TimetableDay>,
+}
+
+impl Timetable {
+    // Lunes de la semana de `date`
+    pub fn week_start(date: NaiveDate) -> NaiveDate {
+        date - Duration::days(Weekday::of(date).number() as i64 - 1)
+    }
+
+    // Reparte las clases (ordenadas por horario) en los siete días de la semana que empieza en `week_start`
+    pub fn build(week_start: NaiveDate, sessions: Vec<ClassSession>) -> Self {
+        let mut days: Vec<TimetableDay> = (0..7)
+            .map(|offset| {
+                let date = week_start + Duration::days(offset);
+                TimetableDay { date, weekday: Weekday::of(date), sessions: Vec::new() }
+            })
+            .collect();
+        for session in sessions {
+            let offset = (session.starts_at.date() - week_start).num_days();
+            if let Some(day) = usize::try_from(offset).ok().and_then(|offset| days.get_mut(offset)) {
+                day.sessions.push(session);
+            }
+        }
+        Self { week_start, week_end: week_start + Duration::days(6), days }
+    }
+}
@@ -0,0 +1,183 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, mysql::MySqlRow};
+use utoipa::ToSchema;
+
+// Día de la semana; en la base se guarda el número ISO (1 = lunes)
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday, Weekday::Tuesday, Weekday::Wednesday, Weekday::Thursday,
+        Weekday::Friday, Weekday::Saturday, Weekday::Sunday];
+
+    pub fn number(&self) -> i8 {
+        Weekday::ALL.iter().position(|day| day == self).unwrap_or(0) as i8 + 1
+    }
+
+    pub fn from_number(number: i8) -> Self {
+        Weekday::ALL[(number.clamp(1, 7) - 1) as usize]
+    }
+
+    pub fn of(date: NaiveDate) -> Self {
+        Weekday::from_number(date.weekday().number_from_monday() as i8)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Scheduled,
+    Cancelled,
+}
+
+impl SessionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionStatus::Scheduled => "scheduled",
+            SessionStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl From<String> for SessionStatus {
+    fn from(status: String) -> Self {
+        match status.as_str() {
+            "cancelled" => SessionStatus::Cancelled,
+            _ => SessionStatus::Scheduled,
+        }
+    }
+}
+
+// Clase fija de la grilla semanal; `starts_at` en UTC
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ClassTemplate {
+    pub id: i32,
+    pub discipline_id: i32,
+    pub weekday: Weekday,
+    #[schema(value_type = String, example = "18:30:00")]
+    pub starts_at: NaiveTime,
+    pub duration_minutes: i32,
+    pub room: Option<String>,
+    pub trainer_id: Option<i32>,
+    pub active: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ClassTemplate {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            discipline_id: row.get("discipline_id"),
+            weekday: Weekday::from_number(row.get("weekday")),
+            starts_at: row.get("starts_at"),
+            duration_minutes: row.get("duration_minutes"),
+            room: row.get("room"),
+            trainer_id: row.get("trainer_id"),
+            active: row.get::<i8, _>("active") != 0,
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+
+    // Fechas entre `from` y `to` (inclusive) que caen en el día de la plantilla
+    pub fn occurrences(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let offset = (self.weekday.number() as i64 - Weekday::of(from).number() as i64).rem_euclid(7);
+        let mut dates = Vec::new();
+        let mut date = from + Duration::days(offset);
+        while date <= to {
+            dates.push(date);
+            date += Duration::days(7);
+        }
+        dates
+    }
+
+    // Ocurrencias que todavía no tienen clase generada (`existing`), sin las que caen en un cierre.
+    // Devuelve además cuántas se saltearon por cierre
+    pub fn missing_occurrences(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        existing: &[NaiveDate],
+        is_closed: impl Fn(NaiveDate) -> bool,
+    ) -> (Vec<NaiveDate>, u64) {
+        let mut skipped_closed = 0;
+        let missing = self.occurrences(from, to).into_iter()
+            .filter(|date| {
+                if is_closed(*date) {
+                    skipped_closed += 1;
+                    return false;
+                }
+                !existing.contains(date)
+            })
+            .collect();
+        (missing, skipped_closed)
+    }
+}
+
+// Clase concreta de un día. `scheduled_on` es la fecha que le tocaba según la plantilla, aunque se haya movido
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ClassSession {
+    pub id: i32,
+    pub template_id: Option<i32>,
+    pub discipline_id: i32,
+    pub scheduled_on: NaiveDate,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub duration_minutes: i32,
+    pub room: Option<String>,
+    pub trainer_id: Option<i32>,
+    pub status: SessionStatus,
+    // Horario original si la clase se movió
+    pub original_starts_at: Option<NaiveDateTime>,
+    pub notes: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ClassSession {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        let starts_at: NaiveDateTime = row.get("starts_at");
+        let duration_minutes: i32 = row.get("duration_minutes");
+        Self {
+            id: row.get("id"),
+            template_id: row.get("template_id"),
+            discipline_id: row.get("discipline_id"),
+            scheduled_on: row.get("scheduled_on"),
+            starts_at,
+            ends_at: starts_at + Duration::minutes(duration_minutes as i64),
+            duration_minutes,
+            room: row.get("room"),
+            trainer_id: row.get("trainer_id"),
+            status: SessionStatus::from(row.get::<String, _>("status")),
+            original_starts_at: row.get("original_starts_at"),
+            notes: row.get("notes"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+        }
+    }
+
+    // Motivo por el que no se puede registrar una asistencia de la disciplina en esta clase en `date`
+    pub fn attendance_error(&self, discipline_id: i32, date: NaiveDate) -> Option<&'static str> {
+        if self.status == SessionStatus::Cancelled {
+            Some("session_cancelled")
+        } else if self.discipline_id != discipline_id {
+            Some("wrong_discipline")
+        } else if self.starts_at.date() != date {
+            Some("session_not_today")
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,386 @@
+use actix_web::{delete, get, post, web, HttpResponse};
+use actix_web_grants::protect;
+use chrono::{Duration, Utc};
+use sqlx::MySqlPool;
+use validator::Validate;
+use crate::errors::{access_denied, AppError};
+use crate::membership::handlers::get_discipline_by_id;
+use crate::users::handlers::obtain_user_by_id;
+use super::handlers::{
+    get_templates, create_template_handler, deactivate_template_handler, get_session_by_id,
+    get_sessions_between, generate_sessions_handler, cancel_session_handler, move_session_handler,
+    count_session_attendance};
+use super::models::requests::{
+    NewTemplateRequest, TemplateQueryParams, GenerateSessionsRequest, CancelSessionRequest, MoveSessionRequest,
+    TimetableParams};
+use super::models::responses::{SessionDetail, Timetable};
+
+fn invalid_field(message: &str, field: &str) -> AppError {
+    AppError::Validation {
+        message: message.to_string(),
+        details: Some(serde_json::json!({ "field": field })),
+    }
+}
+
+// La disciplina debe existir y no estar dada de baja
+async fn ensure_discipline(pool: &MySqlPool, discipline_id: i32) -> Result<(), AppError> {
+    match get_discipline_by_id(pool, discipline_id).await? {
+        Some(discipline) if discipline.deleted_at.is_none() => Ok(()),
+        _ => Err(invalid_field("Invalid discipline", "discipline_id")),
+    }
+}
+
+// El profesor es un usuario activo (Admin o Trainer)
+async fn ensure_trainer(pool: &MySqlPool, trainer_id: Option<i32>) -> Result<(), AppError> {
+    let Some(trainer_id) = trainer_id else {
+        return Ok(());
+    };
+    match obtain_user_by_id(pool, trainer_id).await? {
+        Some(user) if user.active => Ok(()),
+        _ => Err(invalid_field("Invalid trainer", "trainer_id")),
+    }
+}
+
+async fn session_detail(pool: &MySqlPool, id: i32) -> Result<SessionDetail, AppError> {
+    let session = get_session_by_id(pool, id).await?
+        .ok_or_else(|| AppError::NotFound("Class session not found".to_string()))?;
+    let attendance = count_session_attendance(pool, session.id).await?;
+    Ok(SessionDetail { session, attendance })
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// TEMPLATE ENDPOINTS //////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+#[get("/templates")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn list_templates(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<TemplateQueryParams>,
+) -> Result<HttpResponse, AppError> {
+    let templates = get_templates(&pool, &query).await?;
+    Ok(HttpResponse::Ok().json(templates))
+}
+
+#[post("/templates")]
+#[protect("Admin", error = "access_denied")]
+pub async fn new_template(
+    pool: web::Data<MySqlPool>,
+    req: web::Json<NewTemplateRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    ensure_discipline(&pool, req.discipline_id).await?;
+    ensure_trainer(&pool, req.trainer_id).await?;
+
+    let template = create_template_handler(&pool, &req).await?;
+    tracing::info!("Class template {} created", template.id);
+    Ok(HttpResponse::Created().json(template))
+}
+
+// Las clases ya generadas con asistencias se conservan
+#[delete("/templates/{id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn delete_template(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    if deactivate_template_handler(&pool, id.into_inner()).await?.rows_affected() == 0 {
+        return Err(AppError::NotFound("Class template not found".to_string()))
+    }
+    tracing::info!("Class template deleted successfully");
+    Ok(HttpResponse::Ok().body("Class template deleted successfully"))
+}
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// SESSION ENDPOINTS ///////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+#[post("/sessions/generate")]
+#[protect("Admin", error = "access_denied")]
+pub async fn generate_sessions(
+    pool: web::Data<MySqlPool>,
+    req: web::Json<GenerateSessionsRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate_range()?;
+    let generated = generate_sessions_handler(&pool, req.from, req.to, req.discipline_id).await?;
+    tracing::info!("Generated {} class sessions from {} to {}", generated.created, req.from, req.to);
+    Ok(HttpResponse::Ok().json(generated))
+}
+
+#[get("/sessions/{id}")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn get_session(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    Ok(HttpResponse::Ok().json(session_detail(&pool, id.into_inner()).await?))
+}
+
+// Cancela una clase puntual sin tocar la plantilla
+#[post("/sessions/{id}/cancel")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn cancel_session(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+    req: Option<web::Json<CancelSessionRequest>>,
+) -> Result<HttpResponse, AppError> {
+    let req = req.map(web::Json::into_inner).unwrap_or_default();
+    req.validate()?;
+    let session_id = id.into_inner();
+    let session = session_detail(&pool, session_id).await?;
+
+    if !cancel_session_handler(&pool, session_id, req.reason.as_deref().map(str::trim)).await? {
+        return Err(AppError::Conflict("Class session is already cancelled".to_string()))
+    }
+    if session.attendance > 0 {
+        tracing::warn!("Class session {} cancelled with {} attendances", session_id, session.attendance);
+    }
+    tracing::info!("Class session {} cancelled", session_id);
+    Ok(HttpResponse::Ok().json(session_detail(&pool, session_id).await?))
+}
+
+// Mueve una clase puntual a otro horario (y opcionalmente otra sala o profesor)
+#[post("/sessions/{id}/move")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn move_session(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+    req: web::Json<MoveSessionRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    if req.starts_at <= Utc::now().naive_utc() {
+        return Err(invalid_field("starts_at must be in the future", "starts_at"));
+    }
+    let session_id = id.into_inner();
+    session_detail(&pool, session_id).await?;
+    ensure_trainer(&pool, req.trainer_id).await?;
+
+    if !move_session_handler(&pool, session_id, &req).await? {
+        return Err(AppError::Conflict("Cancelled class sessions cannot be moved".to_string()))
+    }
+    tracing::info!("Class session {} moved to {}", session_id, req.starts_at);
+    Ok(HttpResponse::Ok().json(session_detail(&pool, session_id).await?))
+}
+
+// Grilla de la semana con las clases generadas; también la usan los dispositivos que registran asistencias
+#[get("/timetable")]
+#[protect(any("Admin", "Trainer", "attendance:write"), error = "access_denied")]
+pub async fn get_timetable(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<TimetableParams>,
+) -> Result<HttpResponse, AppError> {
+    let week_start = Timetable::week_start(query.date.unwrap_or_else(|| Utc::now().date_naive()));
+    let from = week_start.and_time(chrono::NaiveTime::MIN);
+    let sessions = get_sessions_between(
+        &pool, from, from + Duration::days(7), query.discipline_id, query.trainer_id).await?;
+    Ok(HttpResponse::Ok().json(Timetable::build(week_start, sessions)))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Utc};
+    use validator::Validate;
+    use crate::schedule::models::requests::{GenerateSessionsRequest, MoveSessionRequest, NewTemplateRequest};
+    use crate::schedule::models::responses::Timetable;
+    use crate::schedule::models::schedule::{ClassSession, ClassTemplate, SessionStatus, Weekday};
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn datetime(value: &str) -> NaiveDateTime {
+        value.parse().unwrap()
+    }
+
+    fn template(weekday: Weekday) -> ClassTemplate {
+        let now = Utc::now().naive_utc();
+        ClassTemplate {
+            id: 1,
+            discipline_id: 1,
+            weekday,
+            starts_at: NaiveTime::from_hms_opt(18, 30, 0).unwrap(),
+            duration_minutes: 60,
+            room: Some("Sala 1".to_string()),
+            trainer_id: Some(2),
+            active: true,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn session(id: i32, starts_at: &str) -> ClassSession {
+        let now = Utc::now().naive_utc();
+        let starts_at = datetime(starts_at);
+        ClassSession {
+            id,
+            template_id: Some(1),
+            discipline_id: 1,
+            scheduled_on: starts_at.date(),
+            starts_at,
+            ends_at: starts_at + chrono::Duration::minutes(60),
+            duration_minutes: 60,
+            room: None,
+            trainer_id: None,
+            status: SessionStatus::Scheduled,
+            original_starts_at: None,
+            notes: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_weekday_numbers() {
+        assert_eq!(Weekday::Monday.number(), 1);
+        assert_eq!(Weekday::Sunday.number(), 7);
+        for weekday in Weekday::ALL {
+            assert_eq!(Weekday::from_number(weekday.number()), weekday);
+        }
+        // 18/10/2026 es domingo
+        assert_eq!(Weekday::of(date("2026-10-18")), Weekday::Sunday);
+        assert_eq!(serde_json::to_string(&Weekday::Wednesday).unwrap(), r#""wednesday""#);
+    }
+
+    #[test]
+    fn test_template_occurrences() {
+        let mondays = template(Weekday::Monday).occurrences(date("2026-10-18"), date("2026-11-09"));
+        assert_eq!(mondays, vec![date("2026-10-19"), date("2026-10-26"), date("2026-11-02"), date("2026-11-09")]);
+
+        let sundays = template(Weekday::Sunday).occurrences(date("2026-10-18"), date("2026-10-24"));
+        assert_eq!(sundays, vec![date("2026-10-18")]);
+        assert!(template(Weekday::Sunday).occurrences(date("2026-10-19"), date("2026-10-24")).is_empty());
+    }
+
+    #[test]
+    fn test_regenerating_a_range_creates_nothing() {
+        let template = template(Weekday::Monday);
+        let (from, to) = (date("2026-10-18"), date("2026-11-09"));
+        let no_closures = |_: NaiveDate| false;
+
+        let (missing, skipped) = template.missing_occurrences(from, to, &[], no_closures);
+        assert_eq!(missing.len(), 4);
+        assert_eq!(skipped, 0);
+
+        // Con las clases ya generadas no queda ninguna por crear
+        let (missing, _) = template.missing_occurrences(from, to, &missing, no_closures);
+        assert!(missing.is_empty());
+
+        // Las fechas de cierre se cuentan aparte y no se generan
+        let closed = |day: NaiveDate| day == date("2026-11-02");
+        let (missing, skipped) = template.missing_occurrences(from, to, &[date("2026-10-19")], closed);
+        assert_eq!(missing, vec![date("2026-10-26"), date("2026-11-09")]);
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn test_timetable_groups_sessions_by_day() {
+        let week_start = Timetable::week_start(date("2026-10-22"));
+        assert_eq!(week_start, date("2026-10-19"));
+        assert_eq!(Timetable::week_start(date("2026-10-19")), week_start);
+
+        let timetable = Timetable::build(week_start, vec![
+            session(1, "2026-10-19T08:00:00"),
+            session(2, "2026-10-19T18:30:00"),
+            session(3, "2026-10-25T10:00:00"),
+        ]);
+        assert_eq!(timetable.week_end, date("2026-10-25"));
+        assert_eq!(timetable.days.len(), 7);
+        assert_eq!(timetable.days[0].sessions.iter().map(|s| s.id).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(timetable.days[1].sessions.is_empty());
+        assert_eq!(timetable.days[6].weekday, Weekday::Sunday);
+        assert_eq!(timetable.days[6].sessions[0].id, 3);
+    }
+
+    #[test]
+    fn test_session_attendance_rules() {
+        let mut class = session(1, "2026-10-19T18:30:00");
+        assert_eq!(class.attendance_error(1, date("2026-10-19")), None);
+        assert_eq!(class.attendance_error(2, date("2026-10-19")), Some("wrong_discipline"));
+        assert_eq!(class.attendance_error(1, date("2026-10-20")), Some("session_not_today"));
+        class.status = SessionStatus::Cancelled;
+        assert_eq!(class.attendance_error(1, date("2026-10-19")), Some("session_cancelled"));
+    }
+
+    #[test]
+    fn test_schedule_request_validation() {
+        let request: NewTemplateRequest = serde_json::from_str(
+            r#"{"discipline_id":1,"weekday":"monday","starts_at":"18:30:00","duration_minutes":60}"#).unwrap();
+        assert!(request.validate().is_ok());
+        let request: NewTemplateRequest = serde_json::from_str(
+            r#"{"discipline_id":1,"weekday":"monday","starts_at":"18:30:00","duration_minutes":0,"trainer_id":0}"#).unwrap();
+        let errors = request.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("duration_minutes"));
+        assert!(errors.field_errors().contains_key("trainer_id"));
+        assert!(serde_json::from_str::<NewTemplateRequest>(
+            r#"{"discipline_id":1,"weekday":"lunes","starts_at":"18:30:00","duration_minutes":60}"#).is_err());
+
+        let generate = GenerateSessionsRequest { from: date("2026-10-19"), to: date("2026-12-31"), discipline_id: None };
+        assert!(generate.validate_range().is_ok());
+        let generate = GenerateSessionsRequest { from: date("2026-10-19"), to: date("2027-01-31"), discipline_id: None };
+        assert!(generate.validate_range().is_err());
+        let generate = GenerateSessionsRequest { from: date("2026-10-19"), to: date("2026-10-18"), discipline_id: None };
+        assert!(generate.validate_range().is_err());
+
+        let request: MoveSessionRequest = serde_json::from_str(
+            r#"{"starts_at":"2026-10-21T19:00:00","room":"Sala 2"}"#).unwrap();
+        assert!(request.validate().is_ok());
+    }
+
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_missing_token_is_unauthorized() {
+            let app = init_app(crate::schedule::routes).await;
+
+            let req = test::TestRequest::get().uri("/schedule/timetable").to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_manage_templates() {
+            let app = init_app(crate::schedule::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/schedule/templates")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({
+                    "discipline_id": 1, "weekday": "monday", "starts_at": "18:30:00", "duration_minutes": 60}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+            let req = test::TestRequest::post()
+                .uri("/schedule/sessions/generate")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({"from": "2026-10-19", "to": "2026-10-25"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_invalid_requests_are_unprocessable() {
+            let app = init_app(crate::schedule::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/schedule/sessions/generate")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .set_json(serde_json::json!({"from": "2026-10-19", "to": "2027-03-01"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+            let req = test::TestRequest::post()
+                .uri("/schedule/sessions/1/move")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({"starts_at": "2020-01-01T10:00:00"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+}
@@ -1,9 +1,17 @@
-use sqlx::{self, mysql::MySqlArguments, MySqlPool};
-use super::models::{Subscription, NewSubscriptionRequest, SubscriptionQueryParams};
+use sqlx::{self, mysql::MySqlArguments, MySqlConnection, MySqlPool, Row};
+use super::models::{
+    Subscription, SubscriptionPeriod, SubscriptionFreeze, FreezeUsage, NewSubscriptionRequest, SubscriptionQueryParams};
 use sqlx::Arguments;
-use chrono;
+use chrono::{self, NaiveDate, NaiveDateTime};
 use crate::add_filter;
+use crate::pagination::{fetch_page, PageRequest, PageResult};
 use crate::membership::models::membership::Membership;
+use crate::cash_register::handlers::record_payment_movement;
+use crate::payments::handlers::{insert_charge, insert_payment, lock_charge, PaymentEntry};
+use crate::payments::models::payments::PaymentMethod;
+use crate::payments::models::requests::PurchasePayment;
+use crate::errors::AppError;
+use crate::closures::handlers::get_applied_occurrences;
 
 
 async fn get_by_id(
@@ -84,81 +92,175 @@ pub async fn get_subscription_by_id_handler(
 
 pub async fn get_all_subscriptions_handler(
     pool: &MySqlPool,
-) -> Result<Vec<Subscription>, sqlx::Error> {
-    let rows = sqlx::query("SELECT * FROM subscriptions")
-        .fetch_all(pool)
-        .await?;
+    page: &PageRequest,
+) -> Result<PageResult<Subscription>, sqlx::Error> {
+    fetch_page(pool, "subscriptions", || (String::new(), MySqlArguments::default()), page, Subscription::from_row).await
+}
+
+// Registra una compra: período (arranca ahora y vence según la duración del plan), cargo por el precio
+// vigente y, si se pagó en el momento, el pago
+async fn record_purchase(
+    conn: &mut MySqlConnection,
+    subscription_id: i32,
+    client_id: i32,
+    membership: &Membership,
+    payment: Option<&PurchasePayment>,
+    recorded_by: Option<i32>,
+) -> Result<(), AppError> {
+    let now = chrono::Utc::now().naive_utc();
+    let expires_at = now + chrono::Duration::days(membership.duration_days as i64);
 
-    let mut subscriptions = Vec::new();
-    for row in rows {
-        subscriptions.push(Subscription::from_row(&row));
+    let result = sqlx::query(
+        r#"
+        INSERT INTO subscription_periods
+            (subscription_id, membership_id, price, currency, classes_granted, starts_at, expires_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(subscription_id)
+    .bind(membership.id)
+    .bind(membership.price)
+    .bind(&membership.currency)
+    .bind(membership.total_classes)
+    .bind(now)
+    .bind(expires_at)
+    .execute(&mut *conn)
+    .await?;
+
+    let period_id = result.last_insert_id() as i32;
+    let charge_id = insert_charge(
+        conn, client_id, Some(period_id), &membership.name, membership.price, &membership.currency, recorded_by).await?;
+
+    if let Some(payment) = payment {
+        let charge = lock_charge(conn, charge_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        let payment = insert_payment(conn, PaymentEntry {
+            charge: &charge,
+            amount: payment.amount.unwrap_or(membership.price),
+            method: payment.method,
+            reference: payment.reference.as_deref(),
+            notes: None,
+            refund_of: None,
+            recorded_by,
+        }).await?;
+        // El pago entra en la caja dentro de la misma transacción: en efectivo, con la caja cerrada
+        // se descarta toda la compra
+        if !record_payment_movement(conn, &payment).await? && payment.method == PaymentMethod::Cash {
+            return Err(AppError::Conflict("Cash register is closed".to_string()))
+        }
     }
-    Ok(subscriptions)
+
+    Ok(())
+}
+
+// Recalcula el saldo de la suscripción a partir de sus períodos: clases sin usar de los períodos vigentes
+// y vencimiento del último. Plan y precio reflejan la compra más reciente
+pub async fn refresh_balance(
+    conn: &mut MySqlConnection,
+    subscription_id: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE subscriptions s
+        JOIN subscription_periods last ON last.id = (
+            SELECT id FROM subscription_periods
+            WHERE subscription_id = s.id
+            ORDER BY starts_at DESC, id DESC
+            LIMIT 1)
+        SET s.remaining_classes = (
+                SELECT COALESCE(SUM(classes_granted - classes_used), 0) FROM subscription_periods
+                WHERE subscription_id = s.id AND expires_at > NOW()),
+            s.expires_at = (SELECT MAX(expires_at) FROM subscription_periods WHERE subscription_id = s.id),
+            s.membership_id = last.membership_id,
+            s.price = last.price,
+            s.currency = last.currency
+        WHERE s.id = ?
+        "#,
+    )
+    .bind(subscription_id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
 }
 
 pub async fn create_subscription_handler(
     pool: &MySqlPool,
     req: &NewSubscriptionRequest,
     membership: &Membership,
-) -> Result<Subscription, sqlx::Error> {
-    let now = chrono::Utc::now().naive_utc();
-    let expires_at = now + chrono::Duration::days(membership.duration_days as i64);
-    let remaining_classes = membership.total_classes;
+    recorded_by: Option<i32>,
+) -> Result<Subscription, AppError> {
+    let mut tx = pool.begin().await?;
 
-    // 1. Insertar
     let result = sqlx::query(
         r#"
         INSERT INTO subscriptions (client_id, discipline_id, remaining_classes, expires_at, active)
-        VALUES (?, ?, ?, ?, ?)
+        VALUES (?, ?, 0, NOW(), ?)
         "#,
     )
     .bind(req.client_id)
     .bind(membership.discipline_id)
-    .bind(remaining_classes)
-    .bind(expires_at)
     .bind(true)
-    .execute(pool)
+    .execute(&mut *tx)
     .await?;
 
-    // 2. Obtener el ID recién insertado
-    let inserted_id = result.last_insert_id();
-    // 3. Buscar el registro completo
-    let subscription = get_by_id(pool, inserted_id as i32).await?;
+    let subscription_id = result.last_insert_id() as i32;
+    record_purchase(&mut tx, subscription_id, req.client_id, membership, req.payment.as_ref(), recorded_by).await?;
+    refresh_balance(&mut tx, subscription_id).await?;
+    tx.commit().await?;
 
-    Ok(subscription)
+    Ok(get_by_id(pool, subscription_id).await?)
 }
 
-pub async fn update_subscription_handler(
+// Renovación: agrega un período nuevo con las clases del plan comprado y reactiva la suscripción.
+// Las clases de compras anteriores siguen valiendo hasta su propio vencimiento
+pub async fn renew_subscription_handler(
     pool: &MySqlPool,
-    subscription_id: i32,
-    remaining_classes: i32,
-    expires_at: chrono::NaiveDateTime,
-) -> Result<Subscription, sqlx::Error> {
+    subscription: &Subscription,
+    membership: &Membership,
+    payment: Option<&PurchasePayment>,
+    recorded_by: Option<i32>,
+) -> Result<Subscription, AppError> {
+    let subscription_id = subscription.id;
+    let mut tx = pool.begin().await?;
+
+    record_purchase(&mut tx, subscription_id, subscription.client_id, membership, payment, recorded_by).await?;
     sqlx::query(
         r#"
         UPDATE subscriptions
-        SET remaining_classes = ?, expires_at = ?, updated_at = NOW(), active = ?, deleted_at = NULL
+        SET active = ?, deleted_at = NULL, updated_at = NOW()
         WHERE id = ?
         "#,
     )
-    .bind(remaining_classes)
-    .bind(expires_at)
     .bind(true)
     .bind(subscription_id)
-    .execute(pool)
+    .execute(&mut *tx)
     .await?;
+    refresh_balance(&mut tx, subscription_id).await?;
+    tx.commit().await?;
 
-    // 3. Buscar el registro completo
-    let subscription = get_by_id(pool, subscription_id).await?;
-
-    Ok(subscription)
+    Ok(get_by_id(pool, subscription_id).await?)
 }
 
-pub async fn get_subscription_by_query_params_handler(
+pub async fn get_subscription_periods(
     pool: &MySqlPool,
-    params: SubscriptionQueryParams,
-) -> Result<Vec<Subscription>, sqlx::Error> {
-    let mut query = String::from("SELECT * FROM subscriptions WHERE 1=1");
+    subscription_id: i32,
+) -> Result<Vec<SubscriptionPeriod>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM subscription_periods
+        WHERE subscription_id = ?
+        ORDER BY starts_at DESC, id DESC
+        "#,
+    )
+    .bind(subscription_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(SubscriptionPeriod::from_row).collect())
+}
+
+fn subscription_filters(params: &SubscriptionQueryParams) -> (String, MySqlArguments) {
+    let mut query = String::new();
     let mut args = MySqlArguments::default();
 
     add_filter!(query, args, &params.client_id, " AND client_id = ?");
@@ -177,45 +279,339 @@ pub async fn get_subscription_by_query_params_handler(
     add_filter!(query, args, &params.expires_at_from, " AND expires_at >= ?");
     add_filter!(query, args, &params.expires_at_to, " AND expires_at <= ?");
 
-    let rows = sqlx::query_with(&query, args)
-        .fetch_all(pool)
-        .await?;
+    (query, args)
+}
 
-    Ok(rows.iter().map(Subscription::from_row).collect())
+pub async fn get_subscription_by_query_params_handler(
+    pool: &MySqlPool,
+    params: SubscriptionQueryParams,
+    page: &PageRequest,
+) -> Result<PageResult<Subscription>, sqlx::Error> {
+    fetch_page(pool, "subscriptions", || subscription_filters(&params), page, Subscription::from_row).await
 }
 
-pub async fn delete_subscription_handler(
+// Da de baja las suscripciones activas vencidas o sin clases; la usa la tarea programada de vencimientos.
+// Antes cierra los congelamientos terminados y no toca las suscripciones que siguen congeladas
+pub async fn expire_overdue_subscriptions(
     pool: &MySqlPool,
+) -> Result<u64, sqlx::Error> {
+    end_elapsed_freezes(pool, None).await?;
+    let mut tx = pool.begin().await?;
+    let ids: Vec<i32> = sqlx::query(
+        r#"
+        SELECT s.id FROM subscriptions s
+        WHERE s.active = true AND (s.expires_at < NOW() OR s.remaining_classes <= 0)
+          AND NOT EXISTS (
+            SELECT 1 FROM subscription_freezes f WHERE f.subscription_id = s.id AND f.ended_at IS NULL)
+        FOR UPDATE
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?
+    .iter()
+    .map(|row| row.get("id"))
+    .collect();
+
+    for id in &ids {
+        deactivate_subscription(&mut tx, *id).await?;
+    }
+    tx.commit().await?;
+
+    Ok(ids.len() as u64)
+}
+
+// Da de baja la suscripción cerrando sus períodos vigentes (vencen ahora) y recalculando el saldo a partir
+// de ellos: así un refresh_balance posterior (por ejemplo, una renovación) no revive esas clases
+pub async fn deactivate_subscription(
+    conn: &mut MySqlConnection,
     id: i32,
 ) -> Result<sqlx::mysql::MySqlQueryResult, sqlx::Error> {
     let result = sqlx::query(
         r#"
         UPDATE subscriptions
-        SET deleted_at = NOW(), active = false, remaining_classes = 0
+        SET deleted_at = NOW(), active = false
         WHERE id = ?
         "#,
     )
     .bind(id)
-    .execute(pool)
+    .execute(&mut *conn)
+    .await?;
+
+    // Un congelamiento abierto termina con la baja, sin correr el vencimiento
+    let open_freeze = sqlx::query(
+        "SELECT * FROM subscription_freezes WHERE subscription_id = ? AND ended_at IS NULL FOR UPDATE")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    if let Some(row) = open_freeze {
+        let freeze = SubscriptionFreeze::from_row(&row);
+        close_freeze(conn, &freeze, chrono::Utc::now().date_naive(), None, true).await?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE subscription_periods
+        SET expires_at = NOW()
+        WHERE subscription_id = ? AND expires_at > NOW()
+        "#,
+    )
+    .bind(id)
+    .execute(&mut *conn)
     .await?;
 
+    refresh_balance(conn, id).await?;
     Ok(result)
 }
 
+pub async fn delete_subscription_handler(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<sqlx::mysql::MySqlQueryResult, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let result = deactivate_subscription(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(result)
+}
+
+// Registra la asistencia descontando del período vigente que vence primero.
+// Devuelve false si ningún período tiene clases disponibles
 pub async fn new_attendance_handler(
     pool: &MySqlPool,
     subscription_id: i32,
-) -> Result<(), sqlx::Error> {
+    session_id: Option<i32>,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let period_id: Option<i32> = sqlx::query(
+        r#"
+        SELECT id FROM subscription_periods
+        WHERE subscription_id = ? AND expires_at > NOW() AND classes_used < classes_granted
+        ORDER BY expires_at, id
+        LIMIT 1
+        FOR UPDATE
+        "#,
+    )
+    .bind(subscription_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .map(|row| row.get("id"));
+
+    let Some(period_id) = period_id else {
+        return Ok(false);
+    };
 
     sqlx::query(
         r#"
-        INSERT INTO class_attendance (subscription_id)
-        VALUES (?)
+        INSERT INTO class_attendance (subscription_id, period_id, session_id)
+        VALUES (?, ?, ?)
+        "#,
+    )
+    .bind(subscription_id)
+    .bind(period_id)
+    .bind(session_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE subscription_periods SET classes_used = classes_used + 1 WHERE id = ?")
+        .bind(period_id)
+        .execute(&mut *tx)
+        .await?;
+
+    refresh_balance(&mut tx, subscription_id).await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+
+/////////////////////////////////////////////////////////////////////////////////
+/////////////////// FREEZE HANDLERS /////////////////////////////////////////////
+/////////////////////////////////////////////////////////////////////////////////
+
+async fn get_freeze_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<SubscriptionFreeze>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM subscription_freezes WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| SubscriptionFreeze::from_row(&row)))
+}
+
+pub async fn get_open_freeze(
+    pool: &MySqlPool,
+    subscription_id: i32,
+) -> Result<Option<SubscriptionFreeze>, sqlx::Error> {
+    let row = sqlx::query("SELECT * FROM subscription_freezes WHERE subscription_id = ? AND ended_at IS NULL")
+        .bind(subscription_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| SubscriptionFreeze::from_row(&row)))
+}
+
+pub async fn get_subscription_freezes(
+    pool: &MySqlPool,
+    subscription_id: i32,
+) -> Result<Vec<SubscriptionFreeze>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM subscription_freezes
+        WHERE subscription_id = ?
+        ORDER BY starts_on DESC, id DESC
+        "#,
+    )
+    .bind(subscription_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(SubscriptionFreeze::from_row).collect())
+}
+
+// Congelamientos de la compra y días usados: los abiertos cuentan por lo planificado
+pub async fn get_freeze_usage(
+    pool: &MySqlPool,
+    period_id: i32,
+) -> Result<FreezeUsage, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT COUNT(*) AS freezes,
+               CAST(COALESCE(SUM(COALESCE(frozen_days, DATEDIFF(ends_on, starts_on) + 1)), 0) AS SIGNED) AS days
+        FROM subscription_freezes
+        WHERE period_id = ? AND NOT (ended_at IS NOT NULL AND frozen_days = 0)
+        "#,
+    )
+    .bind(period_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(FreezeUsage {
+        freezes: row.get::<i64, _>("freezes") as i32,
+        days: row.get::<i64, _>("days") as i32,
+    })
+}
+
+// Un segundo congelamiento abierto choca con el índice único de open_marker y se informa como conflicto
+pub async fn create_freeze_handler(
+    pool: &MySqlPool,
+    subscription_id: i32,
+    period_id: i32,
+    starts_on: NaiveDate,
+    ends_on: NaiveDate,
+    reason: &str,
+    approved_by: Option<i32>,
+) -> Result<SubscriptionFreeze, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO subscription_freezes (subscription_id, period_id, starts_on, ends_on, reason, approved_by)
+        VALUES (?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(subscription_id)
+    .bind(period_id)
+    .bind(starts_on)
+    .bind(ends_on)
+    .bind(reason)
+    .bind(approved_by)
     .execute(pool)
     .await?;
 
+    get_freeze_by_id(pool, result.last_insert_id() as i32).await?
+        .ok_or(sqlx::Error::RowNotFound)
+}
+
+// Cierra el congelamiento al día `date` y corre el vencimiento de los períodos que estaban vigentes
+// cuando empezó. None si ya estaba cerrado
+pub async fn end_freeze_handler(
+    pool: &MySqlPool,
+    freeze_id: i32,
+    date: NaiveDate,
+    ended_by: Option<i32>,
+) -> Result<Option<SubscriptionFreeze>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let row = sqlx::query(
+        r#"
+        SELECT f.*, s.deleted_at AS subscription_deleted_at
+        FROM subscription_freezes f
+        JOIN subscriptions s ON s.id = f.subscription_id
+        WHERE f.id = ? AND f.ended_at IS NULL
+        FOR UPDATE
+        "#)
+        .bind(freeze_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let freeze = SubscriptionFreeze::from_row(&row);
+    let deleted = row.get::<Option<NaiveDateTime>, _>("subscription_deleted_at").is_some();
+    close_freeze(&mut tx, &freeze, date, ended_by, deleted).await?;
+    tx.commit().await?;
+
+    get_freeze_by_id(pool, freeze.id).await
+}
+
+// Registra los días congelados y corre el vencimiento de las suscripciones que siguen vigentes
+async fn close_freeze(
+    conn: &mut MySqlConnection,
+    freeze: &SubscriptionFreeze,
+    date: NaiveDate,
+    ended_by: Option<i32>,
+    subscription_deleted: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE subscription_freezes SET ended_at = NOW(), ended_by = ?, frozen_days = ? WHERE id = ?")
+        .bind(ended_by)
+        .bind(freeze.days_frozen_until(date))
+        .bind(freeze.id)
+        .execute(&mut *conn)
+        .await?;
+
+    let closures = get_applied_occurrences(conn, freeze.subscription_id).await?;
+    let extension = freeze.extension_days(date, subscription_deleted, &closures);
+    if extension > 0 {
+        sqlx::query(
+            r#"
+            UPDATE subscription_periods p
+            JOIN subscriptions s ON s.id = p.subscription_id
+            SET p.expires_at = p.expires_at + INTERVAL ? DAY
+            WHERE p.subscription_id = ? AND p.expires_at > ? AND s.deleted_at IS NULL
+            "#,
+        )
+        .bind(extension)
+        .bind(freeze.subscription_id)
+        .bind(freeze.starts_on)
+        .execute(&mut *conn)
+        .await?;
+        refresh_balance(conn, freeze.subscription_id).await?;
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+// Cierra los congelamientos abiertos cuyo `ends_on` ya pasó, de una suscripción o de todas
+pub async fn end_elapsed_freezes(
+    pool: &MySqlPool,
+    subscription_id: Option<i32>,
+) -> Result<u64, sqlx::Error> {
+    let mut query = String::from("SELECT id FROM subscription_freezes WHERE ended_at IS NULL AND ends_on < ?");
+    let mut args = MySqlArguments::default();
+    let today = chrono::Utc::now().date_naive();
+    let _ = args.add(today);
+    add_filter!(query, args, &subscription_id, " AND subscription_id = ?");
+
+    let ids: Vec<i32> = sqlx::query_with(&query, args)
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(|row| row.get("id"))
+        .collect();
+
+    let mut ended = 0;
+    for id in ids {
+        if end_freeze_handler(pool, id, today, None).await?.is_some() {
+            ended += 1;
+        }
+    }
+    Ok(ended)
+}
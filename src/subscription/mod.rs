@@ -3,12 +3,19 @@ pub mod handlers;
 pub mod services;
 
 use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
 
 pub fn routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/subscriptions")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
             .service(services::new_subscription)
             .service(services::get_subscription_by_query_params)
+            .service(services::get_subscription_history)
+            .service(services::get_freezes)
+            .service(services::freeze_subscription)
+            .service(services::unfreeze_subscription)
             .service(services::get_subscription_by_id)
             .service(services::get_all_subscriptions)
             .service(services::class_attendance)
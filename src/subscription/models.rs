@@ -1,8 +1,18 @@
 use serde::{Deserialize, Serialize};
 use sqlx::{mysql::MySqlRow, MySqlPool, Row};
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
 use utoipa::ToSchema;
-use super::handlers::get_subscription_by_id_handler;
+use validator::Validate;
+use super::handlers::{deactivate_subscription, end_elapsed_freezes, get_open_freeze, get_subscription_by_id_handler};
+use crate::closures::handlers::get_closure_on;
+use crate::errors::AppError;
+use crate::membership::models::membership::Membership;
+use crate::payments::debt_policy::DebtPolicy;
+use crate::payments::handlers::get_client_debts;
+use crate::payments::models::requests::PurchasePayment;
+use crate::schedule::handlers::get_session_by_id;
+use crate::validation::validate_not_blank;
 
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -10,6 +20,11 @@ pub struct Subscription {
     pub id: i32,
     pub client_id: i32,
     pub discipline_id: i32,
+    // Plan y precio de la última compra, tal como estaban vigentes en ese momento (null en suscripciones anteriores)
+    pub membership_id: Option<i32>,
+    #[schema(value_type = Option<String>, example = "12999.99")]
+    pub price: Option<Decimal>,
+    pub currency: Option<String>,
     pub remaining_classes: i32,
     pub expires_at: NaiveDateTime,
     pub active: bool,
@@ -18,6 +33,205 @@ pub struct Subscription {
     pub deleted_at: Option<NaiveDateTime>,
 }
 
+// Una compra dentro de la suscripción; las clases de cada período vencen con su propio `expires_at`
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SubscriptionPeriod {
+    pub id: i32,
+    pub subscription_id: i32,
+    pub membership_id: Option<i32>,
+    #[schema(value_type = Option<String>, example = "12999.99")]
+    pub price: Option<Decimal>,
+    pub currency: Option<String>,
+    pub classes_granted: i32,
+    pub classes_used: i32,
+    pub remaining_classes: i32,
+    pub starts_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl SubscriptionPeriod {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        let classes_granted: i32 = row.get("classes_granted");
+        let classes_used: i32 = row.get("classes_used");
+        Self {
+            id: row.get("id"),
+            subscription_id: row.get("subscription_id"),
+            membership_id: row.get("membership_id"),
+            price: row.get("price"),
+            currency: row.get("currency"),
+            classes_granted,
+            classes_used,
+            remaining_classes: classes_granted - classes_used,
+            starts_at: row.get("starts_at"),
+            expires_at: row.get("expires_at"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+// Respuesta de /subscriptions/{id}/history: saldo actual y compras, de la más reciente a la más antigua
+#[derive(Serialize, ToSchema)]
+pub struct SubscriptionHistory {
+    pub subscription: Subscription,
+    pub periods: Vec<SubscriptionPeriod>,
+}
+
+// Congelamiento (lesión, viaje): entre `starts_on` y `ends_on` no se registran asistencias.
+// Queda abierto hasta que se descongela (a mano o al pasar `ends_on`) y `frozen_days` corre el vencimiento
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct SubscriptionFreeze {
+    pub id: i32,
+    pub subscription_id: i32,
+    // Compra sobre la que cuentan los límites del plan
+    pub period_id: Option<i32>,
+    pub starts_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub reason: String,
+    pub approved_by: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub ended_at: Option<NaiveDateTime>,
+    pub ended_by: Option<i32>,
+    pub frozen_days: Option<i32>,
+}
+
+impl SubscriptionFreeze {
+    pub fn from_row(row: &MySqlRow) -> Self {
+        Self {
+            id: row.get("id"),
+            subscription_id: row.get("subscription_id"),
+            period_id: row.get("period_id"),
+            starts_on: row.get("starts_on"),
+            ends_on: row.get("ends_on"),
+            reason: row.get("reason"),
+            approved_by: row.get("approved_by"),
+            created_at: row.get("created_at"),
+            ended_at: row.get("ended_at"),
+            ended_by: row.get("ended_by"),
+            frozen_days: row.get("frozen_days"),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    pub fn is_active_on(&self, date: NaiveDate) -> bool {
+        self.is_open() && self.starts_on <= date && date <= self.ends_on
+    }
+
+    // Días congelados si se descongela en `date`: desde `starts_on` hasta el día anterior, sin pasar de `ends_on`.
+    // Descongelar antes de que empiece equivale a cancelarlo (0 días)
+    pub fn days_frozen_until(&self, date: NaiveDate) -> i32 {
+        let end = date.min(self.ends_on + Duration::days(1));
+        (end - self.starts_on).num_days().max(0) as i32
+    }
+
+    // Días que corre el vencimiento al descongelar en `date`. Si la suscripción se dio de baja
+    // sus períodos ya están cerrados: los días quedan registrados pero no se extiende nada.
+    // Los días de `closures` (cierres ya aplicados, inicio y fin) que caen dentro del congelamiento
+    // ya corrieron el vencimiento y no se vuelven a contar
+    pub fn extension_days(
+        &self,
+        date: NaiveDate,
+        subscription_deleted: bool,
+        closures: &[(NaiveDate, NaiveDate)],
+    ) -> i32 {
+        if subscription_deleted {
+            return 0;
+        }
+        let frozen_days = self.days_frozen_until(date);
+        let last_frozen = self.starts_on + Duration::days(frozen_days as i64 - 1);
+        let closed_days: i64 = closures
+            .iter()
+            .map(|(starts_on, ends_on)| {
+                ((*ends_on).min(last_frozen) - (*starts_on).max(self.starts_on)).num_days() + 1
+            })
+            .filter(|days| *days > 0)
+            .sum();
+        (frozen_days - closed_days as i32).max(0)
+    }
+}
+
+// Congelamientos ya usados en la compra vigente; los cancelados (0 días) no cuentan
+pub struct FreezeUsage {
+    pub freezes: i32,
+    pub days: i32,
+}
+
+impl FreezeUsage {
+    // Límites del plan de la compra vigente; sin plan (suscripciones anteriores) no se puede congelar
+    pub fn check(&self, membership: Option<&Membership>, days: i32) -> Result<(), AppError> {
+        let (max_freezes, max_freeze_days) = membership
+            .map_or((0, 0), |membership| (membership.max_freezes, membership.max_freeze_days));
+        let rejection = |message: String, details: serde_json::Value| Err(AppError::Validation {
+            message,
+            details: Some(details),
+        });
+
+        if max_freezes == 0 || max_freeze_days == 0 {
+            return rejection(
+                "Membership plan does not allow freezes".to_string(),
+                serde_json::json!({ "reason": "freeze_not_allowed" }));
+        }
+        if self.freezes >= max_freezes {
+            return rejection(
+                format!("Freeze limit reached for the current period ({})", max_freezes),
+                serde_json::json!({ "reason": "max_freezes", "max_freezes": max_freezes }));
+        }
+        let remaining_days = (max_freeze_days - self.days).max(0);
+        if days > remaining_days {
+            return rejection(
+                format!("Only {} freeze days left for the current period", remaining_days),
+                serde_json::json!({ "reason": "max_freeze_days", "remaining_days": remaining_days }));
+        }
+        Ok(())
+    }
+}
+
+// Sin `starts_on` el congelamiento empieza hoy; `ends_on` es el último día congelado
+#[derive(Serialize, Deserialize, ToSchema, Validate)]
+#[schema(example = json!({"starts_on": "2026-10-20", "ends_on": "2026-11-02", "reason": "Lesión de rodilla"}))]
+pub struct FreezeRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starts_on: Option<NaiveDate>,
+    pub ends_on: NaiveDate,
+    #[validate(
+        length(min = 1, max = 255, message = "Reason must have between 1 and 255 characters"),
+        custom(function = "validate_not_blank"))]
+    #[schema(min_length = 1, max_length = 255)]
+    pub reason: String,
+}
+
+impl FreezeRequest {
+    // Fechas del congelamiento y su duración en días. No puede empezar en el pasado ni después del vencimiento
+    pub fn dates(&self, today: NaiveDate, expires_on: NaiveDate) -> Result<(NaiveDate, NaiveDate, i32), AppError> {
+        let starts_on = self.starts_on.unwrap_or(today);
+        let invalid = |message: &str, field: &str| Err(AppError::Validation {
+            message: message.to_string(),
+            details: Some(serde_json::json!({ "field": field })),
+        });
+
+        if starts_on < today {
+            return invalid("starts_on must not be in the past", "starts_on");
+        }
+        if starts_on > expires_on {
+            return invalid("starts_on must be before the subscription expires", "starts_on");
+        }
+        if self.ends_on < starts_on {
+            return invalid("ends_on must not be before starts_on", "ends_on");
+        }
+        Ok((starts_on, self.ends_on, (self.ends_on - starts_on).num_days() as i32 + 1))
+    }
+}
+
+// Respuesta de /subscriptions/{id}/unfreeze: el congelamiento cerrado y la suscripción con el vencimiento corrido
+#[derive(Serialize, ToSchema)]
+pub struct UnfreezeResponse {
+    pub freeze: SubscriptionFreeze,
+    pub subscription: Subscription,
+}
+
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct SubscriptionQueryParams {
     pub client_id: Option<i32>,
@@ -45,9 +259,12 @@ pub struct ClassAttendance {
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
-#[schema(example = json!({"subscription_id": 1}))]
+#[schema(example = json!({"subscription_id": 1, "session_id": 10}))]
 pub struct ClassAttendanceRequest {
     pub subscription_id: i32,
+    // Clase de la grilla a la que vino; tiene que ser de hoy y de la disciplina de la suscripción
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<i32>,
 }
 
 impl Subscription {
@@ -56,6 +273,9 @@ impl Subscription {
             id: row.get("id"),
             client_id: row.get("client_id"),
             discipline_id: row.get("discipline_id"),
+            membership_id: row.get("membership_id"),
+            price: row.get("price"),
+            currency: row.get("currency"),
             remaining_classes: row.get("remaining_classes"),
             expires_at: row.get("expires_at"),
             active: row.get::<i8, _>("active") != 0,
@@ -78,23 +298,15 @@ impl Subscription {
         Ok(())
     }
 
-    pub async fn expire_subscription(&self, pool: &MySqlPool) -> Result<(), String> {
-        sqlx::query(
-            r#"
-            UPDATE subscriptions
-            SET active = 0, deleted_at = NOW(), expires_at = NOW(), remaining_classes = 0
-            WHERE id = ?
-            "#,
-        )
-        .bind(self.id)
-        .execute(pool)
-        .await
-        .map_err(|e| format!("Database error expiring subscription: {}", e))?;
+    pub async fn expire_subscription(&self, pool: &MySqlPool) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        deactivate_subscription(&mut tx, self.id).await?;
+        tx.commit().await?;
 
         Ok(())
     }
 
-    async fn has_attendance_today(&self, pool: &MySqlPool) -> Result<bool, String> {
+    async fn has_attendance_today(&self, pool: &MySqlPool) -> Result<bool, AppError> {
         let today = chrono::Utc::now().naive_utc();
         let attendance_exists = sqlx::query(
             r#"
@@ -106,22 +318,28 @@ impl Subscription {
         .bind(self.id)
         .bind(today)
         .fetch_optional(pool)
-        .await
-        .map_err(|e| format!("Database error validating attendance: {}", e))?;
+        .await?;
 
         Ok(attendance_exists.is_some())
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, ToSchema)]
-#[schema(example = json!({"client_id": 1, "membership_id": 1}))]
+#[schema(example = json!({"client_id": 1, "membership_id": 1, "payment": {"method": "cash"}}))]
 pub struct NewSubscriptionRequest{
     pub client_id: i32,
     pub membership_id: i32,
+    // Pago al momento de la compra; sin pago la compra queda como cargo impago
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment: Option<PurchasePayment>,
 }
 
 impl NewSubscriptionRequest {
-    pub async fn validate(&self, pool: &MySqlPool) -> Result<(), String> {
+    pub async fn validate(&self, pool: &MySqlPool) -> Result<(), AppError> {
+        if let Some(payment) = &self.payment {
+            payment.validate()?;
+        }
+
         // Validar existencia del cliente
         let client_exists = sqlx::query(
             r#"
@@ -130,11 +348,13 @@ impl NewSubscriptionRequest {
         )
         .bind(self.client_id)
         .fetch_optional(pool)
-        .await
-        .map_err(|e| format!("Database error validating client ID: {}", e))?;
+        .await?;
 
         if client_exists.is_none() {
-            return Err("Client ID doesn't exists or not is active".to_string());
+            return Err(AppError::Validation {
+                message: "Client ID doesn't exists or not is active".to_string(),
+                details: Some(serde_json::json!({ "field": "client_id" })),
+            });
         }
 
         // Validar existencia de la membresía
@@ -145,11 +365,13 @@ impl NewSubscriptionRequest {
         )
         .bind(self.membership_id)
         .fetch_optional(pool)
-        .await
-        .map_err(|e| format!("Database error validating membership ID: {}", e))?;
+        .await?;
 
         if membership_exists.is_none() {
-            return Err("Membership ID doesn't exists or not is active".to_string());
+            return Err(AppError::Validation {
+                message: "Membership ID doesn't exists or not is active".to_string(),
+                details: Some(serde_json::json!({ "field": "membership_id" })),
+            });
         }
 
         Ok(())
@@ -157,23 +379,59 @@ impl NewSubscriptionRequest {
 }
 
 impl ClassAttendanceRequest {
-    pub async fn validate(&self, pool: &MySqlPool) -> Result<Subscription, String> {
+    // Devuelve la suscripción y, si la política de deuda lo indica, una advertencia para mostrar en recepción
+    pub async fn validate(
+        &self,
+        pool: &MySqlPool,
+        debt_policy: &DebtPolicy,
+    ) -> Result<(Subscription, Option<String>), AppError> {
+        // Los congelamientos que ya terminaron se cierran antes, para validar con el vencimiento corrido
+        end_elapsed_freezes(pool, Some(self.subscription_id)).await?;
         let subscription = get_subscription_by_id_handler(pool, self.subscription_id)
-            .await
-            .map_err(|e| format!("Error fetching subscription: {}", e))?
-            .ok_or_else(|| "Subscription ID doesn't exists".to_string())?;
+            .await?
+            .ok_or_else(|| AppError::NotFound("Subscription ID doesn't exists".to_string()))?;
 
+        let today = chrono::Utc::now().date_naive();
+        if let Some(freeze) = get_open_freeze(pool, subscription.id).await? {
+            if freeze.is_active_on(today) {
+                return Err(AppError::Validation {
+                    message: format!("Subscription is frozen until {}", freeze.ends_on),
+                    details: Some(serde_json::json!({ "reason": "frozen", "ends_on": freeze.ends_on })),
+                });
+            }
+        }
+        if let Some(closure) = get_closure_on(pool, today, subscription.discipline_id).await? {
+            return Err(AppError::Validation {
+                message: format!("Gym is closed today: {}", closure.name),
+                details: Some(serde_json::json!({ "reason": "closed", "closure_id": closure.id })),
+            });
+        }
         if let Err(e) = subscription.validate_if_active() {
-            subscription
-                .expire_subscription(pool)
-                .await
-                .map_err(|e| format!("Error expiring subscription: {}", e))?;
+            subscription.expire_subscription(pool).await?;
             tracing::error!("Subscription not valid: {}", e);
-            return Err(format!("Subscription not valid: {}", e));
+            return Err(AppError::Validation {
+                message: format!("Subscription not valid: {}", e),
+                details: Some(serde_json::json!({ "reason": e })),
+            });
         }
         if subscription.has_attendance_today(pool).await? {
-            return Err("Attendance already registered today".to_string());
+            return Err(AppError::Conflict("Attendance already registered today".to_string()));
+        }
+        if let Some(session_id) = self.session_id {
+            let session = get_session_by_id(pool, session_id).await?
+                .ok_or_else(|| AppError::Validation {
+                    message: "Class session doesn't exists".to_string(),
+                    details: Some(serde_json::json!({ "field": "session_id" })),
+                })?;
+            if let Some(reason) = session.attendance_error(subscription.discipline_id, today) {
+                return Err(AppError::Validation {
+                    message: format!("Class session not valid: {}", reason),
+                    details: Some(serde_json::json!({ "reason": reason, "session_id": session_id })),
+                });
+            }
         }
-        Ok(subscription)
+        let debts = get_client_debts(pool, subscription.client_id).await?;
+        let warning = debt_policy.evaluate(&debts, chrono::Utc::now().naive_utc())?;
+        Ok((subscription, warning))
     }
 }
\ No newline at end of file
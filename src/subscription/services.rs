@@ -1,152 +1,240 @@
-use actix_web::{get, post, web, HttpResponse};
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+use actix_web_grants::protect;
+use chrono::Utc;
 use sqlx::MySqlPool;
-use super::models::{NewSubscriptionRequest, SubscriptionQueryParams, ClassAttendanceRequest};
+use validator::Validate;
+use crate::auth::models::jwt_models::Claims;
+use crate::config::Config;
+use crate::errors::{access_denied, AppError};
+use crate::pagination::PageParams;
+use super::models::{
+    Subscription, SubscriptionHistory, NewSubscriptionRequest, SubscriptionQueryParams, ClassAttendanceRequest,
+    FreezeRequest, UnfreezeResponse};
 use crate::membership::handlers::get_membership_by_id;
 use super::handlers::{
-    get_subscription_by_client_id, update_subscription_handler,
+    get_subscription_by_client_id, renew_subscription_handler, get_subscription_periods,
     create_subscription_handler, get_subscription_by_id_handler,
     get_all_subscriptions_handler, get_subscription_by_query_params_handler,
-    new_attendance_handler};
+    new_attendance_handler, get_open_freeze, get_subscription_freezes, get_freeze_usage,
+    create_freeze_handler, end_freeze_handler, end_elapsed_freezes};
 
 #[post("/")]
+#[protect(any("Admin", "Trainer", "subscriptions:write"), error = "access_denied")]
 pub async fn new_subscription(
     pool: web::Data<MySqlPool>,
+    claims: Option<web::ReqData<Claims>>,
     req: web::Json<NewSubscriptionRequest>,
-) -> HttpResponse {
+) -> Result<HttpResponse, AppError> {
     let request = req.into_inner();
+    request.validate(&pool).await?;
+    let recorded_by = claims.map(|claims| claims.user_id as i32);
 
-    match request.validate(&pool).await {
-        Ok(_) => {
-            let membership = get_membership_by_id(&pool, request.membership_id)
-                .await
-                .unwrap()
-                .unwrap();
-            match get_subscription_by_client_id(&pool, request.client_id, membership.discipline_id).await {
-                Ok(Some(subscription)) => {
-                    let remaining_classes = subscription.remaining_classes + membership.total_classes;
-                    let now = Utc::now().naive_utc();
-                    let expires_at = now + Duration::days(membership.duration_days as i64);
-                    let updated_subscription = update_subscription_handler(
-                        &pool, subscription.id, remaining_classes, expires_at)
-                            .await
-                            .unwrap();
-                    tracing::info!("Subscription updated successfully");
-                    HttpResponse::Ok().json(updated_subscription)
-                },
-                Ok(None) => {
-                    let new_subscription = create_subscription_handler(&pool, &request, &membership).await.unwrap();
-                    tracing::info!("Subscription created successfully");
-                    HttpResponse::Created().json(new_subscription)
-                },
-                Err(e) => {
-                    tracing::error!("Error fetching subscription: {}", e);
-                    HttpResponse::InternalServerError().body("Error fetching subscription")
-                }
-            }
+    // El plan se lee con el precio vigente ahora, que queda registrado en la suscripción
+    let membership = get_membership_by_id(&pool, request.membership_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Membership not found".to_string()))?;
+    // Un pago parcial no puede superar el precio del plan
+    if let Some(amount) = request.payment.as_ref().and_then(|payment| payment.amount) {
+        if amount > membership.price {
+            return Err(AppError::Validation {
+                message: "Payment amount exceeds the membership price".to_string(),
+                details: Some(serde_json::json!({ "field": "payment.amount", "price": membership.price })),
+            });
+        }
+    }
+    // Cada compra es un período propio; si el cliente ya tiene suscripción en la disciplina se le agrega
+    match get_subscription_by_client_id(&pool, request.client_id, membership.discipline_id).await? {
+        Some(subscription) => {
+            let updated_subscription = renew_subscription_handler(
+                &pool, &subscription, &membership, request.payment.as_ref(), recorded_by).await?;
+            tracing::info!("Subscription updated successfully");
+            Ok(HttpResponse::Ok().json(updated_subscription))
         },
-        Err(e) => {
-            tracing::error!("Error creating subscription: {}", e);
-            HttpResponse::BadRequest().body(format!("Error creating subscription {}", e))
+        None => {
+            let new_subscription = create_subscription_handler(&pool, &request, &membership, recorded_by).await?;
+            tracing::info!("Subscription created successfully");
+            Ok(HttpResponse::Created().json(new_subscription))
         }
     }
 }
 
 #[get("/{id}")]
+#[protect(any("Admin", "Trainer", "subscriptions:read"), error = "access_denied")]
 pub async fn get_subscription_by_id(
     pool: web::Data<MySqlPool>,
     id: web::Path<i32>,
-) -> HttpResponse {
-    match get_subscription_by_id_handler(&pool, id.into_inner()).await {
-        Ok(Some(subscription)) => {
+) -> Result<HttpResponse, AppError> {
+    match get_subscription_by_id_handler(&pool, id.into_inner()).await? {
+        Some(subscription) => {
             tracing::info!("Subscription fetched successfully");
-            HttpResponse::Ok().json(subscription)
+            Ok(HttpResponse::Ok().json(subscription))
         },
-        Ok(None) => {
+        None => {
             tracing::info!("Subscription not found");
-            HttpResponse::NotFound().body("Subscription not found")
-        },
-        Err(e) => {
-            tracing::error!("Error fetching subscription: {}", e);
-            HttpResponse::InternalServerError().body("Error fetching subscription")
+            Err(AppError::NotFound("Subscription not found".to_string()))
         }
     }
 }
 
 #[get("/")]
+#[protect(any("Admin", "Trainer", "subscriptions:read"), error = "access_denied")]
 pub async fn get_all_subscriptions(
     pool: web::Data<MySqlPool>,
-) -> HttpResponse {
-    match get_all_subscriptions_handler(&pool).await {
-        Ok(subscriptions) => {
-            tracing::info!("Subscriptions fetched successfully");
-            HttpResponse::Ok().json(subscriptions)
-        },
-        Err(e) => {
-            tracing::error!("Error fetching subscriptions: {}", e);
-            HttpResponse::InternalServerError().body("Error fetching subscriptions")
-        }
-    }
+    http_req: HttpRequest,
+    page: web::Query<PageParams>,
+) -> Result<HttpResponse, AppError> {
+    let page = page.resolve::<Subscription>()?;
+    let subscriptions = get_all_subscriptions_handler(&pool, &page).await?;
+    tracing::info!("Subscriptions fetched successfully");
+    Ok(HttpResponse::Ok().json(subscriptions.into_page(&page, http_req.path(), http_req.query_string())))
 }
 
 #[get("/filter")]
+#[protect(any("Admin", "Trainer", "subscriptions:read"), error = "access_denied")]
 pub async fn get_subscription_by_query_params(
     pool: web::Data<MySqlPool>,
+    http_req: HttpRequest,
     query: web::Query<SubscriptionQueryParams>,
-) -> HttpResponse {
-    match get_subscription_by_query_params_handler(&pool, query.into_inner()).await {
-        Ok(subscriptions) => {
-            tracing::info!("Subscriptions fetched successfully");
-            HttpResponse::Ok().json(subscriptions)
-        },
-        Err(e) => {
-            tracing::error!("Error fetching subscriptions: {}", e);
-            HttpResponse::InternalServerError().body("Error fetching subscriptions")
-        }
-    }
+    page: web::Query<PageParams>,
+) -> Result<HttpResponse, AppError> {
+    let page = page.resolve::<Subscription>()?;
+    let subscriptions = get_subscription_by_query_params_handler(&pool, query.into_inner(), &page).await?;
+    tracing::info!("Subscriptions fetched successfully");
+    Ok(HttpResponse::Ok().json(subscriptions.into_page(&page, http_req.path(), http_req.query_string())))
 }
 
 #[post("/class_attendance")]
+#[protect(any("Admin", "Trainer", "attendance:write"), error = "access_denied")]
 pub async fn class_attendance(
     pool: web::Data<MySqlPool>,
+    config: web::Data<Config>,
     req: web::Json<ClassAttendanceRequest>,
-) -> HttpResponse {
+) -> Result<HttpResponse, AppError> {
     let request = req.into_inner();
-    match request.validate(&pool).await {
-        Ok(mut subscription) => {
-            match new_attendance_handler(&pool, subscription.id).await {
-                Ok(_) => {
-                    subscription.remaining_classes -= 1;
-                    match update_subscription_handler(
-                        &pool, subscription.id,
-                        subscription.remaining_classes,
-                        subscription.expires_at).await {
-                        Ok(_) => {
-                            tracing::info!("Class attendance recorded successfully");
-                            HttpResponse::Ok().body("Class attendance recorded successfully")
-                        },
-                        Err(e) => {
-                            tracing::error!("Error updating subscription: {}", e);
-                            HttpResponse::InternalServerError().body("Error updating subscription")
-                        }
-                    }
-                },
-                Err(e) => {
-                    tracing::error!("Error recording class attendance: {}", e);
-                    HttpResponse::InternalServerError().body("Error recording class attendance")
-                }
-            }
-        },
-        Err(e) => {
-            tracing::error!("Error validating class attendance request: {}", e);
-            HttpResponse::BadRequest().body(format!("Error validating class attendance request: {}", e))
-        }
+    let (subscription, debt_warning) = request.validate(&pool, &config.debt).await?;
+
+    if !new_attendance_handler(&pool, subscription.id, request.session_id).await? {
+        return Err(AppError::Validation {
+            message: "Subscription not valid: No remaining classes".to_string(),
+            details: Some(serde_json::json!({ "reason": "No remaining classes" })),
+        });
+    }
+    tracing::info!("Class attendance recorded successfully");
+    let mut response = HttpResponse::Ok();
+    // Con la política `warn` la asistencia se registra igual y el aviso viaja en el header Warning
+    if let Some(warning) = debt_warning {
+        tracing::warn!("Attendance for subscription {}: {}", subscription.id, warning);
+        response.insert_header(("Warning", format!("199 - \"{}\"", warning)));
+    }
+    Ok(response.body("Class attendance recorded successfully"))
+}
+
+#[get("/{id}/history")]
+#[protect(any("Admin", "Trainer", "subscriptions:read"), error = "access_denied")]
+pub async fn get_subscription_history(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let subscription_id = id.into_inner();
+    let subscription = get_subscription_by_id_handler(&pool, subscription_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Subscription not found".to_string()))?;
+    let periods = get_subscription_periods(&pool, subscription_id).await?;
+    Ok(HttpResponse::Ok().json(SubscriptionHistory { subscription, periods }))
+}
+
+async fn find_subscription(pool: &MySqlPool, id: i32) -> Result<Subscription, AppError> {
+    get_subscription_by_id_handler(pool, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Subscription not found".to_string()))
+}
+
+fn already_frozen() -> AppError {
+    AppError::Conflict("Subscription already has an open freeze".to_string())
+}
+
+#[get("/{id}/freezes")]
+#[protect(any("Admin", "Trainer", "subscriptions:read"), error = "access_denied")]
+pub async fn get_freezes(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let subscription = find_subscription(&pool, id.into_inner()).await?;
+    let freezes = get_subscription_freezes(&pool, subscription.id).await?;
+    Ok(HttpResponse::Ok().json(freezes))
+}
+
+// Congela la suscripción dentro de los límites del plan de la compra vigente; queda registrado quién lo aprobó
+#[post("/{id}/freezes")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn freeze_subscription(
+    pool: web::Data<MySqlPool>,
+    claims: Option<web::ReqData<Claims>>,
+    id: web::Path<i32>,
+    req: web::Json<FreezeRequest>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    let subscription_id = id.into_inner();
+    end_elapsed_freezes(&pool, Some(subscription_id)).await?;
+    let subscription = find_subscription(&pool, subscription_id).await?;
+    if let Err(e) = subscription.validate_if_active() {
+        return Err(AppError::Validation {
+            message: format!("Subscription not valid: {}", e),
+            details: Some(serde_json::json!({ "reason": e })),
+        });
+    }
+    let (starts_on, ends_on, days) = req.dates(Utc::now().date_naive(), subscription.expires_at.date())?;
+    if get_open_freeze(&pool, subscription_id).await?.is_some() {
+        return Err(already_frozen());
     }
+
+    // Los límites cuentan por compra: la más reciente y su plan
+    let period = get_subscription_periods(&pool, subscription_id).await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::validation("Subscription has no purchases"))?;
+    let membership = match period.membership_id {
+        Some(membership_id) => get_membership_by_id(&pool, membership_id).await?,
+        None => None,
+    };
+    get_freeze_usage(&pool, period.id).await?.check(membership.as_ref(), days)?;
+
+    let approved_by = claims.map(|claims| claims.user_id as i32);
+    let freeze = create_freeze_handler(
+        &pool, subscription_id, period.id, starts_on, ends_on, req.reason.trim(), approved_by)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_error) if db_error.is_unique_violation() => already_frozen(),
+            e => e.into(),
+        })?;
+    tracing::info!("Subscription {} frozen from {} to {} (freeze {})", subscription_id, starts_on, ends_on, freeze.id);
+    Ok(HttpResponse::Created().json(freeze))
+}
+
+// Termina el congelamiento abierto hoy y corre el vencimiento por los días que estuvo congelada
+#[post("/{id}/unfreeze")]
+#[protect(any("Admin", "Trainer"), error = "access_denied")]
+pub async fn unfreeze_subscription(
+    pool: web::Data<MySqlPool>,
+    claims: Option<web::ReqData<Claims>>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let subscription_id = id.into_inner();
+    let not_frozen = || AppError::Conflict("Subscription is not frozen".to_string());
+    let open = get_open_freeze(&pool, subscription_id).await?.ok_or_else(not_frozen)?;
+
+    let ended_by = claims.map(|claims| claims.user_id as i32);
+    let freeze = end_freeze_handler(&pool, open.id, Utc::now().date_naive(), ended_by).await?
+        .ok_or_else(not_frozen)?;
+    let subscription = find_subscription(&pool, subscription_id).await?;
+    tracing::info!("Subscription {} unfrozen after {} days", subscription_id, freeze.frozen_days.unwrap_or_default());
+    Ok(HttpResponse::Ok().json(UnfreezeResponse { freeze, subscription }))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
     use chrono::{Duration, Utc};
+    use rust_decimal::Decimal;
     use crate::subscription::models::{Subscription, NewSubscriptionRequest, ClassAttendanceRequest, SubscriptionQueryParams};
 
     // Helper function para crear una subscription de prueba
@@ -156,6 +244,9 @@ mod tests {
             id: 1,
             client_id: 1,
             discipline_id: 1,
+            membership_id: Some(1),
+            price: Some(Decimal::new(1299999, 2)),
+            currency: Some("ARS".to_string()),
             remaining_classes: 10,
             expires_at: now + Duration::days(30),
             active: true,
@@ -170,6 +261,7 @@ mod tests {
         NewSubscriptionRequest {
             client_id: 1,
             membership_id: 1,
+            payment: None,
         }
     }
 
@@ -177,6 +269,7 @@ mod tests {
     fn create_test_class_attendance_request() -> ClassAttendanceRequest {
         ClassAttendanceRequest {
             subscription_id: 1,
+            session_id: None,
         }
     }
 
@@ -196,6 +289,16 @@ mod tests {
         assert_eq!(request.membership_id, 1);
     }
 
+    #[test]
+    fn test_new_subscription_request_with_payment() {
+        let json = r#"{"client_id":1,"membership_id":1,"payment":{"method":"debit","amount":"5000"}}"#;
+        let request: NewSubscriptionRequest = serde_json::from_str(json).unwrap();
+        let payment = request.payment.unwrap();
+        assert_eq!(payment.method, crate::payments::models::payments::PaymentMethod::Debit);
+        assert_eq!(payment.amount, Some(Decimal::new(5000, 0)));
+        assert!(payment.reference.is_none());
+    }
+
     #[test]
     fn test_class_attendance_request_serialization() {
         let request = create_test_class_attendance_request();
@@ -209,6 +312,10 @@ mod tests {
         let json = r#"{"subscription_id":1}"#;
         let request: ClassAttendanceRequest = serde_json::from_str(json).unwrap();
         assert_eq!(request.subscription_id, 1);
+        assert!(request.session_id.is_none());
+
+        let request: ClassAttendanceRequest = serde_json::from_str(r#"{"subscription_id":1,"session_id":10}"#).unwrap();
+        assert_eq!(request.session_id, Some(10));
     }
 
     #[test]
@@ -236,6 +343,41 @@ mod tests {
         assert_eq!(params.active, Some(true));
     }
 
+    #[test]
+    fn test_subscription_serializes_price_paid() {
+        let subscription = create_test_subscription();
+        let json = serde_json::to_value(&subscription).unwrap();
+        assert_eq!(json["membership_id"], 1);
+        assert_eq!(json["price"], "12999.99");
+        assert_eq!(json["currency"], "ARS");
+    }
+
+    #[test]
+    fn test_subscription_history_serialization() {
+        use crate::subscription::models::{SubscriptionHistory, SubscriptionPeriod};
+        let now = Utc::now().naive_utc();
+        let period = SubscriptionPeriod {
+            id: 7,
+            subscription_id: 1,
+            membership_id: Some(1),
+            price: Some(Decimal::new(1299999, 2)),
+            currency: Some("ARS".to_string()),
+            classes_granted: 12,
+            classes_used: 2,
+            remaining_classes: 10,
+            starts_at: now,
+            expires_at: now + Duration::days(30),
+            created_at: now,
+        };
+        let history = SubscriptionHistory { subscription: create_test_subscription(), periods: vec![period] };
+        let json = serde_json::to_value(&history).unwrap();
+        assert_eq!(json["subscription"]["remaining_classes"], 10);
+        assert_eq!(json["periods"][0]["membership_id"], 1);
+        assert_eq!(json["periods"][0]["price"], "12999.99");
+        assert_eq!(json["periods"][0]["classes_granted"], 12);
+        assert_eq!(json["periods"][0]["remaining_classes"], 10);
+    }
+
     #[test]
     fn test_subscription_model_structure() {
         let subscription = create_test_subscription();
@@ -244,10 +386,149 @@ mod tests {
         assert_eq!(subscription.client_id, 1);
         assert_eq!(subscription.discipline_id, 1);
         assert_eq!(subscription.remaining_classes, 10);
-        assert_eq!(subscription.active, true);
+        assert!(subscription.active);
         assert!(subscription.deleted_at.is_none());
     }
 
+    // Tests de congelamientos: fechas, días congelados y límites del plan
+    mod freeze_tests {
+        use chrono::{NaiveDate, Utc};
+        use validator::Validate;
+        use crate::errors::AppError;
+        use crate::membership::models::membership::Membership;
+        use crate::subscription::models::{FreezeRequest, FreezeUsage, SubscriptionFreeze};
+
+        fn date(value: &str) -> NaiveDate {
+            value.parse().unwrap()
+        }
+
+        fn freeze(starts_on: &str, ends_on: &str) -> SubscriptionFreeze {
+            SubscriptionFreeze {
+                id: 1,
+                subscription_id: 1,
+                period_id: Some(1),
+                starts_on: date(starts_on),
+                ends_on: date(ends_on),
+                reason: "Lesión".to_string(),
+                approved_by: Some(1),
+                created_at: Utc::now().naive_utc(),
+                ended_at: None,
+                ended_by: None,
+                frozen_days: None,
+            }
+        }
+
+        fn plan(max_freezes: i32, max_freeze_days: i32) -> Membership {
+            let now = Utc::now().naive_utc();
+            Membership {
+                id: 1,
+                name: "Plan Básico".to_string(),
+                description: None,
+                price: rust_decimal::Decimal::new(1299999, 2),
+                currency: "ARS".to_string(),
+                discipline_id: 1,
+                total_classes: 12,
+                active: true,
+                duration_days: 30,
+                max_freezes,
+                max_freeze_days,
+                created_at: now,
+                updated_at: now,
+                deleted_at: None,
+            }
+        }
+
+        fn reason(result: Result<(), AppError>) -> String {
+            match result {
+                Err(AppError::Validation { details: Some(details), .. }) => details["reason"].as_str().unwrap().to_string(),
+                _ => panic!("expected a validation error"),
+            }
+        }
+
+        #[test]
+        fn test_freeze_is_active_between_its_dates() {
+            let mut freeze = freeze("2026-10-20", "2026-10-29");
+            assert!(!freeze.is_active_on(date("2026-10-19")));
+            assert!(freeze.is_active_on(date("2026-10-20")));
+            assert!(freeze.is_active_on(date("2026-10-29")));
+            assert!(!freeze.is_active_on(date("2026-10-30")));
+
+            freeze.ended_at = Some(Utc::now().naive_utc());
+            assert!(!freeze.is_active_on(date("2026-10-25")));
+        }
+
+        #[test]
+        fn test_days_frozen_until() {
+            let freeze = freeze("2026-10-20", "2026-10-29");
+            // Completo: del 20 al 29 inclusive
+            assert_eq!(freeze.days_frozen_until(date("2026-10-30")), 10);
+            assert_eq!(freeze.days_frozen_until(date("2026-11-15")), 10);
+            // Descongelado antes: el día en que vuelve no cuenta
+            assert_eq!(freeze.days_frozen_until(date("2026-10-23")), 3);
+            // Antes de empezar equivale a cancelarlo
+            assert_eq!(freeze.days_frozen_until(date("2026-10-20")), 0);
+            assert_eq!(freeze.days_frozen_until(date("2026-10-10")), 0);
+        }
+
+        #[test]
+        fn test_deleted_while_frozen_is_not_extended() {
+            let freeze = freeze("2026-10-20", "2026-10-29");
+            assert_eq!(freeze.extension_days(date("2026-10-23"), false, &[]), 3);
+            // La baja cierra el congelamiento: se registran los días pero el vencimiento no corre
+            assert_eq!(freeze.days_frozen_until(date("2026-10-23")), 3);
+            assert_eq!(freeze.extension_days(date("2026-10-23"), true, &[]), 0);
+        }
+
+        #[test]
+        fn test_closure_days_inside_freeze_are_not_extended_twice() {
+            let freeze = freeze("2026-10-20", "2026-10-29");
+            // Cierre del 28 al 31: el 28 y el 29 ya corrieron el vencimiento por el cierre
+            let closures = [(date("2026-10-28"), date("2026-10-31"))];
+            assert_eq!(freeze.extension_days(date("2026-10-30"), false, &closures), 8);
+            // Descongelado el 28: el cierre ya no se superpone con los días congelados
+            assert_eq!(freeze.extension_days(date("2026-10-28"), false, &closures), 8);
+            // Cierres fuera del congelamiento no cambian nada
+            let closures = [(date("2026-10-10"), date("2026-10-12")), (date("2026-11-02"), date("2026-11-02"))];
+            assert_eq!(freeze.extension_days(date("2026-10-30"), false, &closures), 10);
+        }
+
+        #[test]
+        fn test_freeze_request_dates() {
+            let today = date("2026-10-18");
+            let expires_on = date("2026-11-10");
+            let request: FreezeRequest = serde_json::from_str(
+                r#"{"ends_on":"2026-10-31","reason":"Viaje"}"#).unwrap();
+            assert!(request.validate().is_ok());
+            assert_eq!(request.dates(today, expires_on).unwrap(), (today, date("2026-10-31"), 14));
+
+            for json in [
+                r#"{"starts_on":"2026-10-17","ends_on":"2026-10-31","reason":"Viaje"}"#,
+                r#"{"starts_on":"2026-11-11","ends_on":"2026-11-20","reason":"Viaje"}"#,
+                r#"{"starts_on":"2026-10-25","ends_on":"2026-10-24","reason":"Viaje"}"#,
+            ] {
+                let request: FreezeRequest = serde_json::from_str(json).unwrap();
+                assert!(request.dates(today, expires_on).is_err());
+            }
+
+            let request: FreezeRequest = serde_json::from_str(r#"{"ends_on":"2026-10-31","reason":"  "}"#).unwrap();
+            assert!(request.validate().unwrap_err().field_errors().contains_key("reason"));
+        }
+
+        #[test]
+        fn test_freeze_limits() {
+            let unused = FreezeUsage { freezes: 0, days: 0 };
+            assert!(unused.check(Some(&plan(2, 20)), 14).is_ok());
+            assert_eq!(reason(unused.check(Some(&plan(0, 0)), 1)), "freeze_not_allowed");
+            assert_eq!(reason(unused.check(None, 1)), "freeze_not_allowed");
+            assert_eq!(reason(unused.check(Some(&plan(2, 20)), 21)), "max_freeze_days");
+
+            let used = FreezeUsage { freezes: 1, days: 14 };
+            assert!(used.check(Some(&plan(2, 20)), 6).is_ok());
+            assert_eq!(reason(used.check(Some(&plan(2, 20)), 7)), "max_freeze_days");
+            assert_eq!(reason(used.check(Some(&plan(1, 30)), 1)), "max_freezes");
+        }
+    }
+
     // Tests para verificar la lógica de respuesta HTTP
     mod http_response_tests {
         use actix_web::http::StatusCode;
@@ -263,6 +544,150 @@ mod tests {
         }
     }
 
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_missing_token_is_unauthorized() {
+            let app = init_app(crate::subscription::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/subscriptions/class_attendance")
+                .set_json(super::create_test_class_attendance_request())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn test_invalid_token_is_unauthorized() {
+            let app = init_app(crate::subscription::routes).await;
+
+            let req = test::TestRequest::get()
+                .uri("/subscriptions/")
+                .insert_header(("Authorization", "Bearer not-a-jwt"))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn test_unknown_role_is_forbidden() {
+            let app = init_app(crate::subscription::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/subscriptions/class_attendance")
+                .insert_header(bearer("Guest", TokenType::Access))
+                .set_json(super::create_test_class_attendance_request())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_passes_authorization_for_attendance() {
+            let app = init_app(crate::subscription::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/subscriptions/class_attendance")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(super::create_test_class_attendance_request())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_ne!(resp.status(), StatusCode::UNAUTHORIZED);
+            assert_ne!(resp.status(), StatusCode::FORBIDDEN);
+        }
+        #[actix_web::test]
+        async fn test_history_requires_read_permission() {
+            let app = init_app(crate::subscription::routes).await;
+
+            let req = test::TestRequest::get()
+                .uri("/subscriptions/1/history")
+                .insert_header(bearer("Guest", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+            let req = test::TestRequest::get()
+                .uri("/subscriptions/1/history")
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    // Los congelamientos quedan a nombre de un usuario: las API keys no pueden aprobarlos
+    mod freeze_authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_freeze_requires_staff() {
+            let app = init_app(crate::subscription::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/subscriptions/1/freezes")
+                .set_json(serde_json::json!({"ends_on": "2026-10-31", "reason": "Viaje"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+            let req = test::TestRequest::post()
+                .uri("/subscriptions/1/unfreeze")
+                .insert_header(bearer("Guest", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_blank_reason_is_unprocessable() {
+            let app = init_app(crate::subscription::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/subscriptions/1/freezes")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({"ends_on": "2026-10-31", "reason": " "}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+    }
+
+    // Tests del orden por cursor de suscripciones
+    mod pagination_tests {
+        use super::*;
+        use crate::pagination::{PageParams, Sortable, SortOrder};
+
+        #[test]
+        fn test_sort_values_for_cursor() {
+            let subscription = create_test_subscription();
+            assert_eq!(subscription.sort_value("remaining_classes"), "10");
+            assert_eq!(subscription.sort_value("id"), "1");
+            // Formato que MySQL compara directamente contra DATETIME
+            let expires_at = subscription.sort_value("expires_at");
+            assert_eq!(expires_at, subscription.expires_at.format("%Y-%m-%d %H:%M:%S%.f").to_string());
+        }
+
+        #[test]
+        fn test_subscription_sort_whitelist() {
+            let params = PageParams {
+                sort_by: Some("expires_at".to_string()),
+                order: Some(SortOrder::Desc),
+                ..Default::default()
+            };
+            let page = params.resolve::<Subscription>().unwrap();
+            assert_eq!(page.sort_by, "expires_at");
+            assert_eq!(page.order, SortOrder::Desc);
+
+            let params = PageParams { sort_by: Some("deleted_at".to_string()), ..Default::default() };
+            assert!(params.resolve::<Subscription>().is_err());
+        }
+    }
+
     // Tests de integración más avanzados (requieren configuración de DB de prueba)
     #[cfg(feature = "integration-tests")]
     mod integration_tests {
@@ -295,6 +720,7 @@ mod tests {
             let request = NewSubscriptionRequest {
                 client_id: 0, // ID inválido
                 membership_id: 0, // ID inválido
+                payment: None,
             };
 
             // Verificamos que los valores son los esperados para el test
@@ -306,6 +732,7 @@ mod tests {
         fn test_class_attendance_request_validation() {
             let request = ClassAttendanceRequest {
                 subscription_id: 0, // ID inválido
+                session_id: None,
             };
 
             assert_eq!(request.subscription_id, 0);
@@ -0,0 +1,90 @@
+use sqlx::mysql::MySqlQueryResult;
+use sqlx::{self, MySqlConnection, MySqlPool, Row};
+use crate::auth::models::users::{User, UserRole};
+
+
+pub async fn obtain_users(
+    pool: &MySqlPool,
+) -> Result<Vec<User>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT * FROM users
+        "#)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.iter().map(User::from_row).collect())
+}
+
+pub async fn obtain_user_by_id(
+    pool: &MySqlPool,
+    id: i32,
+) -> Result<Option<User>, sqlx::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT * FROM users WHERE id = ?
+        "#)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row.map(|row| User::from_row(&row)))
+}
+
+// Bloquea los Admin activos hasta el fin de la transacción para que dos bajas
+// simultáneas no dejen al gimnasio sin ninguno
+pub async fn lock_active_admins(
+    conn: &mut MySqlConnection,
+) -> Result<Vec<i32>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id FROM users WHERE role = 'Admin' AND active = true FOR UPDATE
+        "#)
+        .fetch_all(conn)
+        .await?;
+
+    Ok(rows.iter().map(|row| row.get("id")).collect())
+}
+
+pub async fn update_user_role(
+    conn: &mut MySqlConnection,
+    id: i32,
+    role: &UserRole,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE users SET role = ? WHERE id = ?
+        "#)
+        .bind(role.as_str())
+        .bind(id)
+        .execute(conn)
+        .await
+}
+
+pub async fn set_user_active(
+    conn: &mut MySqlConnection,
+    id: i32,
+    active: bool,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE users SET active = ? WHERE id = ?
+        "#)
+        .bind(active)
+        .bind(id)
+        .execute(conn)
+        .await
+}
+
+pub async fn delete_user(
+    conn: &mut MySqlConnection,
+    id: i32,
+) -> Result<MySqlQueryResult, sqlx::Error> {
+    sqlx::query(
+        r#"
+        DELETE FROM users WHERE id = ?
+        "#)
+        .bind(id)
+        .execute(conn)
+        .await
+}
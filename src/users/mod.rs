@@ -0,0 +1,25 @@
+pub mod models;
+pub mod handlers;
+pub mod services;
+
+use actix_web::web;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use crate::auth::middleware::auth_middleware;
+
+pub fn routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/users")
+            .wrap(HttpAuthentication::with_fn(auth_middleware))
+            .service(services::create_user)
+            .service(services::get_users)
+            .service(services::get_user_by_id)
+            .service(services::change_user_role)
+            .service(services::disable_user)
+            .service(services::enable_user)
+            .service(services::reset_user_password)
+            .service(services::unlock_user)
+            .service(services::get_user_login_attempts)
+            .service(services::reset_user_mfa)
+            .service(services::delete_user_by_id)
+    );
+}
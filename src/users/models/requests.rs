@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::auth::models::users::UserRole;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[schema(example = json!({"role": "Trainer"}))]
+pub struct UpdateRoleRequest {
+    pub role: UserRole,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+#[schema(example = json!({"password": "nueva_contraseña"}))]
+pub struct ResetPasswordRequest {
+    pub password: String,
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDateTime;
+use utoipa::ToSchema;
+use crate::auth::models::users::{User, UserRole};
+
+// Vista pública del usuario: nunca expone el hash de la contraseña
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct UserResponse {
+    pub id: i32,
+    pub username: String,
+    pub role: UserRole,
+    pub active: bool,
+    pub mfa_enabled: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<User> for UserResponse {
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            role: user.role,
+            active: user.active,
+            mfa_enabled: user.mfa_enabled,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
@@ -0,0 +1,347 @@
+use actix_web::{delete, get, patch, post, put, web, HttpResponse};
+use actix_web_grants::protect;
+use sqlx::{MySqlConnection, MySqlPool};
+use validator::Validate;
+use crate::auth::handlers::{
+    create_user_in_db, update_user_password, revoke_user_tokens,
+    clear_login_failures, get_login_attempts_by_username, disable_mfa};
+use crate::auth::models::requests::RegisterRequest;
+use crate::auth::models::users::{User, UserRole};
+use crate::config::Config;
+use crate::errors::{access_denied, AppError};
+use super::models::requests::{UpdateRoleRequest, ResetPasswordRequest};
+use super::models::responses::UserResponse;
+use super::handlers::{
+    obtain_users, obtain_user_by_id, lock_active_admins,
+    update_user_role, set_user_active, delete_user};
+
+
+async fn find_user(pool: &MySqlPool, id: i32) -> Result<User, AppError> {
+    obtain_user_by_id(pool, id).await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))
+}
+
+// Aplica la regla del último Admin dentro de la transacción que hace el cambio
+async fn ensure_not_last_admin(conn: &mut MySqlConnection, user: &User) -> Result<(), AppError> {
+    let admins = lock_active_admins(conn).await?;
+    if admins.contains(&user.id) && admins.len() <= 1 {
+        tracing::error!("Attempt to remove the last active admin: {}", user.username);
+        return Err(AppError::Conflict("Cannot remove the last active admin".to_string()))
+    }
+    Ok(())
+}
+
+async fn revoke_sessions(pool: &MySqlPool, user_id: i32) {
+    if let Err(e) = revoke_user_tokens(pool, user_id).await {
+        tracing::error!("Error revoking sessions for user {}: {}", user_id, e);
+    }
+}
+
+#[post("/")]
+#[protect("Admin", error = "access_denied")]
+pub async fn create_user(
+    pool: web::Data<MySqlPool>,
+    req: web::Json<RegisterRequest>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    req.validate()?;
+    data.password_policy.validate(&req.username, &req.password)?;
+
+    create_user_in_db(&pool, &req.username, &req.password, req.role.as_str()).await?;
+    tracing::info!("User created successfully: {}", req.username);
+    Ok(HttpResponse::Created().body("User created successfully"))
+}
+
+#[get("/")]
+#[protect("Admin", error = "access_denied")]
+pub async fn get_users(
+    pool: web::Data<MySqlPool>,
+) -> Result<HttpResponse, AppError> {
+    let users = obtain_users(&pool).await?;
+    Ok(HttpResponse::Ok().json(users.into_iter().map(UserResponse::from).collect::<Vec<_>>()))
+}
+
+#[get("/{id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn get_user_by_id(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let user = find_user(&pool, id.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(UserResponse::from(user)))
+}
+
+#[patch("/{id}/role")]
+#[protect("Admin", error = "access_denied")]
+pub async fn change_user_role(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+    req: web::Json<UpdateRoleRequest>,
+) -> Result<HttpResponse, AppError> {
+    let user = find_user(&pool, id.into_inner()).await?;
+    let user_id = user.id;
+
+    let mut tx = pool.begin().await?;
+    if req.role != UserRole::Admin {
+        ensure_not_last_admin(&mut tx, &user).await?;
+    }
+    update_user_role(&mut tx, user_id, &req.role).await?;
+    tx.commit().await?;
+    // Los tokens vigentes llevan el rol anterior
+    revoke_sessions(&pool, user_id).await;
+    tracing::info!("Role of user {} changed to {}", user_id, req.role.as_str());
+    Ok(HttpResponse::Ok().body("User role updated successfully"))
+}
+
+#[patch("/{id}/disable")]
+#[protect("Admin", error = "access_denied")]
+pub async fn disable_user(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let user = find_user(&pool, id.into_inner()).await?;
+    let user_id = user.id;
+
+    let mut tx = pool.begin().await?;
+    ensure_not_last_admin(&mut tx, &user).await?;
+    set_user_active(&mut tx, user_id, false).await?;
+    tx.commit().await?;
+    revoke_sessions(&pool, user_id).await;
+    tracing::info!("User disabled: {}", user_id);
+    Ok(HttpResponse::Ok().body("User disabled successfully"))
+}
+
+#[patch("/{id}/enable")]
+#[protect("Admin", error = "access_denied")]
+pub async fn enable_user(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let result = set_user_active(&mut *pool.acquire().await?, id.into_inner(), true).await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("User not found".to_string()))
+    }
+    tracing::info!("User enabled successfully");
+    Ok(HttpResponse::Ok().body("User enabled successfully"))
+}
+
+#[put("/{id}/password")]
+#[protect("Admin", error = "access_denied")]
+pub async fn reset_user_password(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+    req: web::Json<ResetPasswordRequest>,
+    data: web::Data<Config>,
+) -> Result<HttpResponse, AppError> {
+    let user = find_user(&pool, id.into_inner()).await?;
+    data.password_policy.validate(&user.username, &req.password)?;
+
+    update_user_password(&pool, user.id, &req.password).await?;
+    revoke_sessions(&pool, user.id).await;
+    tracing::info!("Password reset for user: {}", user.id);
+    Ok(HttpResponse::Ok().body("Password reset successfully"))
+}
+
+#[patch("/{id}/unlock")]
+#[protect("Admin", error = "access_denied")]
+pub async fn unlock_user(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let user = find_user(&pool, id.into_inner()).await?;
+
+    clear_login_failures(&pool, &user.username).await?;
+    tracing::info!("User unlocked: {}", user.username);
+    Ok(HttpResponse::Ok().body("User unlocked successfully"))
+}
+
+#[get("/{id}/login_attempts")]
+#[protect("Admin", error = "access_denied")]
+pub async fn get_user_login_attempts(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let user = find_user(&pool, id.into_inner()).await?;
+
+    let attempts = get_login_attempts_by_username(&pool, &user.username, 100).await?;
+    Ok(HttpResponse::Ok().json(attempts))
+}
+
+// Reset del segundo factor cuando el usuario pierde el dispositivo: debe volver a enrolar
+#[delete("/{id}/mfa")]
+#[protect("Admin", error = "access_denied")]
+pub async fn reset_user_mfa(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let user = find_user(&pool, id.into_inner()).await?;
+
+    disable_mfa(&pool, user.id).await?;
+    revoke_sessions(&pool, user.id).await;
+    tracing::info!("MFA reset for user: {}", user.username);
+    Ok(HttpResponse::Ok().body("MFA reset successfully"))
+}
+
+#[delete("/{id}")]
+#[protect("Admin", error = "access_denied")]
+pub async fn delete_user_by_id(
+    pool: web::Data<MySqlPool>,
+    id: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    let user = find_user(&pool, id.into_inner()).await?;
+    let user_id = user.id;
+
+    let mut tx = pool.begin().await?;
+    ensure_not_last_admin(&mut tx, &user).await?;
+    delete_user(&mut tx, user_id).await?;
+    tx.commit().await?;
+    tracing::info!("User deleted: {}", user_id);
+    Ok(HttpResponse::Ok().body("User deleted successfully"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use crate::auth::models::users::{User, UserRole};
+    use crate::users::models::requests::UpdateRoleRequest;
+    use crate::users::models::responses::UserResponse;
+
+    // Helper function para crear un usuario de prueba
+    fn create_test_user() -> User {
+        let now = Utc::now().naive_utc();
+        User {
+            id: 1,
+            username: "admin".to_string(),
+            hash: "$argon2id$secret".to_string(),
+            role: UserRole::Admin,
+            active: true,
+            mfa_secret: Some("JBSWY3DPEHPK3PXP".to_string()),
+            mfa_enabled: true,
+            mfa_last_step: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_user_response_hides_hash() {
+        let response = UserResponse::from(create_test_user());
+        let json = serde_json::to_string(&response).unwrap();
+
+        assert!(!json.contains("hash"));
+        assert!(!json.contains("argon2"));
+        assert!(!json.contains("JBSWY3DPEHPK3PXP"));
+        assert!(response.mfa_enabled);
+        assert_eq!(response.username, "admin");
+        assert_eq!(response.role, UserRole::Admin);
+    }
+
+    #[test]
+    fn test_register_request_username_validation() {
+        use validator::Validate;
+        use crate::auth::models::requests::RegisterRequest;
+
+        let request = |username: &str| RegisterRequest {
+            username: username.to_string(),
+            password: "Str0ng-Passw0rd".to_string(),
+            role: UserRole::Trainer,
+        };
+        for username in ["profe_juan", "ana.garcia", "trainer-01"] {
+            assert!(request(username).validate().is_ok(), "{}", username);
+        }
+        for username in ["", "ab", "con espacios", "juan@gym", &"x".repeat(51)] {
+            let errors = request(username).validate().unwrap_err();
+            assert!(errors.field_errors().contains_key("username"), "{}", username);
+        }
+    }
+
+    #[test]
+    fn test_update_role_request_deserialization() {
+        let json = r#"{"role":"Trainer"}"#;
+        let request: UpdateRoleRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.role, UserRole::Trainer);
+    }
+
+    mod authorization_tests {
+        use actix_web::{test, http::StatusCode};
+        use crate::auth::models::jwt_models::TokenType;
+        use crate::auth::test_utils::{bearer, init_app};
+
+        #[actix_web::test]
+        async fn test_missing_token_is_unauthorized() {
+            let app = init_app(crate::users::routes).await;
+
+            let req = test::TestRequest::get().uri("/users/").to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_create_users() {
+            let app = init_app(crate::users::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/users/")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({"username": "new", "password": "secret", "role": "Admin"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        // La validación corre antes de tocar la base: alcanza con el pool lazy
+        #[actix_web::test]
+        async fn test_invalid_user_is_unprocessable() {
+            let app = init_app(crate::users::routes).await;
+
+            let req = test::TestRequest::post()
+                .uri("/users/")
+                .insert_header(bearer("Admin", TokenType::Access))
+                .set_json(serde_json::json!({"username": "a b", "password": "Str0ng-Passw0rd", "role": "Trainer"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+            let body: serde_json::Value = test::read_body_json(resp).await;
+            assert_eq!(body["code"], "validation_error");
+            assert!(body["details"]["fields"]["username"].is_array());
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_unlock_users() {
+            let app = init_app(crate::users::routes).await;
+
+            let req = test::TestRequest::patch()
+                .uri("/users/1/unlock")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_reset_passwords() {
+            let app = init_app(crate::users::routes).await;
+
+            let req = test::TestRequest::put()
+                .uri("/users/1/password")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .set_json(serde_json::json!({"password": "hijacked"}))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+
+        #[actix_web::test]
+        async fn test_trainer_cannot_reset_mfa() {
+            let app = init_app(crate::users::routes).await;
+
+            let req = test::TestRequest::delete()
+                .uri("/users/1/mfa")
+                .insert_header(bearer("Trainer", TokenType::Access))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::borrow::Cow;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use validator::{ValidationError, ValidationErrors};
+use crate::errors::AppError;
+
+// E.164: hasta 15 dígitos incluyendo el código de país
+const E164_MAX_DIGITS: usize = 15;
+const PHONE_MIN_DIGITS: usize = 8;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PhoneConfig {
+    // Código de país para los números cargados sin prefijo internacional
+    pub default_country_code: String,
+}
+
+impl Default for PhoneConfig {
+    fn default() -> Self {
+        Self { default_country_code: "54".to_string() }
+    }
+}
+
+// Normaliza un teléfono a E.164 (+5491112345678). Acepta espacios, guiones, puntos y paréntesis;
+// "00" equivale a "+" y sin prefijo internacional se descarta el 0 troncal y se agrega el código de país
+pub fn normalize_phone(raw: &str, default_country_code: &str) -> Option<String> {
+    let compact: String = raw.trim().chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+        .collect();
+
+    let digits = if let Some(international) = compact.strip_prefix('+') {
+        international.to_string()
+    } else if let Some(international) = compact.strip_prefix("00") {
+        international.to_string()
+    } else {
+        format!("{}{}", default_country_code, compact.trim_start_matches('0'))
+    };
+
+    let valid = digits.chars().all(|c| c.is_ascii_digit())
+        && !digits.starts_with('0')
+        && (PHONE_MIN_DIGITS..=E164_MAX_DIGITS).contains(&digits.len());
+    valid.then(|| format!("+{}", digits))
+}
+
+fn error(code: &'static str, message: &'static str) -> ValidationError {
+    ValidationError::new(code).with_message(Cow::Borrowed(message))
+}
+
+// Validaciones custom para `#[validate(custom(function = ...))]`
+
+pub fn validate_phone(phone: &str) -> Result<(), ValidationError> {
+    // El código de país no cambia la estructura del número, solo su largo final
+    match normalize_phone(phone, "") {
+        Some(_) => Ok(()),
+        None => Err(error("phone", "Phone must be a valid number (E.164)")),
+    }
+}
+
+pub fn validate_not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(error("blank", "Must not be blank"));
+    }
+    Ok(())
+}
+
+pub fn validate_document(document: &str) -> Result<(), ValidationError> {
+    let valid = document.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '.' | '-'))
+        && (6..=20).contains(&normalize_document(document).len());
+    if !valid {
+        return Err(error("document_number", "Document number must have between 6 and 20 letters or digits"));
+    }
+    Ok(())
+}
+
+// "30.123.456" -> "30123456"
+pub fn normalize_document(document: &str) -> String {
+    document.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+pub fn validate_username(username: &str) -> Result<(), ValidationError> {
+    if !username.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-')) {
+        return Err(error("username", "Username may only contain letters, digits, '.', '_' and '-'"));
+    }
+    Ok(())
+}
+
+// Errores por campo: {"fields": {"age": ["Age must be between 1 and 120"]}}
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut fields = Map::new();
+        for (field, field_errors) in errors.field_errors() {
+            let messages = field_errors.iter()
+                .map(|e| Value::String(e.message.as_ref().map_or_else(|| e.code.to_string(), |m| m.to_string())))
+                .collect();
+            fields.insert(field.to_string(), Value::Array(messages));
+        }
+        AppError::Validation {
+            message: "Invalid request".to_string(),
+            details: Some(serde_json::json!({ "fields": fields })),
+        }
+    }
+}